@@ -0,0 +1,24 @@
+use P80::digraph_converters;
+use P80::graph_converters::labeled;
+
+pub fn main() {
+    let g = labeled::from_string("[k, m-p/5, m-q/7, p-q/9]");
+    println!(
+        "labeled graph (adjacency matrix)\n{:?}",
+        labeled::to_adjacency_matrix(&g)
+    );
+    println!(
+        "labeled graph (laplacian matrix)\n{:?}",
+        labeled::to_laplacian_matrix(&g)
+    );
+
+    let g = digraph_converters::unlabeled::from_string("[s>r, t, u>r, s>u, u>s, v>u]");
+    println!(
+        "unlabeled digraph (sparse adjacency matrix)\n{:?}",
+        digraph_converters::unlabeled::to_sparse_adjacency_matrix(&g)
+    );
+    println!(
+        "unlabeled digraph (incidence matrix)\n{:?}",
+        digraph_converters::unlabeled::to_incidence_matrix(&g)
+    );
+}
@@ -1,4 +1,5 @@
 pub mod unlabeled {
+    use crate::matrix;
     use graph::Digraph;
     use std::collections::HashSet;
 
@@ -102,6 +103,76 @@ pub mod unlabeled {
         }
         g
     }
+
+    /// Returns the sorted node values and the adjacency matrix;
+    /// `m[i][j]` is 1 if there is an arc from `nodes[i]` to `nodes[j]`.
+    pub fn to_adjacency_matrix(g: &Digraph<char>) -> (Vec<char>, Vec<Vec<i32>>) {
+        let (nodes, edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        let m = matrix::adjacency(&nodes, &with_unit_weight(&edges), true);
+        (nodes, m)
+    }
+
+    pub fn from_adjacency_matrix(nodes: &[char], m: &[Vec<i32>]) -> Digraph<char> {
+        let edges = matrix::edges_from_adjacency(nodes, m, true);
+        from_term_form(&nodes.to_vec(), &without_weight(&edges))
+    }
+
+    /// Returns the sorted node values and the non-zero entries `(i, j, m[i][j])`
+    /// of the adjacency matrix in row-major order.
+    pub fn to_sparse_adjacency_matrix(g: &Digraph<char>) -> (Vec<char>, Vec<(usize, usize, i32)>) {
+        let (nodes, edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        let entries = matrix::sparse_adjacency(&nodes, &with_unit_weight(&edges), true);
+        (nodes, entries)
+    }
+
+    pub fn from_sparse_adjacency_matrix(
+        nodes: &[char],
+        entries: &[(usize, usize, i32)],
+    ) -> Digraph<char> {
+        let edges = matrix::edges_from_sparse(nodes, entries, true);
+        from_term_form(&nodes.to_vec(), &without_weight(&edges))
+    }
+
+    /// Returns the sorted node values, the sorted arcs and the incidence matrix
+    /// (nodes x arcs); a column has -1 at the source and 1 at the target of each arc,
+    /// or 2 at the node of a self-loop.
+    #[allow(clippy::type_complexity)]
+    pub fn to_incidence_matrix(g: &Digraph<char>) -> (Vec<char>, Vec<(char, char)>, Vec<Vec<i32>>) {
+        let (nodes, mut edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        edges.sort();
+        let m = matrix::incidence(&nodes, &edges, true);
+        (nodes, edges, m)
+    }
+
+    pub fn from_incidence_matrix(nodes: &[char], m: &[Vec<i32>]) -> Digraph<char> {
+        let edges = matrix::edges_from_incidence(nodes, m, true);
+        from_term_form(&nodes.to_vec(), &edges)
+    }
+
+    /// Returns the sorted node values and the laplacian matrix
+    /// `D - A` where `D` is the out-degree matrix and `A` is the adjacency matrix.
+    pub fn to_laplacian_matrix(g: &Digraph<char>) -> (Vec<char>, Vec<Vec<i32>>) {
+        let (nodes, edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        let m = matrix::laplacian(&nodes, &with_unit_weight(&edges), true);
+        (nodes, m)
+    }
+
+    pub fn from_laplacian_matrix(nodes: &[char], m: &[Vec<i32>]) -> Digraph<char> {
+        let edges = matrix::edges_from_laplacian(nodes, m, true);
+        from_term_form(&nodes.to_vec(), &without_weight(&edges))
+    }
+
+    fn with_unit_weight(edges: &[(char, char)]) -> Vec<(char, char, i32)> {
+        edges.iter().map(|(n1, n2)| (*n1, *n2, 1)).collect()
+    }
+
+    fn without_weight(edges: &[(char, char, i32)]) -> Vec<(char, char)> {
+        edges.iter().map(|(n1, n2, _)| (*n1, *n2)).collect()
+    }
 }
 
 #[cfg(test)]
@@ -163,6 +234,44 @@ mod test_unlabeled {
         check_graph(&g);
     }
 
+    #[test]
+    fn test_to_adjacency_matrix() {
+        let (nodes, m) = to_adjacency_matrix(&make_test_graph());
+        assert_eq!(nodes, vec!['r', 's', 't', 'u', 'v']);
+        assert_eq!(
+            m,
+            vec![
+                vec![0, 0, 0, 0, 0],
+                vec![1, 0, 0, 1, 0],
+                vec![0, 0, 0, 0, 0],
+                vec![1, 1, 0, 0, 0],
+                vec![0, 0, 0, 1, 0],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matrix_forms() {
+        let (nodes, m) = to_adjacency_matrix(&make_test_graph());
+        check_graph(&from_adjacency_matrix(&nodes, &m));
+        let (nodes, entries) = to_sparse_adjacency_matrix(&make_test_graph());
+        check_graph(&from_sparse_adjacency_matrix(&nodes, &entries));
+        let (nodes, _, m) = to_incidence_matrix(&make_test_graph());
+        check_graph(&from_incidence_matrix(&nodes, &m));
+        let (nodes, m) = to_laplacian_matrix(&make_test_graph());
+        check_graph(&from_laplacian_matrix(&nodes, &m));
+    }
+
+    #[test]
+    fn test_incidence_matrix_self_loop() {
+        let g = from_string("[a>b, b>b]");
+        let (nodes, edges, m) = to_incidence_matrix(&g);
+        assert_eq!(edges, vec![('a', 'b'), ('b', 'b')]);
+        assert_eq!(m, vec![vec![-1, 0], vec![1, 2]]);
+        let g2 = from_incidence_matrix(&nodes, &m);
+        assert_eq!(to_incidence_matrix(&g2), (nodes, edges, m));
+    }
+
     fn check_graph(g: &Digraph<char>) {
         assert_eq!(g.size(), 5);
         assert!(has_same_elements(
@@ -204,6 +313,7 @@ mod test_unlabeled {
 }
 
 pub mod labeled {
+    use crate::matrix;
     use graph::LabeledDigraph;
     use std::collections::HashSet;
 
@@ -327,6 +437,89 @@ pub mod labeled {
         }
         g
     }
+
+    /// Returns the sorted node values and the weighted adjacency matrix;
+    /// `m[i][j]` is the label of the arc from `nodes[i]` to `nodes[j]` (labels of parallel arcs are summed up).
+    /// Note that arcs labeled with 0 cannot be distinguished from missing arcs.
+    pub fn to_adjacency_matrix(g: &LabeledDigraph<char, i32>) -> (Vec<char>, Vec<Vec<i32>>) {
+        let (nodes, edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        let m = matrix::adjacency(&nodes, &edges, true);
+        (nodes, m)
+    }
+
+    pub fn from_adjacency_matrix(nodes: &[char], m: &[Vec<i32>]) -> LabeledDigraph<char, i32> {
+        let edges = matrix::edges_from_adjacency(nodes, m, true);
+        from_term_form(&nodes.to_vec(), &edges)
+    }
+
+    /// Returns the sorted node values and the non-zero entries `(i, j, m[i][j])`
+    /// of the weighted adjacency matrix in row-major order.
+    pub fn to_sparse_adjacency_matrix(
+        g: &LabeledDigraph<char, i32>,
+    ) -> (Vec<char>, Vec<(usize, usize, i32)>) {
+        let (nodes, edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        let entries = matrix::sparse_adjacency(&nodes, &edges, true);
+        (nodes, entries)
+    }
+
+    pub fn from_sparse_adjacency_matrix(
+        nodes: &[char],
+        entries: &[(usize, usize, i32)],
+    ) -> LabeledDigraph<char, i32> {
+        let edges = matrix::edges_from_sparse(nodes, entries, true);
+        from_term_form(&nodes.to_vec(), &edges)
+    }
+
+    /// Returns the sorted node values, the sorted arcs and the incidence matrix
+    /// (nodes x arcs); a column has -1 at the source and 1 at the target of each arc,
+    /// or 2 at the node of a self-loop.
+    /// Labels are kept in the arc list, not in the matrix.
+    #[allow(clippy::type_complexity)]
+    pub fn to_incidence_matrix(
+        g: &LabeledDigraph<char, i32>,
+    ) -> (Vec<char>, Vec<(char, char, i32)>, Vec<Vec<i32>>) {
+        let (nodes, mut edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        edges.sort();
+        let pairs: Vec<(char, char)> = edges.iter().map(|(n1, n2, _)| (*n1, *n2)).collect();
+        let m = matrix::incidence(&nodes, &pairs, true);
+        (nodes, edges, m)
+    }
+
+    /// Constructs a graph from an incidence matrix; `labels[k]` is the label of
+    /// the arc in the k-th column.
+    pub fn from_incidence_matrix(
+        nodes: &[char],
+        labels: &[i32],
+        m: &[Vec<i32>],
+    ) -> LabeledDigraph<char, i32> {
+        let pairs = matrix::edges_from_incidence(nodes, m, true);
+        if pairs.len() != labels.len() {
+            panic!("Invalid labels: must have {} labels", pairs.len());
+        }
+        let edges: Vec<(char, char, i32)> = pairs
+            .iter()
+            .zip(labels.iter())
+            .map(|((n1, n2), l)| (*n1, *n2, *l))
+            .collect();
+        from_term_form(&nodes.to_vec(), &edges)
+    }
+
+    /// Returns the sorted node values and the laplacian matrix
+    /// `D - A` where `D` is the weighted out-degree matrix and `A` is the weighted adjacency matrix.
+    pub fn to_laplacian_matrix(g: &LabeledDigraph<char, i32>) -> (Vec<char>, Vec<Vec<i32>>) {
+        let (nodes, edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        let m = matrix::laplacian(&nodes, &edges, true);
+        (nodes, m)
+    }
+
+    pub fn from_laplacian_matrix(nodes: &[char], m: &[Vec<i32>]) -> LabeledDigraph<char, i32> {
+        let edges = matrix::edges_from_laplacian(nodes, m, true);
+        from_term_form(&nodes.to_vec(), &edges)
+    }
 }
 
 #[cfg(test)]
@@ -385,6 +578,35 @@ mod test_labeled {
         check_graph(&g);
     }
 
+    #[test]
+    fn test_to_incidence_matrix() {
+        let (nodes, edges, m) = to_incidence_matrix(&make_test_graph());
+        assert_eq!(nodes, vec!['k', 'm', 'p', 'q']);
+        assert_eq!(edges, vec![('m', 'q', 7), ('p', 'm', 5), ('p', 'q', 9)]);
+        assert_eq!(
+            m,
+            vec![
+                vec![0, 0, 0],
+                vec![-1, 1, 0],
+                vec![0, -1, -1],
+                vec![1, 0, 1],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matrix_forms() {
+        let (nodes, m) = to_adjacency_matrix(&make_test_graph());
+        check_graph(&from_adjacency_matrix(&nodes, &m));
+        let (nodes, entries) = to_sparse_adjacency_matrix(&make_test_graph());
+        check_graph(&from_sparse_adjacency_matrix(&nodes, &entries));
+        let (nodes, edges, m) = to_incidence_matrix(&make_test_graph());
+        let labels: Vec<i32> = edges.iter().map(|(_, _, l)| *l).collect();
+        check_graph(&from_incidence_matrix(&nodes, &labels, &m));
+        let (nodes, m) = to_laplacian_matrix(&make_test_graph());
+        check_graph(&from_laplacian_matrix(&nodes, &m));
+    }
+
     fn check_graph(g: &LabeledDigraph<char, i32>) {
         assert_eq!(g.size(), 4);
         assert!(has_same_elements(
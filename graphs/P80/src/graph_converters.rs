@@ -1,4 +1,5 @@
 pub mod unlabeled {
+    use crate::matrix;
     use graph::Graph;
     use std::collections::HashSet;
 
@@ -121,6 +122,76 @@ pub mod unlabeled {
         }
         g
     }
+
+    /// Returns the sorted node values and the adjacency matrix;
+    /// `m[i][j]` is 1 if `nodes[i]` and `nodes[j]` are linked.
+    pub fn to_adjacency_matrix(g: &Graph<char>) -> (Vec<char>, Vec<Vec<i32>>) {
+        let (nodes, edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        let m = matrix::adjacency(&nodes, &with_unit_weight(&edges), false);
+        (nodes, m)
+    }
+
+    pub fn from_adjacency_matrix(nodes: &[char], m: &[Vec<i32>]) -> Graph<char> {
+        let edges = matrix::edges_from_adjacency(nodes, m, false);
+        from_term_form(&nodes.to_vec(), &without_weight(&edges))
+    }
+
+    /// Returns the sorted node values and the non-zero entries `(i, j, m[i][j])`
+    /// of the adjacency matrix in row-major order.
+    pub fn to_sparse_adjacency_matrix(g: &Graph<char>) -> (Vec<char>, Vec<(usize, usize, i32)>) {
+        let (nodes, edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        let entries = matrix::sparse_adjacency(&nodes, &with_unit_weight(&edges), false);
+        (nodes, entries)
+    }
+
+    pub fn from_sparse_adjacency_matrix(
+        nodes: &[char],
+        entries: &[(usize, usize, i32)],
+    ) -> Graph<char> {
+        let edges = matrix::edges_from_sparse(nodes, entries, false);
+        from_term_form(&nodes.to_vec(), &without_weight(&edges))
+    }
+
+    /// Returns the sorted node values, the sorted edges and the incidence matrix
+    /// (nodes x edges); a column has 1 at both ends of each edge, or 2 at the node
+    /// of a self-loop.
+    #[allow(clippy::type_complexity)]
+    pub fn to_incidence_matrix(g: &Graph<char>) -> (Vec<char>, Vec<(char, char)>, Vec<Vec<i32>>) {
+        let (nodes, mut edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        edges.sort();
+        let m = matrix::incidence(&nodes, &edges, false);
+        (nodes, edges, m)
+    }
+
+    pub fn from_incidence_matrix(nodes: &[char], m: &[Vec<i32>]) -> Graph<char> {
+        let edges = matrix::edges_from_incidence(nodes, m, false);
+        from_term_form(&nodes.to_vec(), &edges)
+    }
+
+    /// Returns the sorted node values and the laplacian matrix
+    /// `D - A` where `D` is the degree matrix and `A` is the adjacency matrix.
+    pub fn to_laplacian_matrix(g: &Graph<char>) -> (Vec<char>, Vec<Vec<i32>>) {
+        let (nodes, edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        let m = matrix::laplacian(&nodes, &with_unit_weight(&edges), false);
+        (nodes, m)
+    }
+
+    pub fn from_laplacian_matrix(nodes: &[char], m: &[Vec<i32>]) -> Graph<char> {
+        let edges = matrix::edges_from_laplacian(nodes, m, false);
+        from_term_form(&nodes.to_vec(), &without_weight(&edges))
+    }
+
+    fn with_unit_weight(edges: &[(char, char)]) -> Vec<(char, char, i32)> {
+        edges.iter().map(|(n1, n2)| (*n1, *n2, 1)).collect()
+    }
+
+    fn without_weight(edges: &[(char, char, i32)]) -> Vec<(char, char)> {
+        edges.iter().map(|(n1, n2, _)| (*n1, *n2)).collect()
+    }
 }
 
 #[cfg(test)]
@@ -191,6 +262,36 @@ mod tests_unlabeled {
         check_graph(&g);
     }
 
+    #[test]
+    fn test_to_adjacency_matrix() {
+        let (nodes, m) = to_adjacency_matrix(&make_test_graph());
+        assert_eq!(nodes, vec!['b', 'c', 'd', 'f', 'g', 'h', 'k']);
+        assert_eq!(
+            m,
+            vec![
+                vec![0, 1, 0, 1, 0, 0, 0],
+                vec![1, 0, 0, 1, 0, 0, 0],
+                vec![0, 0, 0, 0, 0, 0, 0],
+                vec![1, 1, 0, 0, 0, 0, 1],
+                vec![0, 0, 0, 0, 0, 1, 0],
+                vec![0, 0, 0, 0, 1, 0, 0],
+                vec![0, 0, 0, 1, 0, 0, 0],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matrix_forms() {
+        let (nodes, m) = to_adjacency_matrix(&make_test_graph());
+        check_graph(&from_adjacency_matrix(&nodes, &m));
+        let (nodes, entries) = to_sparse_adjacency_matrix(&make_test_graph());
+        check_graph(&from_sparse_adjacency_matrix(&nodes, &entries));
+        let (nodes, _, m) = to_incidence_matrix(&make_test_graph());
+        check_graph(&from_incidence_matrix(&nodes, &m));
+        let (nodes, m) = to_laplacian_matrix(&make_test_graph());
+        check_graph(&from_laplacian_matrix(&nodes, &m));
+    }
+
     fn check_graph(g: &Graph<char>) {
         assert_eq!(g.size(), 7);
         assert!(has_same_elements(
@@ -242,6 +343,7 @@ mod tests_unlabeled {
 }
 
 pub mod labeled {
+    use crate::matrix;
     use graph::LabeledGraph;
     use std::collections::HashSet;
 
@@ -381,6 +483,89 @@ pub mod labeled {
         }
         g
     }
+
+    /// Returns the sorted node values and the weighted adjacency matrix;
+    /// `m[i][j]` is the label of the edge between `nodes[i]` and `nodes[j]` (labels of parallel edges are summed up).
+    /// Note that edges labeled with 0 cannot be distinguished from missing edges.
+    pub fn to_adjacency_matrix(g: &LabeledGraph<char, i32>) -> (Vec<char>, Vec<Vec<i32>>) {
+        let (nodes, edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        let m = matrix::adjacency(&nodes, &edges, false);
+        (nodes, m)
+    }
+
+    pub fn from_adjacency_matrix(nodes: &[char], m: &[Vec<i32>]) -> LabeledGraph<char, i32> {
+        let edges = matrix::edges_from_adjacency(nodes, m, false);
+        from_term_form(&nodes.to_vec(), &edges)
+    }
+
+    /// Returns the sorted node values and the non-zero entries `(i, j, m[i][j])`
+    /// of the weighted adjacency matrix in row-major order.
+    pub fn to_sparse_adjacency_matrix(
+        g: &LabeledGraph<char, i32>,
+    ) -> (Vec<char>, Vec<(usize, usize, i32)>) {
+        let (nodes, edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        let entries = matrix::sparse_adjacency(&nodes, &edges, false);
+        (nodes, entries)
+    }
+
+    pub fn from_sparse_adjacency_matrix(
+        nodes: &[char],
+        entries: &[(usize, usize, i32)],
+    ) -> LabeledGraph<char, i32> {
+        let edges = matrix::edges_from_sparse(nodes, entries, false);
+        from_term_form(&nodes.to_vec(), &edges)
+    }
+
+    /// Returns the sorted node values, the sorted edges and the incidence matrix
+    /// (nodes x edges); a column has 1 at both ends of each edge, or 2 at the node
+    /// of a self-loop.
+    /// Labels are kept in the edge list, not in the matrix.
+    #[allow(clippy::type_complexity)]
+    pub fn to_incidence_matrix(
+        g: &LabeledGraph<char, i32>,
+    ) -> (Vec<char>, Vec<(char, char, i32)>, Vec<Vec<i32>>) {
+        let (nodes, mut edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        edges.sort();
+        let pairs: Vec<(char, char)> = edges.iter().map(|(n1, n2, _)| (*n1, *n2)).collect();
+        let m = matrix::incidence(&nodes, &pairs, false);
+        (nodes, edges, m)
+    }
+
+    /// Constructs a graph from an incidence matrix; `labels[k]` is the label of
+    /// the edge in the k-th column.
+    pub fn from_incidence_matrix(
+        nodes: &[char],
+        labels: &[i32],
+        m: &[Vec<i32>],
+    ) -> LabeledGraph<char, i32> {
+        let pairs = matrix::edges_from_incidence(nodes, m, false);
+        if pairs.len() != labels.len() {
+            panic!("Invalid labels: must have {} labels", pairs.len());
+        }
+        let edges: Vec<(char, char, i32)> = pairs
+            .iter()
+            .zip(labels.iter())
+            .map(|((n1, n2), l)| (*n1, *n2, *l))
+            .collect();
+        from_term_form(&nodes.to_vec(), &edges)
+    }
+
+    /// Returns the sorted node values and the laplacian matrix
+    /// `D - A` where `D` is the weighted degree matrix and `A` is the weighted adjacency matrix.
+    pub fn to_laplacian_matrix(g: &LabeledGraph<char, i32>) -> (Vec<char>, Vec<Vec<i32>>) {
+        let (nodes, edges) = to_term_form(g);
+        let nodes = matrix::sorted(&nodes);
+        let m = matrix::laplacian(&nodes, &edges, false);
+        (nodes, m)
+    }
+
+    pub fn from_laplacian_matrix(nodes: &[char], m: &[Vec<i32>]) -> LabeledGraph<char, i32> {
+        let edges = matrix::edges_from_laplacian(nodes, m, false);
+        from_term_form(&nodes.to_vec(), &edges)
+    }
 }
 
 #[cfg(test)]
@@ -439,6 +624,34 @@ mod tests_labeled {
         check_graph(&g);
     }
 
+    #[test]
+    fn test_to_laplacian_matrix() {
+        let (nodes, m) = to_laplacian_matrix(&make_test_graph());
+        assert_eq!(nodes, vec!['k', 'm', 'p', 'q']);
+        assert_eq!(
+            m,
+            vec![
+                vec![0, 0, 0, 0],
+                vec![0, 12, -5, -7],
+                vec![0, -5, 14, -9],
+                vec![0, -7, -9, 16],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_matrix_forms() {
+        let (nodes, m) = to_adjacency_matrix(&make_test_graph());
+        check_graph(&from_adjacency_matrix(&nodes, &m));
+        let (nodes, entries) = to_sparse_adjacency_matrix(&make_test_graph());
+        check_graph(&from_sparse_adjacency_matrix(&nodes, &entries));
+        let (nodes, edges, m) = to_incidence_matrix(&make_test_graph());
+        let labels: Vec<i32> = edges.iter().map(|(_, _, l)| *l).collect();
+        check_graph(&from_incidence_matrix(&nodes, &labels, &m));
+        let (nodes, m) = to_laplacian_matrix(&make_test_graph());
+        check_graph(&from_laplacian_matrix(&nodes, &m));
+    }
+
     fn check_graph(g: &LabeledGraph<char, i32>) {
         assert_eq!(g.size(), 4);
         assert!(has_same_elements(
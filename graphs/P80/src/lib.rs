@@ -1,2 +1,3 @@
 pub mod digraph_converters;
 pub mod graph_converters;
+mod matrix;
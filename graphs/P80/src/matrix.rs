@@ -0,0 +1,234 @@
+// Common logic for the matrix forms of graphs and digraphs.
+// Rows and columns are ordered by the sorted node values so that the same
+// graph always produces the same matrix.
+use std::collections::BTreeMap;
+
+pub fn sorted(nodes: &[char]) -> Vec<char> {
+    let mut nodes = nodes.to_vec();
+    nodes.sort();
+    nodes.dedup();
+    nodes
+}
+
+fn index_of(nodes: &[char], v: char) -> usize {
+    nodes.binary_search(&v).unwrap()
+}
+
+pub fn adjacency(nodes: &[char], edges: &[(char, char, i32)], directed: bool) -> Vec<Vec<i32>> {
+    let mut matrix = vec![vec![0; nodes.len()]; nodes.len()];
+    for (i, j, w) in sparse_adjacency(nodes, edges, directed) {
+        matrix[i][j] = w;
+    }
+    matrix
+}
+
+pub fn sparse_adjacency(
+    nodes: &[char],
+    edges: &[(char, char, i32)],
+    directed: bool,
+) -> Vec<(usize, usize, i32)> {
+    // BTreeMap keeps entries in row-major order
+    let mut entries = BTreeMap::<(usize, usize), i32>::new();
+    for (v1, v2, w) in edges {
+        let (i, j) = (index_of(nodes, *v1), index_of(nodes, *v2));
+        *entries.entry((i, j)).or_insert(0) += w;
+        if !directed && i != j {
+            *entries.entry((j, i)).or_insert(0) += w;
+        }
+    }
+    entries.into_iter().map(|((i, j), w)| (i, j, w)).collect()
+}
+
+// A self-loop has 2 in the row of its node, also in digraphs where -1 and 1
+// would cancel out.
+pub fn incidence(nodes: &[char], edges: &[(char, char)], directed: bool) -> Vec<Vec<i32>> {
+    let mut matrix = vec![vec![0; edges.len()]; nodes.len()];
+    for (k, (v1, v2)) in edges.iter().enumerate() {
+        let (i, j) = (index_of(nodes, *v1), index_of(nodes, *v2));
+        if directed && i != j {
+            matrix[i][k] -= 1;
+            matrix[j][k] += 1;
+        } else {
+            matrix[i][k] += 1;
+            matrix[j][k] += 1;
+        }
+    }
+    matrix
+}
+
+pub fn laplacian(nodes: &[char], edges: &[(char, char, i32)], directed: bool) -> Vec<Vec<i32>> {
+    let mut matrix = vec![vec![0; nodes.len()]; nodes.len()];
+    for (i, j, w) in sparse_adjacency(nodes, edges, directed) {
+        // self-loops do not contribute to the laplacian
+        if i != j {
+            matrix[i][j] -= w;
+            matrix[i][i] += w;
+        }
+    }
+    matrix
+}
+
+pub fn check_square(nodes: &[char], matrix: &[Vec<i32>]) {
+    if matrix.len() != nodes.len() || matrix.iter().any(|row| row.len() != nodes.len()) {
+        panic!("Invalid matrix size: must be {0}x{0}", nodes.len());
+    }
+}
+
+pub fn edges_from_adjacency(
+    nodes: &[char],
+    matrix: &[Vec<i32>],
+    directed: bool,
+) -> Vec<(char, char, i32)> {
+    check_square(nodes, matrix);
+    let entries: Vec<(usize, usize, i32)> = matrix
+        .iter()
+        .enumerate()
+        .flat_map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, w)| **w != 0)
+                .map(move |(j, w)| (i, j, *w))
+        })
+        .collect();
+    edges_from_sparse(nodes, &entries, directed)
+}
+
+pub fn edges_from_sparse(
+    nodes: &[char],
+    entries: &[(usize, usize, i32)],
+    directed: bool,
+) -> Vec<(char, char, i32)> {
+    if entries
+        .iter()
+        .any(|(i, j, _)| *i >= nodes.len() || *j >= nodes.len())
+    {
+        panic!("Invalid matrix entry: index out of {} nodes", nodes.len());
+    }
+    let weights: BTreeMap<(usize, usize), i32> =
+        entries.iter().map(|(i, j, w)| ((*i, *j), *w)).collect();
+    weights
+        .iter()
+        .filter(|(&(i, j), &w)| {
+            if !directed && weights.get(&(j, i)) != Some(&w) {
+                panic!("Invalid matrix: must be symmetric for undirected graphs");
+            }
+            w != 0 && (directed || i <= j)
+        })
+        .map(|(&(i, j), &w)| (nodes[i], nodes[j], w))
+        .collect()
+}
+
+pub fn edges_from_incidence(
+    nodes: &[char],
+    matrix: &[Vec<i32>],
+    directed: bool,
+) -> Vec<(char, char)> {
+    if matrix.len() != nodes.len() {
+        panic!("Invalid matrix size: must have {} rows", nodes.len());
+    }
+    let num_edges = matrix.first().map_or(0, |row| row.len());
+    (0..num_edges)
+        .map(|k| {
+            let column: Vec<(usize, i32)> = matrix
+                .iter()
+                .enumerate()
+                .filter(|(_, row)| row[k] != 0)
+                .map(|(i, row)| (i, row[k]))
+                .collect();
+            match (directed, column.as_slice()) {
+                (true, [(i, -1), (j, 1)]) => (nodes[*i], nodes[*j]),
+                (true, [(i, 1), (j, -1)]) => (nodes[*j], nodes[*i]),
+                (false, [(i, 1), (j, 1)]) => (nodes[*i], nodes[*j]),
+                (_, [(i, 2)]) => (nodes[*i], nodes[*i]),
+                _ => panic!("Invalid matrix: column {} is not an edge", k),
+            }
+        })
+        .collect()
+}
+
+pub fn edges_from_laplacian(
+    nodes: &[char],
+    matrix: &[Vec<i32>],
+    directed: bool,
+) -> Vec<(char, char, i32)> {
+    check_square(nodes, matrix);
+    let adjacency: Vec<Vec<i32>> = matrix
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(j, w)| if i == j { 0 } else { -w })
+                .collect()
+        })
+        .collect();
+    edges_from_adjacency(nodes, &adjacency, directed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjacency() {
+        let nodes = vec!['a', 'b', 'c'];
+        let edges = vec![('a', 'b', 2), ('c', 'b', 3)];
+        let matrix = adjacency(&nodes, &edges, false);
+        assert_eq!(matrix, vec![vec![0, 2, 0], vec![2, 0, 3], vec![0, 3, 0]]);
+        assert_eq!(
+            edges_from_adjacency(&nodes, &matrix, false),
+            vec![('a', 'b', 2), ('b', 'c', 3)]
+        );
+        let matrix = adjacency(&nodes, &edges, true);
+        assert_eq!(matrix, vec![vec![0, 2, 0], vec![0, 0, 0], vec![0, 3, 0]]);
+        assert_eq!(edges_from_adjacency(&nodes, &matrix, true), edges);
+    }
+
+    #[test]
+    fn test_incidence() {
+        let nodes = vec!['a', 'b', 'c'];
+        let edges = vec![('a', 'b'), ('c', 'b')];
+        let matrix = incidence(&nodes, &edges, true);
+        assert_eq!(matrix, vec![vec![-1, 0], vec![1, 1], vec![0, -1]]);
+        assert_eq!(edges_from_incidence(&nodes, &matrix, true), edges);
+        let matrix = incidence(&nodes, &edges, false);
+        assert_eq!(matrix, vec![vec![1, 0], vec![1, 1], vec![0, 1]]);
+        assert_eq!(
+            edges_from_incidence(&nodes, &matrix, false),
+            vec![('a', 'b'), ('b', 'c')]
+        );
+
+        // self-loops
+        let edges = vec![('a', 'b'), ('b', 'b')];
+        let matrix = incidence(&nodes, &edges, true);
+        assert_eq!(matrix, vec![vec![-1, 0], vec![1, 2], vec![0, 0]]);
+        assert_eq!(edges_from_incidence(&nodes, &matrix, true), edges);
+        let matrix = incidence(&nodes, &edges, false);
+        assert_eq!(matrix, vec![vec![1, 0], vec![1, 2], vec![0, 0]]);
+        assert_eq!(edges_from_incidence(&nodes, &matrix, false), edges);
+    }
+
+    #[test]
+    fn test_laplacian() {
+        let nodes = vec!['a', 'b', 'c'];
+        let edges = vec![('a', 'b', 2), ('c', 'b', 3)];
+        let matrix = laplacian(&nodes, &edges, false);
+        assert_eq!(
+            matrix,
+            vec![vec![2, -2, 0], vec![-2, 5, -3], vec![0, -3, 3]]
+        );
+        assert_eq!(
+            edges_from_laplacian(&nodes, &matrix, false),
+            vec![('a', 'b', 2), ('b', 'c', 3)]
+        );
+        let matrix = laplacian(&nodes, &edges, true);
+        assert_eq!(matrix, vec![vec![2, -2, 0], vec![0, 0, 0], vec![0, -3, 3]]);
+        assert_eq!(edges_from_laplacian(&nodes, &matrix, true), edges);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_asymmetric_matrix() {
+        edges_from_adjacency(&['a', 'b'], &[vec![0, 1], vec![0, 0]], false);
+    }
+}
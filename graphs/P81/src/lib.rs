@@ -1,11 +1,12 @@
-use graph::{LabeledDigraph, LabeledGraph};
+use graph::Neighbors;
+use std::hash::Hash;
 
-pub trait PathFinder<T> {
-    fn find_paths(&self, start: char, end: char) -> Vec<Vec<T>>;
+pub trait PathFinder<T: Copy + Eq> {
+    fn find_paths(&self, start: T, end: T) -> Vec<Vec<T>>;
 
-    fn find_all_paths<F>(start: char, end: char, adjacents: F) -> Vec<Vec<char>>
+    fn find_all_paths<F>(start: T, end: T, adjacents: F) -> Vec<Vec<T>>
     where
-        F: Fn(char) -> Vec<char>,
+        F: Fn(T) -> Vec<T>,
     {
         let mut paths = vec![];
         let mut resolved = vec![];
@@ -37,21 +38,14 @@ pub trait PathFinder<T> {
     }
 }
 
-impl<U: Copy + Eq> PathFinder<char> for LabeledGraph<char, U> {
-    fn find_paths(&self, start: char, end: char) -> Vec<Vec<char>> {
-        let adjacents = |v| self.get_node(&v).unwrap().adjacents();
-        if let Some(_) = self.get_node(&start) {
-            Self::find_all_paths(start, end, adjacents)
-        } else {
-            vec![]
-        }
-    }
-}
-
-impl<U: Copy + Eq> PathFinder<char> for LabeledDigraph<char, U> {
-    fn find_paths(&self, start: char, end: char) -> Vec<Vec<char>> {
-        let adjacents = |v| self.get_node(&v).unwrap().adjacents();
-        if let Some(_) = self.get_node(&start) {
+impl<T, G> PathFinder<T> for G
+where
+    T: Hash + Copy + Eq,
+    G: Neighbors<T>,
+{
+    fn find_paths(&self, start: T, end: T) -> Vec<Vec<T>> {
+        let adjacents = |v: T| self.neighbors(&v).into_owned();
+        if self.contains(&start) {
             Self::find_all_paths(start, end, adjacents)
         } else {
             vec![]
@@ -84,4 +78,16 @@ mod tests {
         );
         assert_eq!(g.find_paths('p', 'k'), Vec::<Vec<char>>::new());
     }
+
+    #[test]
+    fn test_find_paths_implicit() {
+        use graph::ImplicitGraph;
+        // n -> n + 1 and n -> 2n within 1..=6
+        let g = ImplicitGraph::new((1..=6).collect(), true, |n: &u32| {
+            vec![n + 1, n * 2].into_iter().filter(|m| *m <= 6).collect()
+        });
+        let mut paths = g.find_paths(2, 4);
+        paths.sort();
+        assert_eq!(paths, vec![vec![2, 3, 4], vec![2, 4]]);
+    }
 }
@@ -1,11 +1,12 @@
-use graph::{LabeledDigraph, LabeledGraph};
+use graph::Neighbors;
+use std::hash::Hash;
 
-pub trait CycleFinder<T> {
-    fn find_cycles(&self, node: char) -> Vec<Vec<char>>;
+pub trait CycleFinder<T: Copy + Eq> {
+    fn find_cycles(&self, node: T) -> Vec<Vec<T>>;
 
-    fn find_all_cycles<F>(node: char, adjacents: F) -> Vec<Vec<char>>
+    fn find_all_cycles<F>(node: T, adjacents: F) -> Vec<Vec<T>>
     where
-        F: Fn(char) -> Vec<char>,
+        F: Fn(T) -> Vec<T>,
     {
         let mut paths = vec![];
         let mut cycles = vec![];
@@ -37,21 +38,14 @@ pub trait CycleFinder<T> {
     }
 }
 
-impl<U: Copy + Eq> CycleFinder<char> for LabeledGraph<char, U> {
-    fn find_cycles(&self, node: char) -> Vec<Vec<char>> {
-        let adjacents = |v| self.get_node(&v).unwrap().adjacents();
-        if let Some(_) = self.get_node(&node) {
-            Self::find_all_cycles(node, adjacents)
-        } else {
-            vec![]
-        }
-    }
-}
-
-impl<U: Copy + Eq> CycleFinder<char> for LabeledDigraph<char, U> {
-    fn find_cycles(&self, node: char) -> Vec<Vec<char>> {
-        let adjacents = |v| self.get_node(&v).unwrap().adjacents();
-        if let Some(_) = self.get_node(&node) {
+impl<T, G> CycleFinder<T> for G
+where
+    T: Hash + Copy + Eq,
+    G: Neighbors<T>,
+{
+    fn find_cycles(&self, node: T) -> Vec<Vec<T>> {
+        let adjacents = |v: T| self.neighbors(&v).into_owned();
+        if self.contains(&node) {
             Self::find_all_cycles(node, adjacents)
         } else {
             vec![]
@@ -87,6 +81,15 @@ mod tests {
         assert_eq!(g.find_cycles('g'), Vec::<Vec<char>>::new());
     }
 
+    #[test]
+    fn test_find_cycles_csr() {
+        use graph::CsrGraph;
+        let input = "1 2\n2 3\n3 1\n3 4\n";
+        let g = CsrGraph::<u32, i32>::read_edge_list(input.as_bytes(), true).unwrap();
+        assert_eq!(g.find_cycles(1), vec![vec![1, 2, 3, 1]]);
+        assert_eq!(g.find_cycles(4), Vec::<Vec<u32>>::new());
+    }
+
     fn has_same_elements<T: Hash + Eq>(li1: &Vec<T>, li2: &Vec<T>) -> bool {
         if li1.len() != li2.len() {
             false
@@ -1,16 +1,27 @@
-use graph::Graph;
-use P80::graph_converters::unlabeled;
-use P82::*;
+use graph::{Directed, Graph, Neighbors};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
 
-pub fn spanning_trees(graph: &Graph<char>) -> Vec<Graph<char>> {
-    let all_nodes: Vec<char> = graph.get_nodes().iter().map(|n| *n.get_value()).collect();
+/// Returns all spanning trees of `graph`. For digraphs, arcs are followed in
+/// their directions, so that the trees are spanning out-trees (returned as
+/// undirected graphs). Self-loops are never used, and parallel edges are not
+/// told apart: each tree is returned once.
+pub fn spanning_trees<T, G>(graph: &G) -> Vec<Graph<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let all_nodes: Vec<T> = graph.node_values().into_owned();
     if all_nodes.is_empty() {
         vec![]
     } else {
         // edges, visited
-        let mut paths: Vec<(Vec<(char, char)>, Vec<char>)> = vec![];
+        let mut paths: Vec<(Vec<(T, T)>, Vec<T>)> = vec![];
         // resolved paths
-        let mut found_paths: Vec<Vec<(char, char)>> = vec![];
+        let mut found_paths: Vec<Vec<(T, T)>> = vec![];
 
         // initialize paths
         for start in all_nodes.clone() {
@@ -19,13 +30,13 @@ pub fn spanning_trees(graph: &Graph<char>) -> Vec<Graph<char>> {
         // traverse graph
         while !paths.is_empty() {
             let (edges, visited) = paths.pop().unwrap();
-            if visited.len() == graph.size() && !found_paths.contains(&edges) {
+            if visited.len() == all_nodes.len() && !found_paths.contains(&edges) {
                 // found a path that visits all nodes in the graph
                 found_paths.push(edges);
                 continue;
             }
             for node in &visited {
-                for next in graph.get_node(node).unwrap().adjacents() {
+                for &next in graph.neighbors(node).iter() {
                     if visited.contains(&next) {
                         continue;
                     }
@@ -46,26 +57,158 @@ pub fn spanning_trees(graph: &Graph<char>) -> Vec<Graph<char>> {
         // convert all found paths to a tree list
         found_paths
             .iter()
-            .map(|path| unlabeled::from_term_form(&all_nodes, &path))
+            .map(|path| to_tree(&all_nodes, path))
             .collect()
     }
 }
 
-pub fn is_tree(graph: &Graph<char>) -> bool {
-    !spanning_trees(&graph).is_empty()
-        && graph
-            .get_nodes()
-            .iter()
-            .all(|node| graph.find_cycles(*node.get_value()).is_empty())
+/// Same as `spanning_trees()`, but the trees are grown in parallel (with the
+/// `rayon` feature) and returned sorted by their edges. The graph is only read
+/// before the threads start, so it does not need to be `Sync`.
+#[cfg(feature = "rayon")]
+pub fn par_spanning_trees<T, G>(graph: &G) -> Vec<Graph<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let mut all_nodes: Vec<T> = graph.node_values().into_owned();
+    all_nodes.sort();
+    let n = all_nodes.len();
+    if n == 0 {
+        return vec![];
+    }
+    // the threads share a copy on node indices, in the order of the values
+    let adjs: Vec<Vec<usize>> = all_nodes
+        .iter()
+        .map(|v| {
+            let mut list: Vec<usize> = graph
+                .neighbors(v)
+                .iter()
+                .map(|w| all_nodes.binary_search(w).unwrap())
+                .collect();
+            list.sort_unstable();
+            list.dedup();
+            list
+        })
+        .collect();
+    // every spanning tree grows from some node, one edge at a time; the
+    // subtrees with one more edge are found in parallel for each level
+    let mut subtrees: Vec<_> = (0..n).map(|i| (vec![], vec![i])).collect();
+    for _ in 1..n {
+        subtrees = subtrees
+            .par_iter()
+            .flat_map(|(edges, visited)| {
+                let mut grown = vec![];
+                for &i in visited {
+                    for &j in &adjs[i] {
+                        if visited.binary_search(&j).is_ok() {
+                            continue;
+                        }
+                        let mut new_edges: Vec<(usize, usize)> = edges.clone();
+                        new_edges.push((i.min(j), i.max(j)));
+                        new_edges.sort_unstable();
+                        let mut new_visited = visited.clone();
+                        new_visited.push(j);
+                        new_visited.sort_unstable();
+                        grown.push((new_edges, new_visited));
+                    }
+                }
+                grown
+            })
+            .collect();
+        subtrees.par_sort();
+        subtrees.dedup();
+    }
+    subtrees
+        .iter()
+        .map(|(edges, _)| {
+            let edges: Vec<(T, T)> = edges
+                .iter()
+                .map(|(i, j)| (all_nodes[*i], all_nodes[*j]))
+                .collect();
+            to_tree(&all_nodes, &edges)
+        })
+        .collect()
+}
+
+fn to_tree<T: Hash + Copy + Eq + Ord>(nodes: &[T], edges: &[(T, T)]) -> Graph<T> {
+    let mut tree = Graph::new();
+    for v in nodes {
+        tree.add_node(*v);
+    }
+    for (v1, v2) in edges {
+        tree.add_edge(*v1, *v2);
+    }
+    tree
+}
+
+/// Returns true if `graph` is a tree, i.e. connected without cycles. For
+/// digraphs, arcs are followed in their directions, so that the tree must be
+/// an out-tree. Parallel edges and self-loops are cycles. Runs in linear time.
+pub fn is_tree<T, G>(graph: &G) -> bool
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T> + Directed,
+{
+    let all_nodes: Vec<T> = graph.node_values().into_owned();
+    let directed = graph.is_directed();
+    // a tree is traversed from its root without meeting a node twice, except
+    // (if undirected) for going back to the parent along the edge that led to
+    // the node. Any node can be the root of an undirected tree; an out-tree
+    // grows from the only node without incoming arcs.
+    let root = if directed {
+        let mut in_degrees: HashMap<T, usize> = all_nodes.iter().map(|v| (*v, 0)).collect();
+        for v in &all_nodes {
+            for w in graph.neighbors(v).iter() {
+                *in_degrees.get_mut(w).unwrap() += 1;
+            }
+        }
+        let mut sources = all_nodes.iter().filter(|v| in_degrees[*v] == 0);
+        match (sources.next(), sources.next()) {
+            (Some(root), None) => *root,
+            _ => return false,
+        }
+    } else {
+        match all_nodes.first() {
+            Some(root) => *root,
+            None => return false,
+        }
+    };
+    let mut parents: HashMap<T, Option<T>> = HashMap::new();
+    parents.insert(root, None);
+    let mut queue = VecDeque::from(vec![root]);
+    while let Some(v) = queue.pop_front() {
+        let mut parent = if directed { None } else { parents[&v] };
+        for &w in graph.neighbors(&v).iter() {
+            if Some(w) == parent {
+                parent = None;
+                continue;
+            }
+            match parents.entry(w) {
+                Entry::Occupied(_) => return false,
+                Entry::Vacant(e) => {
+                    e.insert(Some(v));
+                    queue.push_back(w);
+                }
+            }
+        }
+    }
+    parents.len() == all_nodes.len()
 }
 
-pub fn is_connected(graph: &Graph<char>) -> bool {
-    !spanning_trees(&graph).is_empty()
+pub fn is_connected<T, G>(graph: &G) -> bool
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    !spanning_trees(graph).is_empty()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use P80::digraph_converters;
+    use P80::graph_converters::unlabeled;
     #[test]
     fn test_spanning_trees() {
         let g = unlabeled::from_string("[a-b, b-c, a-c]");
@@ -79,6 +222,62 @@ mod tests {
         assert!(is_tree(&g));
         let g = unlabeled::from_string("[a-b, b-c, a-c]");
         assert!(!is_tree(&g));
+        let g = unlabeled::from_string("[a-b, c-d]");
+        assert!(!is_tree(&g));
+        let g = unlabeled::from_string("[a-b, b-b]");
+        assert!(!is_tree(&g));
+        // a cycle away from the first node
+        let g = unlabeled::from_string("[a-b, b-c, c-d, d-b]");
+        assert!(!is_tree(&g));
+
+        let g = digraph_converters::unlabeled::from_string("[a>b, a>c, c>d]");
+        assert!(is_tree(&g));
+        let g = digraph_converters::unlabeled::from_string("[a>b, a>c, b>c]");
+        assert!(!is_tree(&g));
+        let g = digraph_converters::unlabeled::from_string("[a>b, c>b]");
+        assert!(!is_tree(&g));
+        // 2-cycles are cycles in digraphs
+        let g = digraph_converters::unlabeled::from_string("[a>b, b>a]");
+        assert!(!is_tree(&g));
+        let g = digraph_converters::unlabeled::from_string("[a>b, b>a, a>c]");
+        assert!(!is_tree(&g));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_spanning_trees() {
+        let g = unlabeled::from_string("[a-b, b-c, c-d, d-a, a-c, b-e, d-e]");
+        let mut trees = spanning_trees(&g);
+        let par_trees = par_spanning_trees(&g);
+        assert_eq!(par_trees.len(), trees.len());
+        for tree in par_trees {
+            let i = trees.iter().position(|t| *t == tree).unwrap();
+            trees.remove(i);
+        }
+        let g = unlabeled::from_string("[a-b, c]");
+        assert!(par_spanning_trees(&g).is_empty());
+
+        // out-trees of digraphs grow from a node that is not the first one
+        let g = digraph_converters::unlabeled::from_string("[a>b, c>a]");
+        assert_eq!(par_spanning_trees(&g), spanning_trees(&g));
+        assert_eq!(par_spanning_trees(&g).len(), 1);
+        let g = digraph_converters::unlabeled::from_string("[a>b, b>c, c>a, a>d]");
+        assert_eq!(par_spanning_trees(&g).len(), spanning_trees(&g).len());
+    }
+
+    #[test]
+    fn test_multigraph() {
+        let mut g = Graph::new();
+        for v in "abc".chars() {
+            g.add_node(v);
+        }
+        g.add_edge('a', 'b');
+        g.add_edge('b', 'c');
+        assert!(is_tree(&g));
+        // a double edge is a cycle
+        g.add_edge('c', 'b');
+        assert!(!is_tree(&g));
+        assert_eq!(spanning_trees(&g).len(), 1);
     }
 
     #[test]
@@ -88,4 +287,21 @@ mod tests {
         let g = unlabeled::from_string("[a-b, b-c, a-c, d-e]");
         assert!(!is_connected(&g));
     }
+
+    #[test]
+    fn test_spanning_trees_implicit() {
+        use graph::ImplicitGraph;
+        // the complete graph K4 has 16 spanning trees
+        let g = ImplicitGraph::new((0..4).collect(), false, |v: &u32| {
+            (0..4).filter(|w| w != v).collect()
+        });
+        assert_eq!(spanning_trees(&g).len(), 16);
+        assert!(is_connected(&g));
+
+        // the path 0-1-2-3
+        let g = ImplicitGraph::new((0..4).collect(), false, |v: &u32| {
+            (0..4).filter(|w| *w + 1 == *v || *v + 1 == *w).collect()
+        });
+        assert!(is_tree(&g));
+    }
 }
@@ -0,0 +1,35 @@
+use graph::LabeledDigraph;
+use P84::*;
+
+pub fn main() {
+    // directed links between relays with asymmetric costs
+    let links = vec![
+        ('a', 'b', 4),
+        ('b', 'a', 1),
+        ('a', 'c', 2),
+        ('c', 'b', 1),
+        ('b', 'd', 3),
+        ('d', 'b', 2),
+        ('c', 'd', 6),
+        ('d', 'e', 1),
+        ('e', 'c', 1),
+        ('e', 'f', 2),
+    ];
+    let mut g = LabeledDigraph::new();
+    for v in "abcdef".chars() {
+        g.add_node(v);
+    }
+    for (src, dst, cost) in links {
+        g.add_labeled_arc(src, dst, cost);
+    }
+    for &root in &['a', 'd', 'f'] {
+        match minimum_spanning_arborescence(&g, root) {
+            Ok(tree) => {
+                let mut arcs = tree.get_arcs();
+                arcs.sort();
+                println!("from {}: {:?} (cost={})", root, arcs, label_sum(&tree));
+            }
+            Err(e) => println!("from {}: {}", root, e),
+        }
+    }
+}
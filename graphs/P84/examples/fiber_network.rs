@@ -0,0 +1,56 @@
+use graph::AttributedGraph;
+use P84::*;
+
+#[derive(Debug)]
+struct Site {
+    name: String,
+    customers: u32,
+}
+
+#[derive(Debug)]
+struct Trench {
+    km: i32,
+    // existing ducts that a cable can be pulled through
+    ducts: bool,
+}
+
+pub fn main() {
+    // sites and the trenches that could be dug between them
+    let mut g = AttributedGraph::new();
+    for &(id, name) in &[(1, "depot"), (2, "school"), (3, "harbour"), (4, "mill")] {
+        let site = Site {
+            name: name.to_string(),
+            customers: 0,
+        };
+        g.add_node(id, site);
+    }
+    let trenches = vec![
+        (1, 2, 3, false),
+        (1, 3, 5, true),
+        (2, 3, 2, false),
+        (3, 4, 4, false),
+        (2, 4, 6, true),
+    ];
+    for (v, w, km, ducts) in trenches {
+        g.add_edge(v, w, Trench { km, ducts });
+    }
+    g.node_mut(&3).unwrap().customers += 120;
+
+    // digging costs 10 per km, pulling a cable through a duct 1 per km
+    let cost = g.weighted_by(|t: &Trench| if t.ducts { t.km } else { t.km * 10 });
+    for tree in minimal_spanning_trees(&cost) {
+        let mut edges: Vec<(&str, &str)> = tree
+            .get_edges()
+            .iter()
+            .map(|(v, w, _)| {
+                (
+                    g.node(v).unwrap().name.as_str(),
+                    g.node(w).unwrap().name.as_str(),
+                )
+            })
+            .collect();
+        edges.sort();
+        println!("{:?} (cost={})", edges, label_sum(&tree));
+    }
+    println!("{:?}", g.node(&3).unwrap());
+}
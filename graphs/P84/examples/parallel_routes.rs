@@ -0,0 +1,32 @@
+use graph::{GraphKind, LabeledGraph};
+use P84::*;
+
+pub fn main() {
+    // stations linked by bus and train routes (travel times in minutes)
+    let mut g = LabeledGraph::with_kind(GraphKind::Multigraph);
+    for v in "abcd".chars() {
+        g.add_node(v);
+    }
+    let routes = vec![
+        ('a', 'b', 30),
+        ('a', 'b', 12),
+        ('b', 'c', 20),
+        ('a', 'c', 25),
+        ('c', 'd', 15),
+        ('c', 'd', 40),
+    ];
+    for (v, w, minutes) in routes {
+        g.add_labeled_edge(v, w, minutes);
+    }
+    println!("routes between a and b: {}", g.multiplicity(&'a', &'b'));
+    if let Err(e) = g.try_add_edge_with_value('d', 'd', Some(5)) {
+        println!("{}", e);
+    }
+
+    // the fastest network still connecting all stations
+    for tree in minimal_spanning_trees(&g) {
+        let mut edges = tree.get_edges();
+        edges.sort();
+        println!("{:?} (minutes={})", edges, label_sum(&tree));
+    }
+}
@@ -0,0 +1,248 @@
+use graph::{EdgeWeights, LabeledDigraph};
+use std::collections::VecDeque;
+use std::fmt;
+use std::hash::Hash;
+
+/// Errors on finding a spanning arborescence
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArborescenceError<T> {
+    /// The root is not a node of the digraph.
+    UnknownRoot(T),
+    /// No arcs lead from the root to these nodes (sorted).
+    Unreachable(Vec<T>),
+}
+
+impl<T: fmt::Debug> fmt::Display for ArborescenceError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArborescenceError::UnknownRoot(v) => write!(f, "unknown root: {:?}", v),
+            ArborescenceError::Unreachable(vs) => {
+                write!(f, "nodes unreachable from the root: {:?}", vs)
+            }
+        }
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for ArborescenceError<T> {}
+
+// an arc between node indices with its weight
+type Arc = (usize, usize, i64);
+
+/// Returns a minimum spanning arborescence of `graph` rooted at `root`, i.e. the
+/// arcs of least total label through which every node is reached from the root
+/// along exactly one path (Chu–Liu/Edmonds' algorithm).
+///
+/// Arcs must be labeled; labels may be negative. Self-loops are ignored and of
+/// parallel arcs only the lightest counts. For undirected graphs, each edge can
+/// be used in either direction.
+///
+/// Fails if `root` is not in the graph or some nodes cannot be reached from it.
+pub fn minimum_spanning_arborescence<T, G>(
+    graph: &G,
+    root: T,
+) -> Result<LabeledDigraph<T, i32>, ArborescenceError<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let mut values = graph.node_values().into_owned();
+    values.sort();
+    let r = match values.binary_search(&root) {
+        Ok(r) => r,
+        Err(_) => return Err(ArborescenceError::UnknownRoot(root)),
+    };
+    let mut arcs: Vec<Arc> = vec![];
+    for (i, v) in values.iter().enumerate() {
+        for (w, l) in graph.neighbors_with_weight(v) {
+            let j = values.binary_search(&w).unwrap();
+            if i != j {
+                arcs.push((i, j, i64::from(l.expect("arcs must be labeled"))));
+            }
+        }
+    }
+
+    let n = values.len();
+    let mut succs: Vec<Vec<usize>> = vec![vec![]; n];
+    for (i, j, _) in &arcs {
+        succs[*i].push(*j);
+    }
+    let mut reached = vec![false; n];
+    reached[r] = true;
+    let mut queue = VecDeque::from(vec![r]);
+    while let Some(i) = queue.pop_front() {
+        for j in &succs[i] {
+            if !reached[*j] {
+                reached[*j] = true;
+                queue.push_back(*j);
+            }
+        }
+    }
+    let unreachable: Vec<T> = (0..n).filter(|i| !reached[*i]).map(|i| values[i]).collect();
+    if !unreachable.is_empty() {
+        return Err(ArborescenceError::Unreachable(unreachable));
+    }
+
+    let mut tree = LabeledDigraph::new();
+    for v in &values {
+        tree.add_node(*v);
+    }
+    for k in edmonds(n, r, &arcs) {
+        let (i, j, w) = arcs[k];
+        tree.add_labeled_arc(values[i], values[j], w as i32);
+    }
+    Ok(tree)
+}
+
+// returns the indices of the arcs of a minimum arborescence; every node must
+// be reachable from the root
+fn edmonds(n: usize, root: usize, arcs: &[Arc]) -> Vec<usize> {
+    // the lightest arc into each node (the first one on ties)
+    let mut min_in: Vec<Option<usize>> = vec![None; n];
+    for (k, (i, j, w)) in arcs.iter().enumerate() {
+        if i == j || *j == root {
+            continue;
+        }
+        match min_in[*j] {
+            Some(m) if arcs[m].2 <= *w => {}
+            _ => min_in[*j] = Some(k),
+        }
+    }
+
+    // follow the chosen arcs backwards from every node to find a cycle
+    let mut cycle: Vec<usize> = vec![];
+    let mut mark = vec![None; n];
+    for start in 0..n {
+        let mut v = start;
+        while mark[v].is_none() {
+            mark[v] = Some(start);
+            match min_in[v] {
+                Some(k) => v = arcs[k].0,
+                None => break,
+            }
+        }
+        if mark[v] == Some(start) && min_in[v].is_some() {
+            cycle.push(v);
+            let mut u = arcs[min_in[v].unwrap()].0;
+            while u != v {
+                cycle.push(u);
+                u = arcs[min_in[u].unwrap()].0;
+            }
+            break;
+        }
+    }
+    if cycle.is_empty() {
+        return min_in.into_iter().flatten().collect();
+    }
+
+    // contract the cycle into a new node numbered last; an arc entering the
+    // cycle costs what it saves over the cycle arc it replaces
+    let mut in_cycle = vec![false; n];
+    for v in &cycle {
+        in_cycle[*v] = true;
+    }
+    let mut id = vec![0; n];
+    let mut next = 0;
+    for v in 0..n {
+        if !in_cycle[v] {
+            id[v] = next;
+            next += 1;
+        }
+    }
+    for v in &cycle {
+        id[*v] = next;
+    }
+    let mut contracted: Vec<Arc> = vec![];
+    let mut origins: Vec<usize> = vec![];
+    for (k, (i, j, w)) in arcs.iter().enumerate() {
+        if id[*i] == id[*j] {
+            continue;
+        }
+        let w = if in_cycle[*j] {
+            w - arcs[min_in[*j].unwrap()].2
+        } else {
+            *w
+        };
+        contracted.push((id[*i], id[*j], w));
+        origins.push(k);
+    }
+
+    // expand: the arc entering the cycle replaces the cycle arc into its head
+    let mut chosen: Vec<usize> = edmonds(next + 1, id[root], &contracted)
+        .into_iter()
+        .map(|k| origins[k])
+        .collect();
+    let entry = chosen
+        .iter()
+        .map(|k| arcs[*k].1)
+        .find(|j| in_cycle[*j])
+        .unwrap();
+    for v in cycle {
+        if v != entry {
+            chosen.push(min_in[v].unwrap());
+        }
+    }
+    chosen.sort();
+    chosen
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::label_sum;
+    use P80::digraph_converters;
+    use P80::graph_converters::labeled;
+
+    #[test]
+    fn test_minimum_spanning_arborescence() {
+        // the cheap cycle b>c>d>b has to be broken at its cheapest entry
+        let g = digraph_converters::labeled::from_string(
+            "[a>b/10, a>c/2, a>d/9, b>c/1, c>d/1, d>b/1, d>d/-5, c>a/0]",
+        );
+        let tree = minimum_spanning_arborescence(&g, 'a').unwrap();
+        let mut arcs = tree.get_arcs();
+        arcs.sort();
+        assert_eq!(
+            arcs,
+            vec![
+                ('a', 'c', Some(2)),
+                ('c', 'd', Some(1)),
+                ('d', 'b', Some(1))
+            ]
+        );
+        assert_eq!(label_sum(&tree), 4);
+
+        // nested cycles
+        let g = digraph_converters::labeled::from_string(
+            "[r>a/5, r>b/6, a>b/1, b>c/1, c>a/1, c>d/2, d>c/1, r>d/3]",
+        );
+        let tree = minimum_spanning_arborescence(&g, 'r').unwrap();
+        assert_eq!(label_sum(&tree), 6);
+        assert_eq!(tree.size(), 5);
+        assert_eq!(tree.get_arcs().len(), 4);
+
+        // undirected edges can be used either way
+        let g = labeled::from_string("[a-b/1, b-c/2, a-c/3]");
+        let tree = minimum_spanning_arborescence(&g, 'c').unwrap();
+        let mut arcs = tree.get_arcs();
+        arcs.sort();
+        assert_eq!(arcs, vec![('b', 'a', Some(1)), ('c', 'b', Some(2))]);
+    }
+
+    #[test]
+    fn test_minimum_spanning_arborescence_errors() {
+        let g = digraph_converters::labeled::from_string("[a>b/1, c>b/1, d>c/1]");
+        assert_eq!(
+            minimum_spanning_arborescence(&g, 'a').unwrap_err(),
+            ArborescenceError::Unreachable(vec!['c', 'd'])
+        );
+        let e = minimum_spanning_arborescence(&g, 'x').unwrap_err();
+        assert_eq!(e, ArborescenceError::UnknownRoot('x'));
+        assert_eq!(e.to_string(), "unknown root: 'x'");
+        assert_eq!(
+            minimum_spanning_arborescence(&g, 'd')
+                .unwrap_err()
+                .to_string(),
+            "nodes unreachable from the root: ['a']"
+        );
+    }
+}
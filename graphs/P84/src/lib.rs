@@ -1,17 +1,29 @@
-use graph::LabeledGraph;
-use P80::graph_converters::labeled;
+use graph::{Directed, EdgeWeights, LabeledGraph};
+use std::hash::Hash;
 
-pub fn minimal_spanning_trees(graph: &LabeledGraph<char, i32>) -> Vec<LabeledGraph<char, i32>> {
-    let all_nodes: Vec<char> = graph.get_nodes().iter().map(|n| *n.get_value()).collect();
-    if all_nodes.is_empty() {
+mod arborescence;
+
+pub use arborescence::{minimum_spanning_arborescence, ArborescenceError};
+
+/// Returns all minimal spanning trees of `graph` (Prim's algorithm from every
+/// node). Edges must be labeled. Of parallel edges only the lightest one can be
+/// used, and self-loops are never used.
+///
+/// Prim's algorithm does not work along arcs, so none are returned for
+/// digraphs; use `minimum_spanning_arborescence()` instead.
+pub fn minimal_spanning_trees<T, G>(graph: &G) -> Vec<LabeledGraph<T, i32>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32> + Directed,
+{
+    let all_nodes: Vec<T> = graph.node_values().into_owned();
+    if all_nodes.is_empty() || graph.is_directed() {
         vec![]
     } else {
         // edges, visited
-        let mut paths: Vec<(Vec<(char, char, i32)>, Vec<char>)> = vec![];
+        let mut paths: Vec<(Vec<(T, T, i32)>, Vec<T>)> = vec![];
         // resolved minimum paths
-        let mut found_paths: Vec<(Vec<(char, char, i32)>, i32)> = vec![];
-
-        let path_score = |edges: &Vec<(char, char, i32)>| edges.iter().map(|(_, _, l)| l).sum();
+        let mut found_paths: Vec<Vec<(T, T, i32)>> = vec![];
 
         // initialize paths
         for start in all_nodes.clone() {
@@ -20,26 +32,25 @@ pub fn minimal_spanning_trees(graph: &LabeledGraph<char, i32>) -> Vec<LabeledGra
         // traverse graph
         while !paths.is_empty() {
             let (edges, visited) = paths.pop().unwrap();
-            let score = path_score(&edges);
-            if visited.len() == graph.size() {
+            if visited.len() == all_nodes.len() {
                 // found a path with minimal score
-                found_paths.push((edges, score));
+                found_paths.push(edges);
                 continue;
             }
-            let mut min_edge = ('_', '_', std::i32::MAX);
-            let mut found = false;
+            let mut min_edge: Option<(T, T, i32)> = None;
             for node in &visited {
-                for (next, label) in graph.get_node(node).unwrap().adjacents_with_label() {
+                for (next, label) in graph.neighbors_with_weight(node) {
                     if visited.contains(&next) {
                         continue;
                     }
-                    if label.unwrap() < min_edge.2 {
-                        min_edge = (*node, next, label.unwrap());
-                        found = true;
+                    let label = label.expect("edges must be labeled");
+                    match min_edge {
+                        Some((_, _, min)) if min <= label => {}
+                        _ => min_edge = Some((*node, next, label)),
                     }
                 }
             }
-            if found {
+            if let Some(min_edge) = min_edge {
                 let mut new_edges = edges.clone();
                 new_edges.push(min_edge);
                 let mut new_visited = visited.clone();
@@ -49,8 +60,14 @@ pub fn minimal_spanning_trees(graph: &LabeledGraph<char, i32>) -> Vec<LabeledGra
         }
 
         let mut trees = vec![];
-        for (path, _) in found_paths {
-            let tree = labeled::from_term_form(&all_nodes, &path);
+        for path in found_paths {
+            let mut tree = LabeledGraph::new();
+            for v in &all_nodes {
+                tree.add_node(*v);
+            }
+            for (v1, v2, l) in path {
+                tree.add_labeled_edge(v1, v2, l);
+            }
             if !trees.contains(&tree) {
                 trees.push(tree);
             }
@@ -59,22 +76,49 @@ pub fn minimal_spanning_trees(graph: &LabeledGraph<char, i32>) -> Vec<LabeledGra
     }
 }
 
-pub fn label_sum(graph: &LabeledGraph<char, i32>) -> i32 {
-    labeled::to_term_form(&graph)
-        .1
+/// Returns the sum of all edge labels; unlabeled edges are ignored.
+pub fn label_sum<T, G>(graph: &G) -> i32
+where
+    T: Hash + Copy + Eq,
+    G: EdgeWeights<T, Weight = i32> + Directed,
+{
+    let sum: i32 = graph
+        .node_values()
         .iter()
-        .map(|(_, _, l)| l)
-        .sum()
+        .flat_map(|v| graph.neighbors_with_weight(v))
+        .filter_map(|(_, l)| l)
+        .sum();
+    if graph.is_directed() {
+        sum
+    } else {
+        // every edge is seen from both of its ends
+        sum / 2
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use P80::graph_converters::labeled;
     #[test]
     fn test_spanning_trees() {
         let g = labeled::from_string("[a-b/1, b-c/2, a-c/3]");
         let trees = minimal_spanning_trees(&g);
         assert_eq!(trees.len(), 1);
         assert_eq!(label_sum(&trees.get(0).unwrap()), 3);
+
+        let g = P80::digraph_converters::labeled::from_string("[a>b/1, b>c/2, a>c/3]");
+        assert!(minimal_spanning_trees(&g).is_empty());
+    }
+
+    #[test]
+    fn test_spanning_trees_csr() {
+        use graph::CsrGraph;
+        let input = "a b 1\nb c 2\na c 3\nc d 1\n";
+        let g = CsrGraph::<char, i32>::read_edge_list(input.as_bytes(), false).unwrap();
+        assert_eq!(label_sum(&g), 7);
+        let trees = minimal_spanning_trees(&g);
+        assert_eq!(trees.len(), 1);
+        assert_eq!(label_sum(&trees[0]), 4);
     }
 }
@@ -1,99 +1,323 @@
-use graph::Graph;
-use std::collections::{HashMap, HashSet};
+use graph::{GraphBase, Neighbors};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::hash::Hash;
 
-pub fn is_isomorphic_to<T: Hash + Copy + Eq + Ord, U: Hash + Copy + Eq + Ord>(
-    g1: &Graph<T>,
-    g2: &Graph<U>,
-) -> bool {
-    fn is_isonorphic_to_rec<T: Hash + Copy + Eq + Ord, U: Hash + Copy + Eq + Ord>(
-        g1: &Graph<T>,
-        g2: &Graph<U>,
-        unmapped1: &Vec<T>,
-        unmapped2: &Vec<U>,
-        mapping: &HashMap<T, U>,
-    ) -> bool {
-        if unmapped1.is_empty() {
+/// Checks if `g1` and `g2` are isomorphic, i.e. there is a bijection between
+/// their nodes that preserves adjacency (and arc directions for digraphs).
+/// Parallel edges and self-loops must be as many on both sides.
+pub fn is_isomorphic_to<T, U, G1, G2>(g1: &G1, g2: &G2) -> bool
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Hash + Copy + Eq + Ord,
+    G1: Neighbors<T>,
+    G2: Neighbors<U>,
+{
+    let unmapped1: Vec<T> = nodes_by_degree(g1);
+    let unmapped2: Vec<U> = nodes_by_degree(g2);
+    let mapping = HashMap::<T, U>::new();
+    is_isonorphic_to_rec(g1, g2, &unmapped1, &unmapped2, &mapping)
+}
+
+fn is_isonorphic_to_rec<T, U, G1, G2>(
+    g1: &G1,
+    g2: &G2,
+    unmapped1: &Vec<T>,
+    unmapped2: &Vec<U>,
+    mapping: &HashMap<T, U>,
+) -> bool
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Hash + Copy + Eq + Ord,
+    G1: Neighbors<T>,
+    G2: Neighbors<U>,
+{
+    if unmapped1.is_empty() {
+        return unmapped2.is_empty();
+    }
+    // the next node is mapped to every candidate in turn, backtracking if
+    // the rest cannot be mapped
+    for j in 0..unmapped2.len() {
+        let mut unmapped1_copy = unmapped1.clone();
+        let n1 = unmapped1_copy.remove(0);
+        let mut unmapped2_copy = unmapped2.clone();
+        let n2 = unmapped2_copy.remove(j);
+        let mut mapping_copy = mapping.clone();
+        mapping_copy.insert(n1, n2);
+        if is_valid_mapping(g1, g2, &mapping_copy)
+            && is_isonorphic_to_rec(g1, g2, &unmapped1_copy, &unmapped2_copy, &mapping_copy)
+        {
             return true;
         }
-        for i in 0..unmapped1.len() {
-            for j in 0..unmapped2.len() {
-                let mut unmapped1_copy = unmapped1.clone();
-                let n1 = unmapped1_copy.remove(i);
-                let mut unmapped2_copy = unmapped2.clone();
-                let n2 = unmapped2_copy.remove(j);
-                let mut mapping_copy = mapping.clone();
-                mapping_copy.insert(n1, n2);
-                if is_valid_mapping(g1, g2, &mapping_copy) {
-                    return is_isonorphic_to_rec(
-                        g1,
-                        g2,
-                        &unmapped1_copy,
-                        &unmapped2_copy,
-                        &mapping_copy,
-                    );
-                }
-            }
+    }
+    return false;
+}
+
+fn is_valid_mapping<T, U, G1, G2>(g1: &G1, g2: &G2, mapping: &HashMap<T, U>) -> bool
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Hash + Copy + Eq + Ord,
+    G1: Neighbors<T>,
+    G2: Neighbors<U>,
+{
+    for (n1, n2) in mapping {
+        if g1.degree(n1) != g2.degree(n2) {
+            // the degrees are not equivalent
+            return false;
         }
-        return false;
-    }
-
-    fn is_valid_mapping<T: Hash + Copy + Eq + Ord, U: Hash + Copy + Eq + Ord>(
-        g1: &Graph<T>,
-        g2: &Graph<U>,
-        mapping: &HashMap<T, U>,
-    ) -> bool {
-        for (n1, n2) in mapping {
-            let node1 = g1.get_node(n1).unwrap();
-            let node2 = g2.get_node(n2).unwrap();
-            if node1.degree() != node2.degree() {
-                // the degrees are not equivalent
+        // check if the mapping is consistent: mapped nodes must be linked
+        // by as many edges
+        let adjs1 = g1.neighbors(n1);
+        let adjs2 = g2.neighbors(n2);
+        for (m1, m2) in mapping {
+            let count1 = adjs1.iter().filter(|n| *n == m1).count();
+            let count2 = adjs2.iter().filter(|n| *n == m2).count();
+            if count1 != count2 {
                 return false;
-            } else {
-                // check if the mapping is consistent
-                let known_nodes1: Vec<&T> = mapping.keys().map(|x| x).collect();
-                let known_nodes2: Vec<&U> = mapping.values().map(|x| x).collect();
-                let adjs1: HashSet<T> = node1
-                    .adjacents()
-                    .iter()
-                    .filter(|n| known_nodes1.contains(n))
-                    .map(|n| *n)
-                    .collect();
-                let adjs2: HashSet<U> = node2
-                    .adjacents()
+            }
+        }
+    }
+    true
+}
+
+// nodes sorted according to increasing degree
+fn nodes_by_degree<T, G>(g: &G) -> Vec<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let mut nodes = g.node_values().into_owned();
+    nodes.sort_by_key(|v| (g.degree(v), *v));
+    nodes
+}
+
+/// Same as `is_isomorphic_to()`, trying the candidates for the first node in
+/// parallel (with the `rayon` feature). The graphs are only read before the
+/// threads start, so they do not need to be `Sync`.
+#[cfg(feature = "rayon")]
+pub fn par_is_isomorphic_to<T, U, G1, G2>(g1: &G1, g2: &G2) -> bool
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Hash + Copy + Eq + Ord,
+    G1: Neighbors<T>,
+    G2: Neighbors<U>,
+{
+    // the threads share copies on node indices
+    let g1 = Adjacency::new(g1);
+    let g2 = Adjacency::new(g2);
+    let mut unmapped1: Vec<usize> = nodes_by_degree(&g1);
+    let unmapped2: Vec<usize> = nodes_by_degree(&g2);
+    if unmapped1.is_empty() {
+        return unmapped2.is_empty();
+    }
+    let n1 = unmapped1.remove(0);
+    (0..unmapped2.len()).into_par_iter().any(|j| {
+        let mut unmapped2_copy = unmapped2.clone();
+        let n2 = unmapped2_copy.remove(j);
+        let mut mapping = HashMap::new();
+        mapping.insert(n1, n2);
+        is_valid_mapping(&g1, &g2, &mapping)
+            && is_isonorphic_to_rec(&g1, &g2, &unmapped1, &unmapped2_copy, &mapping)
+    })
+}
+
+// a copy of a graph on the indices of its nodes in sorted order, with the
+// (out-)adjacency lists sorted; parallel edges and self-loops are kept
+struct Adjacency {
+    nodes: Vec<usize>,
+    outs: Vec<Vec<usize>>,
+}
+
+impl Adjacency {
+    fn new<T, G>(g: &G) -> Self
+    where
+        T: Hash + Copy + Eq + Ord,
+        G: Neighbors<T>,
+    {
+        let mut values = g.node_values().into_owned();
+        values.sort();
+        let outs = values
+            .iter()
+            .map(|v| {
+                let mut list: Vec<usize> = g
+                    .neighbors(v)
                     .iter()
-                    .filter(|n| known_nodes2.contains(n))
-                    .map(|n| *n)
+                    .map(|w| values.binary_search(w).unwrap())
                     .collect();
-                let ok = adjs1
-                    .iter()
-                    .all(|adj| adjs2.contains(mapping.get(adj).unwrap()));
-                if !ok {
-                    return false;
+                list.sort_unstable();
+                list
+            })
+            .collect();
+        Adjacency {
+            nodes: (0..values.len()).collect(),
+            outs,
+        }
+    }
+}
+
+impl GraphBase<usize> for Adjacency {
+    fn node_values(&self) -> Cow<'_, [usize]> {
+        Cow::Borrowed(&self.nodes)
+    }
+
+    fn contains(&self, v: &usize) -> bool {
+        *v < self.nodes.len()
+    }
+}
+
+impl Neighbors<usize> for Adjacency {
+    fn neighbors(&self, v: &usize) -> Cow<'_, [usize]> {
+        Cow::Borrowed(&self.outs[*v])
+    }
+}
+
+/// Returns a canonical form of `g`: the sorted (out-)adjacency lists of its
+/// nodes renumbered 0..n in an order that only depends on the structure of the
+/// graph. Two graphs are isomorphic iff their canonical forms are equal, so the
+/// forms can be hashed to weed out duplicates among many graphs.
+///
+/// The order is found by individualization and refinement: the nodes are split
+/// into cells by the number of neighbors they have in every other cell, ties are
+/// broken by trying each node of a cell in turn, and the smallest adjacency
+/// lists over all the tries are kept. Parallel edges and self-loops are kept
+/// in the lists, so that multigraphs with different multiplicities differ.
+pub fn canonical_form<T, G>(g: &G) -> Vec<Vec<usize>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let outs = Adjacency::new(g).outs;
+    let n = outs.len();
+    // the lists stay sorted, for i increases
+    let mut ins: Vec<Vec<usize>> = vec![vec![]; n];
+    for (i, adj) in outs.iter().enumerate() {
+        for j in adj {
+            ins[*j].push(i);
+        }
+    }
+    let mut best = None;
+    search(&outs, &ins, vec![(0..n).collect()], &mut best);
+    best.unwrap_or_default()
+}
+
+// explores the individualization tree below the partition `cells`, keeping the
+// smallest relabeled adjacency lists found at its leaves
+fn search(
+    outs: &[Vec<usize>],
+    ins: &[Vec<usize>],
+    cells: Vec<Vec<usize>>,
+    best: &mut Option<Vec<Vec<usize>>>,
+) {
+    let cells = refine(outs, ins, cells);
+    let k = match cells.iter().position(|c| c.len() > 1) {
+        Some(k) => k,
+        None => {
+            let mut position = vec![0; outs.len()];
+            for (p, c) in cells.iter().enumerate() {
+                position[c[0]] = p;
+            }
+            let mut form = vec![vec![]; outs.len()];
+            for (v, adj) in outs.iter().enumerate() {
+                form[position[v]] = adj.iter().map(|w| position[*w]).collect();
+                form[position[v]].sort();
+            }
+            let smaller = match best {
+                Some(b) => form < *b,
+                None => true,
+            };
+            if smaller {
+                *best = Some(form);
+            }
+            return;
+        }
+    };
+    let mut tried: Vec<usize> = vec![];
+    for v in &cells[k] {
+        // swapping twins is an automorphism, so their subtrees give the same forms
+        if tried
+            .iter()
+            .any(|u| twins(outs, *u, *v) && twins(ins, *u, *v))
+        {
+            continue;
+        }
+        tried.push(*v);
+        let mut next = cells[..k].to_vec();
+        next.push(vec![*v]);
+        next.push(cells[k].iter().filter(|w| *w != v).copied().collect());
+        next.extend_from_slice(&cells[k + 1..]);
+        search(outs, ins, next, best);
+    }
+}
+
+// whether u and v have the same neighbors apart from each other
+fn twins(adj: &[Vec<usize>], u: usize, v: usize) -> bool {
+    let others = |x: usize, y: usize| adj[x].iter().filter(move |w| **w != y);
+    others(u, v).eq(others(v, u))
+}
+
+// splits the cells by the neighbor counts of their nodes in every cell until no
+// cell splits any more
+fn refine(outs: &[Vec<usize>], ins: &[Vec<usize>], mut cells: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+    loop {
+        let mut cell_of = vec![0; outs.len()];
+        for (c, cell) in cells.iter().enumerate() {
+            for v in cell {
+                cell_of[*v] = c;
+            }
+        }
+        let signature = |v: usize| {
+            let mut counts = vec![(0, 0); cells.len()];
+            for w in &outs[v] {
+                counts[cell_of[*w]].0 += 1;
+            }
+            for w in &ins[v] {
+                counts[cell_of[*w]].1 += 1;
+            }
+            counts
+        };
+        let mut refined = vec![];
+        for cell in &cells {
+            if cell.len() == 1 {
+                refined.push(cell.clone());
+                continue;
+            }
+            let mut signed: Vec<(Vec<(usize, usize)>, usize)> =
+                cell.iter().map(|v| (signature(*v), *v)).collect();
+            signed.sort();
+            for (i, (sig, v)) in signed.iter().enumerate() {
+                if i == 0 || signed[i - 1].0 != *sig {
+                    refined.push(vec![]);
                 }
+                refined.last_mut().unwrap().push(*v);
             }
         }
-        true
-    }
-
-    let unmapped1: Vec<T> = g1
-        .get_nodes_by_degree(false)
-        .iter()
-        .map(|n| *n.get_value())
-        .collect();
-    let unmapped2: Vec<U> = g2
-        .get_nodes_by_degree(false)
-        .iter()
-        .map(|n| *n.get_value())
-        .collect();
-    let mapping = HashMap::<T, U>::new();
-    is_isonorphic_to_rec(g1, g2, &unmapped1, &unmapped2, &mapping)
+        if refined.len() == cells.len() {
+            return refined;
+        }
+        cells = refined;
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use graph::Graph;
     use P80::graph_converters::unlabeled;
+
+    // term forms are sets of edges, so parallel edges are added one by one
+    fn multigraph(nodes: &str, edges: &[(char, char)]) -> Graph<char> {
+        let mut g = Graph::new();
+        for v in nodes.chars() {
+            g.add_node(v);
+        }
+        for (v, w) in edges {
+            g.add_edge(*v, *w);
+        }
+        g
+    }
+
     #[test]
     fn test_is_isomorphic_to() {
         let g1 = unlabeled::from_string("[a-b]");
@@ -107,5 +331,91 @@ mod tests {
         let g1 = unlabeled::from_string("[a-b a-c b-d a-d]");
         let g2 = unlabeled::from_string("[1-3 1-2 3-4 2-4]");
         assert!(!is_isomorphic_to(&g1, &g2));
+
+        // the first consistent choice for a is a wrong one
+        let g1 = unlabeled::from_string("[a-b c-d d-e]");
+        let g2 = unlabeled::from_string("[1-2 2-3 4-5]");
+        assert!(is_isomorphic_to(&g1, &g2));
+
+        let g1 = unlabeled::from_string("[a-b c]");
+        let g2 = unlabeled::from_string("[1-2]");
+        assert!(!is_isomorphic_to(&g1, &g2));
+
+        // two double edges are not a cycle, although all degrees are 2
+        let g1 = multigraph("abcd", &[('a', 'b'), ('a', 'b'), ('c', 'd'), ('c', 'd')]);
+        let g2 = unlabeled::from_string("[w-x, x-y, y-z, z-w]");
+        assert!(!is_isomorphic_to(&g1, &g2));
+        let g2 = multigraph("wxyz", &[('w', 'x'), ('y', 'z'), ('x', 'w'), ('z', 'y')]);
+        assert!(is_isomorphic_to(&g1, &g2));
+        let g1 = multigraph("ab", &[('a', 'a'), ('a', 'b')]);
+        let g2 = multigraph("xy", &[('x', 'y'), ('y', 'y')]);
+        assert!(is_isomorphic_to(&g1, &g2));
+        let g2 = multigraph("xy", &[('x', 'y'), ('x', 'y')]);
+        assert!(!is_isomorphic_to(&g1, &g2));
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        let g1 = unlabeled::from_string("[a-b, b-c, c-d, d-a, a-c, e]");
+        let g2 = unlabeled::from_string("[5-4, 4-2, 2-1, 1-5, 4-1, 3]");
+        assert_eq!(canonical_form(&g1), canonical_form(&g2));
+
+        // the two cubic graphs with 6 nodes
+        let prism = unlabeled::from_string("[a-b, b-c, c-a, d-e, e-f, f-d, a-d, b-e, c-f]");
+        let k33 = unlabeled::from_string("[a-d, a-e, a-f, b-d, b-e, b-f, c-d, c-e, c-f]");
+        assert_ne!(canonical_form(&prism), canonical_form(&k33));
+        let shuffled = unlabeled::from_string("[f-b, b-e, e-f, c-a, a-d, d-c, f-a, b-c, e-d]");
+        assert_eq!(canonical_form(&prism), canonical_form(&shuffled));
+
+        // parallel edges count
+        let g1 = multigraph("abc", &[('a', 'b'), ('b', 'c'), ('b', 'c')]);
+        let g2 = unlabeled::from_string("[a-b, b-c]");
+        let g3 = multigraph("xyz", &[('x', 'y'), ('y', 'z'), ('x', 'y')]);
+        assert_ne!(canonical_form(&g1), canonical_form(&g2));
+        assert_eq!(canonical_form(&g1), canonical_form(&g3));
+
+        // arcs keep their direction
+        use P80::digraph_converters;
+        let g1 = digraph_converters::unlabeled::from_string("[a>b, b>c]");
+        let g2 = digraph_converters::unlabeled::from_string("[x>y, z>y]");
+        let g3 = digraph_converters::unlabeled::from_string("[z>x, y>z]");
+        assert_ne!(canonical_form(&g1), canonical_form(&g2));
+        assert_eq!(canonical_form(&g1), canonical_form(&g3));
+    }
+
+    #[test]
+    fn test_is_isomorphic_to_digraph() {
+        use graph::ImplicitGraph;
+        use P80::digraph_converters;
+        let g1 = digraph_converters::unlabeled::from_string("[a>b, b>c, c>a]");
+        let g2 = ImplicitGraph::new((0..3).collect(), true, |v: &u32| vec![(v + 1) % 3]);
+        assert!(is_isomorphic_to(&g1, &g2));
+
+        let g1 = digraph_converters::unlabeled::from_string("[a>b, b>c, a>c]");
+        assert!(!is_isomorphic_to(&g1, &g2));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_is_isomorphic_to() {
+        use graph::CsrGraph;
+        use P80::digraph_converters;
+        let prism = unlabeled::from_string("[a-b, b-c, c-a, d-e, e-f, f-d, a-d, b-e, c-f]");
+        let k33 = unlabeled::from_string("[a-d, a-e, a-f, b-d, b-e, b-f, c-d, c-e, c-f]");
+        let shuffled = unlabeled::from_string("[f-b, b-e, e-f, c-a, a-d, d-c, f-a, b-c, e-d]");
+        assert!(par_is_isomorphic_to(&prism, &shuffled));
+        assert!(!par_is_isomorphic_to(&prism, &k33));
+        let edge = unlabeled::from_string("[a-b]");
+        assert!(!par_is_isomorphic_to(&prism, &edge));
+        assert!(par_is_isomorphic_to(
+            &CsrGraph::from_graph(&prism),
+            &shuffled
+        ));
+
+        let g1 = digraph_converters::unlabeled::from_string("[a>b, b>c]");
+        let g2 = digraph_converters::unlabeled::from_string("[x>z, y>x]");
+        let g3 = digraph_converters::unlabeled::from_string("[x>y, x>z]");
+        assert!(par_is_isomorphic_to(&g1, &g2));
+        assert!(!par_is_isomorphic_to(&g1, &g3));
     }
 }
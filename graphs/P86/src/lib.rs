@@ -1,29 +1,43 @@
-use graph::Graph;
+use graph::{GraphBase, Neighbors, UnderlyingGraph};
 use std::hash::Hash;
 
-pub fn color_nodes<T: Hash + Copy + Eq + Ord>(g: &Graph<T>) -> Vec<(T, u32)> {
-    let nodes = g.get_nodes_by_degree(true);
+/// Colors nodes so that adjacent nodes have different colors. Arc directions
+/// of digraphs are ignored, parallel edges count once and self-loops are
+/// ignored (a node cannot differ from itself).
+pub fn color_nodes<T, G>(g: &G) -> Vec<(T, u32)>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let g = UnderlyingGraph::new(g);
+    // nodes sorted according to decreasing degree
+    let degree = |v: &T| g.neighbors(v).iter().filter(|w| *w != v).count();
+    let mut nodes = g.node_values().into_owned();
+    nodes.sort_by(|a, b| degree(b).cmp(&degree(a)).then(a.cmp(b)));
     let mut colored_nodes = vec![];
     let mut color = 0u32;
     let mut res = vec![];
+    let adjacent = |v: &T, w: &T| v != w && g.neighbors(v).contains(w);
 
     // Welsh-Powell's algorithm
-    while colored_nodes.len() < g.size() {
+    while colored_nodes.len() < nodes.len() {
         for node in &nodes {
-            if colored_nodes.contains(node.get_value()) {
+            if colored_nodes.contains(node) {
                 continue;
             } else {
                 color += 1;
-                res.push((*node.get_value(), color));
-                colored_nodes.push(*node.get_value());
+                res.push((*node, color));
+                colored_nodes.push(*node);
 
-                let neighbors = node.adjacents();
+                // nodes not adjacent to any node of this color get it as well
+                let mut same_color = vec![*node];
                 for node2 in &nodes {
-                    if !colored_nodes.contains(node2.get_value())
-                        && !neighbors.contains(node2.get_value())
+                    if !colored_nodes.contains(node2)
+                        && same_color.iter().all(|v| !adjacent(v, node2))
                     {
-                        res.push((*node2.get_value(), color));
-                        colored_nodes.push(*node2.get_value());
+                        res.push((*node2, color));
+                        colored_nodes.push(*node2);
+                        same_color.push(*node2);
                     }
                 }
             }
@@ -46,4 +60,36 @@ mod tests {
         assert!(colored.contains(&('c', 3)));
         assert!(colored.contains(&('d', 2)));
     }
+
+    #[test]
+    fn test_color_nodes_not_adjacent_to_first() {
+        // c and d are not adjacent to a, but to each other
+        let g = unlabeled::from_string("[a-b, c-d]");
+        let colored = color_nodes(&g);
+        assert_eq!(colored, vec![('a', 1), ('c', 1), ('b', 2), ('d', 2)]);
+    }
+
+    #[test]
+    fn test_color_nodes_multigraph() {
+        let mut g = graph::Graph::new();
+        for v in "abc".chars() {
+            g.add_node(v);
+        }
+        for (v, w) in &[('a', 'b'), ('b', 'a'), ('b', 'b'), ('b', 'c')] {
+            g.add_edge(*v, *w);
+        }
+        let colored = color_nodes(&g);
+        assert_eq!(colored, vec![('b', 1), ('a', 2), ('c', 2)]);
+    }
+
+    #[test]
+    fn test_color_nodes_digraph() {
+        use P80::digraph_converters;
+        let g = digraph_converters::unlabeled::from_string("[a>b, b>c, c>a, d>a]");
+        let colored = color_nodes(&g);
+        assert!(colored.contains(&('a', 1)));
+        assert!(colored.contains(&('b', 2)));
+        assert!(colored.contains(&('c', 3)));
+        assert!(colored.contains(&('d', 2)));
+    }
 }
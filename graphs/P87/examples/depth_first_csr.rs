@@ -0,0 +1,15 @@
+use graph::CsrGraph;
+use P80::graph_converters::unlabeled;
+use P87::*;
+
+pub fn main() {
+    // a frozen copy of a graph
+    let g = CsrGraph::from_graph(&unlabeled::from_string("[a-b, b-c, e, a-c, a-d]"));
+    println!("{} nodes, {} edges", g.size(), g.edge_count());
+    println!("{:?}", nodes_by_depth_from(&g, 'd'));
+
+    // a digraph streamed from an edge list
+    let edges = "1 2\n2 3\n3 1\n3 4\n5 1\n";
+    let g = CsrGraph::<u32, i32>::read_edge_list(edges.as_bytes(), true).unwrap();
+    println!("{:?}", nodes_by_depth_from(&g, 1));
+}
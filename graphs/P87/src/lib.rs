@@ -1,12 +1,20 @@
-use graph::Graph;
+use graph::Neighbors;
 use std::hash::Hash;
 
-pub fn nodes_by_depth_from<T: Hash + Copy + Eq + Ord>(g: &Graph<T>, start: T) -> Vec<T> {
-    fn traverse<T: Hash + Copy + Eq + Ord>(g: &Graph<T>, v: T, visited: &mut Vec<T>) -> Vec<T> {
+pub fn nodes_by_depth_from<T, G>(g: &G, start: T) -> Vec<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    fn traverse<T, G>(g: &G, v: T, visited: &mut Vec<T>) -> Vec<T>
+    where
+        T: Hash + Copy + Eq + Ord,
+        G: Neighbors<T>,
+    {
         if !visited.contains(&v) {
             visited.push(v);
         }
-        let neighbors = g.get_node(&v).unwrap().adjacents();
+        let neighbors = g.neighbors(&v);
         let to_visit: Vec<&T> = neighbors.iter().filter(|&n| !visited.contains(n)).collect();
         for n in to_visit {
             for w in traverse(g, *n, visited) {
@@ -33,4 +41,16 @@ mod tests {
         let nodes = nodes_by_depth_from(&g, 'd');
         assert!(nodes == vec!['c', 'b', 'a', 'd'] || nodes == vec!['b', 'c', 'a', 'd']);
     }
+
+    #[test]
+    fn test_nodes_by_depth_from_csr() {
+        use graph::CsrGraph;
+        let g = CsrGraph::from_graph(&unlabeled::from_string("[a-b, b-c, e, a-c, a-d]"));
+        let nodes = nodes_by_depth_from(&g, 'd');
+        assert!(nodes == vec!['c', 'b', 'a', 'd'] || nodes == vec!['b', 'c', 'a', 'd']);
+
+        let input = "1 2\n2 3\n3 1\n3 4\n5 1\n";
+        let g = CsrGraph::<u32, i32>::read_edge_list(input.as_bytes(), true).unwrap();
+        assert_eq!(nodes_by_depth_from(&g, 1), vec![4, 3, 2, 1]);
+    }
 }
@@ -0,0 +1,18 @@
+use graph::ImplicitGraph;
+use P80::digraph_converters;
+use P80::graph_converters::unlabeled;
+use P88::*;
+
+pub fn main() {
+    // a digraph is split into its weakly connected components
+    let g = digraph_converters::unlabeled::from_string("[a>b, c>b, d, e>f, f>e]");
+    for g in split_graph(&g) {
+        println!("{:?}", unlabeled::to_term_form(&g));
+    }
+
+    // an implicit graph: numbers are linked if they differ by 3
+    let g = ImplicitGraph::new((0..7).collect(), false, |v: &u32| {
+        (0..7).filter(|w| *w + 3 == *v || *v + 3 == *w).collect()
+    });
+    println!("{} components", split_graph(&g).len());
+}
@@ -1,42 +1,33 @@
-use graph::Graph;
+use algebra::induced_subgraph;
+use graph::{Graph, GraphBase, Neighbors, UnderlyingGraph};
 use std::hash::Hash;
 use P87::nodes_by_depth_from;
 
-pub fn split_graph<T: Hash + Copy + Eq + Ord>(g: &Graph<T>) -> Vec<Graph<T>> {
+/// Splits a graph into its connected components. Arc directions of digraphs
+/// are ignored (i.e. the weakly connected components are returned), and
+/// parallel edges are merged.
+pub fn split_graph<T, G>(g: &G) -> Vec<Graph<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let g = UnderlyingGraph::new(g);
     let mut paths = Vec::<Vec<T>>::new();
-    let node_vals = g.get_node_values();
 
     // find all depth-first paths
-    for v in node_vals {
-        let is_visited = paths.iter().any(|path| path.contains(&v));
+    for v in g.node_values().iter() {
+        let is_visited = paths.iter().any(|path| path.contains(v));
         if !is_visited {
-            let path = nodes_by_depth_from(&g, v);
+            let path = nodes_by_depth_from(&g, *v);
             paths.push(path);
         }
     }
 
     // make partial graphs from the found paths
-    let mut res = vec![];
-    for path in paths {
-        let mut part_graph = Graph::<T>::new();
-
-        // register nodes
-        for v in &path {
-            part_graph.add_node(*v);
-        }
-
-        // register edges
-        for v in &path {
-            for adj in g.get_node(&v).unwrap().adjacents() {
-                if !part_graph.is_linked(*v, adj) {
-                    part_graph.add_edge(*v, adj);
-                }
-            }
-        }
-
-        res.push(part_graph);
-    }
-    res
+    paths
+        .iter()
+        .map(|path| induced_subgraph(&g, path))
+        .collect()
 }
 
 #[cfg(test)]
@@ -68,4 +59,18 @@ mod tests {
         ];
         assert!(expected.iter().all(|g| actual.contains(&g)));
     }
+
+    #[test]
+    fn test_split_digraph() {
+        use P80::digraph_converters;
+        let g = digraph_converters::unlabeled::from_string("[a>b, b>a, c>b, d, e>f]");
+        let actual = split_graph(&g);
+        let expected = [
+            unlabeled::from_string("[a-b, b-c]"),
+            unlabeled::from_string("[d]"),
+            unlabeled::from_string("[e-f]"),
+        ];
+        assert_eq!(actual.len(), 3);
+        assert!(expected.iter().all(|g| actual.contains(g)));
+    }
 }
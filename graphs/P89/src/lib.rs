@@ -1,11 +1,18 @@
-use graph::Graph;
+use graph::{Neighbors, UnderlyingGraph};
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::hash::Hash;
 use P88::split_graph;
 
-pub fn is_bipartite<T: Hash + Copy + Eq + Ord>(g: &Graph<T>) -> bool {
-    assert!(g.size() > 0, "graph must not be empty!");
+/// Checks if the nodes can be split into two sides so that every edge links
+/// nodes on different sides. Arc directions of digraphs are ignored.
+pub fn is_bipartite<T, G>(g: &G) -> bool
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    assert!(g.node_count() > 0, "graph must not be empty!");
+    let g = UnderlyingGraph::new(g);
 
     let mut colored = HashMap::<i8, HashSet<T>>::new();
     colored.insert(1, HashSet::<T>::new());
@@ -37,8 +44,8 @@ pub fn is_bipartite<T: Hash + Copy + Eq + Ord>(g: &Graph<T>) -> bool {
             -1
         };
 
-        let neighbors = g.get_node(&v).unwrap().adjacents();
-        for neighbor in neighbors {
+        let neighbors = g.neighbors(&v);
+        for &neighbor in neighbors.iter() {
             if colored.get(&color).unwrap().contains(&neighbor) {
                 // neighbor is on the same side. the graph is not a bipartite.
                 return false;
@@ -78,4 +85,13 @@ mod tests_undirected {
             true
         );
     }
+
+    #[test]
+    fn test_is_bipartite_digraph() {
+        use P80::digraph_converters;
+        let g = digraph_converters::unlabeled::from_string("[a>b, c>b, c>d, d>a]");
+        assert!(is_bipartite(&g));
+        let g = digraph_converters::unlabeled::from_string("[a>b, b>c, a>c]");
+        assert!(!is_bipartite(&g));
+    }
 }
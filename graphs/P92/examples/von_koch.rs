@@ -0,0 +1,19 @@
+use P80::graph_converters::unlabeled;
+use P92::*;
+
+pub fn main() {
+    let g = unlabeled::from_string("[d-a, a-g, a-b, b-e, b-c, e-f]");
+    let labeling = graceful_labeling(&g).unwrap();
+    let mut nodes: Vec<(char, usize)> = labeling.nodes.into_iter().collect();
+    nodes.sort();
+    println!("nodes: {:?}", nodes);
+    println!("edges: {:?}", labeling.edges);
+    println!("{} labelings in all", graceful_labelings(&g).len());
+
+    let g =
+        unlabeled::from_string("[a-i, a-h, a-g, a-b, a-c, c-f, c-d, d-k, c-e, e-q, q-m, q-n, n-p]");
+    let labeling = graceful_labeling(&g).unwrap();
+    let mut nodes: Vec<(char, usize)> = labeling.nodes.into_iter().collect();
+    nodes.sort();
+    println!("nodes: {:?}", nodes);
+}
@@ -0,0 +1,505 @@
+use graph::{Directed, Neighbors};
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+use P83::is_tree;
+
+/// A graceful labeling of a tree with n nodes: the nodes are labeled 1 to n so
+/// that the edges, labeled with the differences of the labels of their ends,
+/// are labeled 1 to n - 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Labeling<T>
+where
+    T: Hash + Eq,
+{
+    /// The label of each node.
+    pub nodes: HashMap<T, usize>,
+    /// Each edge as `(v, w, label)` with `v < w`, sorted by label.
+    pub edges: Vec<(T, T, usize)>,
+}
+
+/// Returns a graceful labeling of `tree`; `None` if it is not a tree (checked
+/// with P83's `is_tree()`). Every tree with up to 35 nodes is known to have
+/// one, and Von Koch conjectured that all trees have.
+///
+/// Nodes are labeled one by one in breadth-first order from a center of the
+/// tree, each with a label that gives the edge to its parent an unused
+/// difference. Symmetric labelings are pruned: the labeling with every label x
+/// replaced by n + 1 - x is graceful as well, and so is any labeling obtained
+/// by swapping isomorphic subtrees; only one of each is searched for.
+pub fn graceful_labeling<T, G>(tree: &G) -> Option<Labeling<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T> + Directed,
+{
+    if !is_tree(tree) {
+        return None;
+    }
+    let t = RootedTree::new(tree);
+    let mut found = vec![];
+    t.search(false, &mut found);
+    found.pop().map(|labels| t.to_labeling(&labels))
+}
+
+/// Returns all graceful labelings of `tree`, ordered by the labels of the
+/// nodes in sorted order; empty if it is not a tree.
+///
+/// Only labelings that are not symmetric to each other are searched for (see
+/// `graceful_labeling()`); the others are derived from them. Highly symmetric
+/// trees have many labelings, e.g. a star with n nodes has 2 (n - 1)!.
+pub fn graceful_labelings<T, G>(tree: &G) -> Vec<Labeling<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T> + Directed,
+{
+    if !is_tree(tree) {
+        return vec![];
+    }
+    let t = RootedTree::new(tree);
+    let mut found = vec![];
+    t.search(true, &mut found);
+    let mut all = BTreeSet::new();
+    for labels in found {
+        all.extend(t.symmetric(labels));
+    }
+    all.iter().map(|labels| t.to_labeling(labels)).collect()
+}
+
+// a tree rooted at a center, with node indices in sorted order of the values
+struct RootedTree<T> {
+    values: Vec<T>,
+    edges: Vec<(usize, usize)>,
+    parent: Vec<Option<usize>>,
+    // children sorted by the canonical forms of their subtrees
+    children: Vec<Vec<usize>>,
+    // breadth-first order from the root
+    order: Vec<usize>,
+    // the previous sibling with an isomorphic subtree, if any
+    twin: Vec<Option<usize>>,
+    // the other center of a bicentral tree whose halves are isomorphic
+    mirror: Option<usize>,
+    // the position of the last child in order; 0 (the root) if none
+    last_child: Vec<usize>,
+}
+
+// a partial labeling during the search
+struct State {
+    // by node; 0 if unlabeled
+    labels: Vec<usize>,
+    // the node of each label
+    nodes: Vec<Option<usize>>,
+    // whether each difference is taken by an edge
+    diffs: Vec<bool>,
+}
+
+impl<T: Hash + Copy + Eq + Ord> RootedTree<T> {
+    fn new<G: Neighbors<T>>(g: &G) -> Self {
+        let mut values = g.node_values().into_owned();
+        values.sort();
+        let n = values.len();
+        // arcs of an out-tree count as edges
+        let mut adjs: Vec<Vec<usize>> = vec![vec![]; n];
+        for (i, v) in values.iter().enumerate() {
+            for w in g.neighbors(v).iter() {
+                let j = values.binary_search(w).unwrap();
+                adjs[i].push(j);
+                adjs[j].push(i);
+            }
+        }
+        for a in adjs.iter_mut() {
+            a.sort();
+            a.dedup();
+        }
+        let edges = (0..n)
+            .flat_map(|i| {
+                adjs[i]
+                    .iter()
+                    .filter(move |j| i < **j)
+                    .map(move |j| (i, *j))
+            })
+            .collect();
+
+        // the centers are left after stripping leaves repeatedly
+        let mut degrees: Vec<usize> = adjs.iter().map(|a| a.len()).collect();
+        let mut leaves: Vec<usize> = (0..n).filter(|i| degrees[*i] <= 1).collect();
+        let mut remaining = n;
+        while remaining > 2 {
+            remaining -= leaves.len();
+            let mut next = vec![];
+            for i in leaves {
+                for j in &adjs[i] {
+                    degrees[*j] -= 1;
+                    if degrees[*j] == 1 {
+                        next.push(*j);
+                    }
+                }
+            }
+            leaves = next;
+        }
+        leaves.sort();
+        let root = leaves[0];
+
+        let mut parent = vec![None; n];
+        let mut order = vec![root];
+        let mut k = 0;
+        while k < order.len() {
+            let i = order[k];
+            for j in &adjs[i] {
+                if *j != root && parent[*j].is_none() {
+                    parent[*j] = Some(i);
+                    order.push(*j);
+                }
+            }
+            k += 1;
+        }
+        // AHU canonical forms, children first
+        let mut canon = vec![String::new(); n];
+        for i in order.iter().rev() {
+            let mut forms: Vec<&String> = adjs[*i]
+                .iter()
+                .filter(|j| parent[**j] == Some(*i))
+                .map(|j| &canon[*j])
+                .collect();
+            forms.sort();
+            canon[*i] = format!("({})", forms.into_iter().cloned().collect::<String>());
+        }
+        let children: Vec<Vec<usize>> = (0..n)
+            .map(|i| {
+                let mut c: Vec<usize> = adjs[i]
+                    .iter()
+                    .filter(|j| parent[**j] == Some(i))
+                    .copied()
+                    .collect();
+                c.sort_by(|a, b| canon[*a].cmp(&canon[*b]).then(a.cmp(b)));
+                c
+            })
+            .collect();
+        let mut twin = vec![None; n];
+        for c in &children {
+            for w in c.windows(2) {
+                if canon[w[0]] == canon[w[1]] {
+                    twin[w[1]] = Some(w[0]);
+                }
+            }
+        }
+        // the halves of a bicentral tree are swapped by an automorphism if the
+        // root without the other center looks like the other center
+        let mirror = if leaves.len() == 2 {
+            let other = leaves[1];
+            let mut forms: Vec<&String> = children[root]
+                .iter()
+                .filter(|j| **j != other)
+                .map(|j| &canon[*j])
+                .collect();
+            forms.sort();
+            let rest = format!("({})", forms.into_iter().cloned().collect::<String>());
+            if rest == canon[other] {
+                Some(other)
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+        // the breadth-first order follows the sorted children
+        order = vec![root];
+        let mut k = 0;
+        while k < order.len() {
+            let i = order[k];
+            order.extend(children[i].iter().copied());
+            k += 1;
+        }
+        let mut last_child = vec![0; n];
+        for (k, i) in order.iter().enumerate() {
+            if let Some(p) = parent[*i] {
+                last_child[p] = k;
+            }
+        }
+        RootedTree {
+            values,
+            edges,
+            parent,
+            children,
+            order,
+            twin,
+            mirror,
+            last_child,
+        }
+    }
+
+    // collects canonical labelings (indexed by node) in `found`, all of them or
+    // only the first one
+    fn search(&self, all: bool, found: &mut Vec<Vec<usize>>) {
+        let n = self.values.len();
+        let mut state = State {
+            labels: vec![0; n],
+            nodes: vec![None; n + 1],
+            diffs: vec![false; n],
+        };
+        self.extend(0, all, &mut state, found);
+    }
+
+    // labels the k-th node in order; returns true to stop
+    fn extend(&self, k: usize, all: bool, state: &mut State, found: &mut Vec<Vec<usize>>) -> bool {
+        let n = self.values.len();
+        if k == n {
+            found.push(state.labels.clone());
+            return !all;
+        }
+        let v = self.order[k];
+        // the root takes the smaller one of complementary labels
+        let max = if k == 0 { n - n / 2 } else { n };
+        // isomorphic siblings, and the halves of a symmetric tree, take
+        // increasing labels
+        let min = match (self.twin[v], self.mirror) {
+            (Some(s), _) => state.labels[s] + 1,
+            (None, Some(m)) if m == v => state.labels[self.order[0]] + 1,
+            _ => 1,
+        };
+        let parent_label = self.parent[v].map(|p| state.labels[p]);
+        let mut candidates: Vec<usize> = (min..=max)
+            .filter(|x| state.nodes[*x].is_none())
+            .filter(|x| match parent_label {
+                Some(l) => !state.diffs[diff(*x, l)],
+                None => true,
+            })
+            .collect();
+        // large differences are the hardest to place, so they go first
+        if let Some(l) = parent_label {
+            candidates.sort_by_key(|x| std::cmp::Reverse(diff(*x, l)));
+        }
+        for x in candidates {
+            let d = parent_label.map(|l| diff(x, l));
+            if let Some(d) = d {
+                state.diffs[d] = true;
+            }
+            state.nodes[x] = Some(v);
+            state.labels[v] = x;
+            if self.feasible(k, state) && self.extend(k + 1, all, state, found) {
+                return true;
+            }
+            state.nodes[x] = None;
+            if let Some(d) = d {
+                state.diffs[d] = false;
+            }
+        }
+        false
+    }
+
+    // checks whether every unused difference can still be given to an edge,
+    // after the first k + 1 nodes in order are labeled; the edge needs two
+    // free labels, or a free label next to a node with unlabeled children
+    fn feasible(&self, k: usize, state: &State) -> bool {
+        let n = self.values.len();
+        let unlabeled = n - k - 1;
+        let open = |x: usize| match state.nodes[x] {
+            Some(v) => self.last_child[v] > k,
+            None => false,
+        };
+        (1..n).filter(|d| !state.diffs[*d]).all(|d| {
+            (1..=n - d).any(|x| {
+                let y = x + d;
+                match (state.nodes[x].is_none(), state.nodes[y].is_none()) {
+                    (true, true) => unlabeled >= 2,
+                    (true, false) => open(y),
+                    (false, true) => open(x),
+                    (false, false) => false,
+                }
+            })
+        })
+    }
+
+    // all labelings symmetric to a canonical one
+    fn symmetric(&self, labels: Vec<usize>) -> Vec<Vec<usize>> {
+        let n = self.values.len();
+        let root = self.order[0];
+        let mut all = vec![labels];
+        if let Some(other) = self.mirror {
+            let half = self.preorder(root, Some(other));
+            let other_half = self.preorder(other, None);
+            let swapped: Vec<Vec<usize>> = all
+                .iter()
+                .map(|l| {
+                    let mut s = l.clone();
+                    for (a, b) in half.iter().zip(&other_half) {
+                        s[*a] = l[*b];
+                        s[*b] = l[*a];
+                    }
+                    s
+                })
+                .collect();
+            all.extend(swapped);
+        }
+        let complements: Vec<Vec<usize>> = all
+            .iter()
+            .map(|l| l.iter().map(|x| n + 1 - x).collect())
+            .collect();
+        all.extend(complements);
+        // permute isomorphic subtrees, from the root down
+        for v in &self.order {
+            let c = &self.children[*v];
+            let mut start = 0;
+            while start < c.len() {
+                let mut end = start + 1;
+                while end < c.len() && self.twin[c[end]] == Some(c[end - 1]) {
+                    end += 1;
+                }
+                if end - start > 1 {
+                    let subtrees: Vec<Vec<usize>> = c[start..end]
+                        .iter()
+                        .map(|j| self.preorder(*j, None))
+                        .collect();
+                    let mut next = vec![];
+                    for l in &all {
+                        for p in permutations(subtrees.len()) {
+                            let mut s = l.clone();
+                            for (to, from) in p.into_iter().enumerate() {
+                                for (a, b) in subtrees[to].iter().zip(&subtrees[from]) {
+                                    s[*a] = l[*b];
+                                }
+                            }
+                            next.push(s);
+                        }
+                    }
+                    all = next;
+                }
+                start = end;
+            }
+        }
+        all
+    }
+
+    // the nodes of the subtree of v in preorder, children sorted by their
+    // canonical forms, so that isomorphic subtrees match node by node
+    fn preorder(&self, v: usize, skip: Option<usize>) -> Vec<usize> {
+        let mut nodes = vec![];
+        let mut stack = vec![v];
+        while let Some(i) = stack.pop() {
+            nodes.push(i);
+            for j in self.children[i].iter().rev() {
+                if Some(*j) != skip {
+                    stack.push(*j);
+                }
+            }
+        }
+        nodes
+    }
+
+    fn to_labeling(&self, labels: &[usize]) -> Labeling<T> {
+        let mut edges: Vec<(T, T, usize)> = self
+            .edges
+            .iter()
+            .map(|(i, j)| {
+                (
+                    self.values[*i],
+                    self.values[*j],
+                    diff(labels[*i], labels[*j]),
+                )
+            })
+            .collect();
+        edges.sort_by_key(|(_, _, d)| *d);
+        Labeling {
+            nodes: self
+                .values
+                .iter()
+                .copied()
+                .zip(labels.iter().copied())
+                .collect(),
+            edges,
+        }
+    }
+}
+
+fn diff(a: usize, b: usize) -> usize {
+    a.max(b) - a.min(b)
+}
+
+// all permutations of 0..k
+fn permutations(k: usize) -> Vec<Vec<usize>> {
+    let mut all = vec![vec![]];
+    for i in 0..k {
+        all = all
+            .into_iter()
+            .flat_map(|p: Vec<usize>| {
+                (0..=i).map(move |pos| {
+                    let mut q = p.clone();
+                    q.insert(pos, i);
+                    q
+                })
+            })
+            .collect();
+    }
+    all
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use P80::graph_converters::unlabeled;
+
+    fn is_graceful(l: &Labeling<char>) -> bool {
+        let n = l.nodes.len();
+        let mut nodes: Vec<usize> = l.nodes.values().copied().collect();
+        nodes.sort();
+        let edges: Vec<usize> = l.edges.iter().map(|(_, _, d)| *d).collect();
+        nodes == (1..=n).collect::<Vec<_>>()
+            && edges == (1..n).collect::<Vec<_>>()
+            && l.edges
+                .iter()
+                .all(|(v, w, d)| diff(l.nodes[v], l.nodes[w]) == *d)
+    }
+
+    #[test]
+    fn test_graceful_labeling() {
+        // the example tree of P92
+        let g = unlabeled::from_string("[a-d, a-g, a-b, b-e, b-f, b-c, e-k, k-h, k-i, k-m]");
+        let l = graceful_labeling(&g).unwrap();
+        assert!(is_graceful(&l));
+        assert_eq!(l.nodes.len(), 11);
+
+        let g = unlabeled::from_string("[a]");
+        assert_eq!(graceful_labeling(&g).unwrap().nodes[&'a'], 1);
+        let g = unlabeled::from_string("[a-b, b-c, c-a]");
+        assert_eq!(graceful_labeling(&g), None);
+        let g = unlabeled::from_string("[a-b, c]");
+        assert_eq!(graceful_labeling(&g), None);
+    }
+
+    #[test]
+    fn test_graceful_labelings() {
+        // paths a-b-c and a-b-c-d
+        let all = graceful_labelings(&unlabeled::from_string("[a-b, b-c]"));
+        let nodes: Vec<Vec<usize>> = all
+            .iter()
+            .map(|l| "abc".chars().map(|v| l.nodes[&v]).collect())
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![vec![1, 3, 2], vec![2, 1, 3], vec![2, 3, 1], vec![3, 1, 2]]
+        );
+        assert_eq!(
+            graceful_labelings(&unlabeled::from_string("[a-b, b-c, c-d]")).len(),
+            4
+        );
+
+        // stars have 2 (n - 1)! labelings
+        let g = unlabeled::from_string("[a-b, a-c, a-d, a-e]");
+        assert_eq!(graceful_labelings(&g).len(), 48);
+
+        // a central tree with isomorphic subtrees
+        let g = unlabeled::from_string("[a-b, a-c, b-d, b-e, c-f, c-g]");
+        let all = graceful_labelings(&g);
+        assert!(all.iter().all(is_graceful));
+        assert_eq!(all.len(), 48);
+
+        // a bicentral tree (centers a and b) with isomorphic halves
+        let g = unlabeled::from_string("[a-b, a-c, a-d, b-e, b-f]");
+        assert!(RootedTree::new(&g).mirror.is_some());
+        let all = graceful_labelings(&g);
+        assert!(all.iter().all(is_graceful));
+        assert_eq!(all.len(), 48);
+        assert_eq!(
+            graceful_labelings(&unlabeled::from_string("[a-b, b-c, a-c]")),
+            vec![]
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use graph::Neighbors;
+use P94::*;
+
+pub fn main() {
+    // how many (non-isomorphic!) 3-regular graphs with 6 nodes are there?
+    for g in regular_graphs(6, 3) {
+        let mut edges: Vec<(u32, u32)> = g.get_edges().iter().map(|(v, w, _)| (*v, *w)).collect();
+        edges.sort();
+        println!("{:?}", edges);
+    }
+
+    // families of test graphs: the triangle-free 4-regular graphs with 8 nodes
+    let triangle_free = regular_graphs(8, 4).filter(|g| {
+        (1..=8).all(|v| {
+            let adj = g.neighbors(&v);
+            adj.iter()
+                .all(|w| g.neighbors(w).iter().all(|x| !adj.contains(x)))
+        })
+    });
+    println!(
+        "{} of {} 4-regular graphs with 8 nodes are triangle-free",
+        triangle_free.count(),
+        regular_graphs(8, 4).count()
+    );
+}
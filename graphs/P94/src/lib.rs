@@ -0,0 +1,198 @@
+use graph::Graph;
+use std::collections::HashSet;
+use P85::canonical_form;
+
+/// Returns an iterator over all K-regular simple graphs with N nodes, i.e. the
+/// graphs without self-loops or parallel edges where every node has degree K,
+/// one per isomorphism class. The nodes are numbered 1 to N. The empty graph
+/// is the only 0-regular graph with no nodes.
+///
+/// Graphs are built lazily by connecting the nodes in order to the later ones
+/// they still lack edges to. Later nodes with the same neighbors so far are
+/// interchangeable, so only the first ones of such a group are picked; the
+/// remaining duplicates are weeded out by their P85 canonical forms, which the
+/// iterator keeps for every graph it has yielded.
+pub fn regular_graphs(n: usize, k: usize) -> RegularGraphs {
+    let mut graphs = RegularGraphs {
+        n,
+        k,
+        adj: vec![vec![]; n],
+        stack: vec![],
+        seen: HashSet::new(),
+    };
+    if n == 0 && k == 0 {
+        // nothing to connect; the empty graph is yielded as it is
+        graphs.stack.push(Frame {
+            node: 0,
+            choices: vec![vec![]],
+            next: 0,
+        });
+    } else if k < n && (n * k) & 1 == 0 {
+        let frame = graphs.frame(0);
+        graphs.stack.push(frame);
+    }
+    graphs
+}
+
+/// The iterator returned by [`regular_graphs`].
+pub struct RegularGraphs {
+    n: usize,
+    k: usize,
+    // the neighbors (sorted) of each node, 0-based
+    adj: Vec<Vec<usize>>,
+    stack: Vec<Frame>,
+    seen: HashSet<Vec<Vec<usize>>>,
+}
+
+// the choices of later neighbors for a node, and the next one to try
+struct Frame {
+    node: usize,
+    choices: Vec<Vec<usize>>,
+    next: usize,
+}
+
+impl RegularGraphs {
+    fn frame(&self, node: usize) -> Frame {
+        let need = self.k - self.adj[node].len();
+        // the candidates grouped by their neighbors so far
+        let mut groups: Vec<Vec<usize>> = vec![];
+        for v in node + 1..self.n {
+            if self.adj[v].len() == self.k {
+                continue;
+            }
+            match groups.iter_mut().find(|g| self.adj[g[0]] == self.adj[v]) {
+                Some(g) => g.push(v),
+                None => groups.push(vec![v]),
+            }
+        }
+        let mut choices = vec![];
+        choose(&groups, need, &mut vec![], &mut choices);
+        Frame {
+            node,
+            choices,
+            next: 0,
+        }
+    }
+
+    fn connect(&mut self, v: usize, w: usize) {
+        self.adj[v].push(w);
+        self.adj[w].push(v);
+        self.adj[v].sort();
+        self.adj[w].sort();
+    }
+
+    fn disconnect(&mut self, v: usize, w: usize) {
+        self.adj[v].retain(|x| *x != w);
+        self.adj[w].retain(|x| *x != v);
+    }
+
+    fn to_graph(&self) -> Graph<u32> {
+        let mut g = Graph::new();
+        for v in 1..=self.n {
+            g.add_node(v as u32);
+        }
+        for (v, adj) in self.adj.iter().enumerate() {
+            for w in adj.iter().filter(|w| **w > v) {
+                g.add_edge(v as u32 + 1, *w as u32 + 1);
+            }
+        }
+        g
+    }
+}
+
+// every way to pick `need` nodes taking the first ones of each group
+fn choose(
+    groups: &[Vec<usize>],
+    need: usize,
+    picked: &mut Vec<usize>,
+    choices: &mut Vec<Vec<usize>>,
+) {
+    if need == 0 {
+        choices.push(picked.clone());
+        return;
+    }
+    if groups.is_empty() {
+        return;
+    }
+    for m in (0..=need.min(groups[0].len())).rev() {
+        picked.extend_from_slice(&groups[0][..m]);
+        choose(&groups[1..], need - m, picked, choices);
+        picked.truncate(picked.len() - m);
+    }
+}
+
+impl Iterator for RegularGraphs {
+    type Item = Graph<u32>;
+
+    fn next(&mut self) -> Option<Graph<u32>> {
+        loop {
+            let (node, undo, choice) = {
+                let frame = self.stack.last_mut()?;
+                let undo = if frame.next > 0 {
+                    frame.choices[frame.next - 1].clone()
+                } else {
+                    vec![]
+                };
+                let choice = frame.choices.get(frame.next).cloned();
+                frame.next += 1;
+                (frame.node, undo, choice)
+            };
+            for w in undo {
+                self.disconnect(node, w);
+            }
+            let choice = match choice {
+                Some(choice) => choice,
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+            for w in choice {
+                self.connect(node, w);
+            }
+            match (node + 1..self.n).find(|v| self.adj[*v].len() < self.k) {
+                Some(v) => {
+                    let frame = self.frame(v);
+                    self.stack.push(frame);
+                }
+                None => {
+                    let g = self.to_graph();
+                    if self.seen.insert(canonical_form(&g)) {
+                        return Some(g);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::{GraphBase, Neighbors};
+
+    #[test]
+    fn test_regular_graphs() {
+        // OEIS A008483, A005638 and A033483
+        let counts = |n: std::ops::Range<usize>, k: usize| -> Vec<usize> {
+            n.map(|n| regular_graphs(n, k).count()).collect()
+        };
+        assert_eq!(counts(3..11, 2), vec![1, 1, 1, 2, 2, 3, 4, 5]);
+        assert_eq!(counts(4..11, 3), vec![1, 0, 2, 0, 6, 0, 21]);
+        assert_eq!(counts(5..10, 4), vec![1, 1, 2, 6, 16]);
+
+        assert_eq!(counts(1..5, 0), vec![1, 1, 1, 1]);
+        assert_eq!(counts(1..5, 1), vec![0, 1, 0, 1]);
+        assert_eq!(regular_graphs(4, 4).count(), 0);
+        let empty: Vec<_> = regular_graphs(0, 0).collect();
+        assert_eq!(empty.len(), 1);
+        assert_eq!(empty[0].node_count(), 0);
+        assert_eq!(regular_graphs(0, 1).count(), 0);
+
+        for g in regular_graphs(8, 3) {
+            assert_eq!(g.node_count(), 8);
+            assert_eq!(g.get_edges().len(), 12);
+            assert!((1..=8).all(|v| g.degree(&v) == 3));
+        }
+    }
+}
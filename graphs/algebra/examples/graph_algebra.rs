@@ -0,0 +1,28 @@
+use algebra::*;
+use P80::graph_converters::unlabeled;
+
+pub fn main() {
+    let g = unlabeled::from_string("[a-b, b-c, c-d, d-a]");
+    println!("complement: {:?}", unlabeled::to_term_form(&complement(&g)));
+    println!(
+        "induced subgraph on [a, b, c]: {:?}",
+        unlabeled::to_term_form(&induced_subgraph(&g, &['a', 'b', 'c']))
+    );
+    println!(
+        "contract a-b: {:?}",
+        unlabeled::to_term_form(&contract_edge(&g, 'a', 'b'))
+    );
+    let line_edges: Vec<_> = edges(&line_graph(&g))
+        .into_iter()
+        .map(|(e1, e2, _)| (e1, e2))
+        .collect();
+    println!("line graph: {:?}", line_edges);
+
+    let k2 = unlabeled::from_string("[x-y]");
+    let product = cartesian_product(&g, &k2);
+    println!(
+        "cartesian product with [x-y]: {} nodes, {} edges",
+        product.size(),
+        edges(&product).len()
+    );
+}
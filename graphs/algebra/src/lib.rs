@@ -0,0 +1,376 @@
+use graph::{EdgeWeights, Graph, LabeledGraph};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// Returns all edges of a graph as `(v1, v2, label)` with `v1 < v2`, each edge once.
+pub fn edges<T, G>(g: &G) -> Vec<(T, T, Option<G::Weight>)>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T>,
+{
+    let mut values = g.node_values().into_owned();
+    values.sort();
+    let mut res = vec![];
+    for v in values {
+        for (adj, label) in g.neighbors_with_weight(&v) {
+            if v < adj {
+                res.push((v, adj, label));
+            }
+        }
+    }
+    res
+}
+
+/// Complement; two nodes are linked if and only if they are not linked in the given graph.
+pub fn complement<T, U>(g: &LabeledGraph<T, U>) -> Graph<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    let mut values = g.get_node_values();
+    values.sort();
+    let mut res = Graph::new();
+    for v in &values {
+        res.add_node(*v);
+    }
+    for (i, v1) in values.iter().enumerate() {
+        for v2 in &values[i + 1..] {
+            if !g.is_linked(*v1, *v2) {
+                res.add_edge(*v1, *v2);
+            }
+        }
+    }
+    res
+}
+
+/// Disjoint union; nodes of `g1` and `g2` are tagged with 0 and 1 respectively
+/// so that they never collide.
+pub fn disjoint_union<T, U>(
+    g1: &LabeledGraph<T, U>,
+    g2: &LabeledGraph<T, U>,
+) -> LabeledGraph<(usize, T), U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    let mut res = LabeledGraph::new();
+    for (tag, g) in [g1, g2].iter().enumerate() {
+        for v in g.get_node_values() {
+            res.add_node((tag, v));
+        }
+        for (v1, v2, label) in edges(g) {
+            res.add_edge_with_value((tag, v1), (tag, v2), label);
+        }
+    }
+    res
+}
+
+/// Union; the graph with the union of node sets and edge sets. If an edge is in
+/// both graphs, the label of `g1` is used.
+pub fn union<T, U>(g1: &LabeledGraph<T, U>, g2: &LabeledGraph<T, U>) -> LabeledGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    // LabeledGraph::clone() shares nodes with the original graph, so copy g1 via
+    // its induced subgraph on all nodes.
+    let mut res = induced_subgraph(g1, &g1.get_node_values());
+    for v in g2.get_node_values() {
+        if res.get_node(&v).is_none() {
+            res.add_node(v);
+        }
+    }
+    for (v1, v2, label) in edges(g2) {
+        if !res.is_linked(v1, v2) {
+            res.add_edge_with_value(v1, v2, label);
+        }
+    }
+    res
+}
+
+/// Intersection; the graph with the common nodes and the common edges. Labels
+/// are taken from `g1`.
+pub fn intersection<T, U>(g1: &LabeledGraph<T, U>, g2: &LabeledGraph<T, U>) -> LabeledGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    let mut res = LabeledGraph::new();
+    for v in g1.get_node_values() {
+        if g2.get_node(&v).is_some() {
+            res.add_node(v);
+        }
+    }
+    for (v1, v2, label) in edges(g1) {
+        if g2.is_linked(v1, v2) {
+            res.add_edge_with_value(v1, v2, label);
+        }
+    }
+    res
+}
+
+/// Induced subgraph; the given nodes and all edges between them.
+/// Nodes that are not in the graph are ignored. Any undirected graph can be
+/// given, e.g. the underlying graph of a digraph.
+pub fn induced_subgraph<T, G>(g: &G, nodes: &[T]) -> LabeledGraph<T, G::Weight>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T>,
+{
+    let nodes: HashSet<T> = nodes.iter().filter(|v| g.contains(v)).copied().collect();
+    let mut res = LabeledGraph::new();
+    for v in &nodes {
+        res.add_node(*v);
+    }
+    for (v1, v2, label) in edges(g) {
+        if nodes.contains(&v1) && nodes.contains(&v2) {
+            res.add_edge_with_value(v1, v2, label);
+        }
+    }
+    res
+}
+
+/// Edge-induced subgraph; the given edges and their end nodes.
+/// Edges that are not in the graph are ignored.
+pub fn edge_subgraph<T, U>(g: &LabeledGraph<T, U>, edge_list: &[(T, T)]) -> LabeledGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    let keys: HashSet<(T, T)> = edge_list.iter().map(|&(a, b)| ordered(a, b)).collect();
+    let mut res = LabeledGraph::new();
+    for (v1, v2, label) in edges(g) {
+        if keys.contains(&(v1, v2)) {
+            for v in &[v1, v2] {
+                if res.get_node(v).is_none() {
+                    res.add_node(*v);
+                }
+            }
+            res.add_edge_with_value(v1, v2, label);
+        }
+    }
+    res
+}
+
+/// Line graph; each edge `(v1, v2)` (with `v1 < v2`) of the given graph becomes a node,
+/// and two nodes are linked if the edges share an end node.
+pub fn line_graph<T, U>(g: &LabeledGraph<T, U>) -> Graph<(T, T)>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    let es: Vec<(T, T)> = edges(g).into_iter().map(|(a, b, _)| (a, b)).collect();
+    let mut res = Graph::new();
+    for e in &es {
+        res.add_node(*e);
+    }
+    for (i, e1) in es.iter().enumerate() {
+        for e2 in &es[i + 1..] {
+            if e1.0 == e2.0 || e1.0 == e2.1 || e1.1 == e2.0 || e1.1 == e2.1 {
+                res.add_edge(*e1, *e2);
+            }
+        }
+    }
+    res
+}
+
+/// Cartesian product; `(u1, v1)` and `(u2, v2)` are linked if
+/// `u1 == u2` and `v1 - v2`, or `u1 - u2` and `v1 == v2`.
+pub fn cartesian_product<T, S>(g1: &Graph<T>, g2: &Graph<S>) -> Graph<(T, S)>
+where
+    T: Hash + Copy + Eq + Ord,
+    S: Hash + Copy + Eq + Ord,
+{
+    product(g1, g2, |same1, linked1, same2, linked2| {
+        (same1 && linked2) || (linked1 && same2)
+    })
+}
+
+/// Tensor (categorical) product; `(u1, v1)` and `(u2, v2)` are linked if
+/// `u1 - u2` and `v1 - v2`.
+pub fn tensor_product<T, S>(g1: &Graph<T>, g2: &Graph<S>) -> Graph<(T, S)>
+where
+    T: Hash + Copy + Eq + Ord,
+    S: Hash + Copy + Eq + Ord,
+{
+    product(g1, g2, |_, linked1, _, linked2| linked1 && linked2)
+}
+
+/// Strong product; the union of the cartesian product and the tensor product.
+pub fn strong_product<T, S>(g1: &Graph<T>, g2: &Graph<S>) -> Graph<(T, S)>
+where
+    T: Hash + Copy + Eq + Ord,
+    S: Hash + Copy + Eq + Ord,
+{
+    product(g1, g2, |same1, linked1, same2, linked2| {
+        (same1 && linked2) || (linked1 && same2) || (linked1 && linked2)
+    })
+}
+
+/// Contracts the edge between `v1` and `v2`; `v2` is merged into `v1` and the edges
+/// of `v2` are reattached to `v1`. Self-loops and parallel edges are dropped
+/// (the label of the edge that was attached to `v1` first wins).
+pub fn contract_edge<T, U>(g: &LabeledGraph<T, U>, v1: T, v2: T) -> LabeledGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    assert!(g.is_linked(v1, v2), "nodes must be linked");
+    let merged = |v: T| if v == v2 { v1 } else { v };
+    let mut res = LabeledGraph::new();
+    for v in g.get_node_values() {
+        if v != v2 {
+            res.add_node(v);
+        }
+    }
+    // edges attached to v1 go first so that their labels are kept
+    let (mut es, others): (Vec<_>, Vec<_>) = edges(g)
+        .into_iter()
+        .partition(|(a, b, _)| *a == v1 || *b == v1);
+    es.extend(others);
+    for (a, b, label) in es {
+        let (a, b) = (merged(a), merged(b));
+        if a != b && !res.is_linked(a, b) {
+            res.add_edge_with_value(a, b, label);
+        }
+    }
+    res
+}
+
+fn product<T, S, F>(g1: &Graph<T>, g2: &Graph<S>, is_linked: F) -> Graph<(T, S)>
+where
+    T: Hash + Copy + Eq + Ord,
+    S: Hash + Copy + Eq + Ord,
+    F: Fn(bool, bool, bool, bool) -> bool,
+{
+    let mut nodes = vec![];
+    let mut values1 = g1.get_node_values();
+    values1.sort();
+    let mut values2 = g2.get_node_values();
+    values2.sort();
+    for u in &values1 {
+        for v in &values2 {
+            nodes.push((*u, *v));
+        }
+    }
+    let mut res = Graph::new();
+    for n in &nodes {
+        res.add_node(*n);
+    }
+    for (i, (u1, v1)) in nodes.iter().enumerate() {
+        for (u2, v2) in &nodes[i + 1..] {
+            if is_linked(
+                u1 == u2,
+                g1.is_linked(*u1, *u2),
+                v1 == v2,
+                g2.is_linked(*v1, *v2),
+            ) {
+                res.add_edge((*u1, *v1), (*u2, *v2));
+            }
+        }
+    }
+    res
+}
+
+fn ordered<T: Ord>(a: T, b: T) -> (T, T) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use P80::graph_converters::{labeled, unlabeled};
+
+    #[test]
+    fn test_complement() {
+        let g = unlabeled::from_string("[a-b, b-c, d]");
+        assert_eq!(
+            complement(&g),
+            unlabeled::from_string("[a-c, a-d, b-d, c-d]")
+        );
+    }
+
+    #[test]
+    fn test_union_and_intersection() {
+        let g1 = labeled::from_string("[a-b/1, b-c/2]");
+        let g2 = labeled::from_string("[b-c/5, c-d/3, e]");
+        assert_eq!(
+            union(&g1, &g2),
+            labeled::from_string("[a-b/1, b-c/2, c-d/3, e]")
+        );
+        assert_eq!(intersection(&g1, &g2), labeled::from_string("[b-c/2]"));
+
+        let g = disjoint_union(&g1, &g2);
+        assert_eq!(g.size(), 7);
+        assert!(g.is_linked((0, 'b'), (0, 'c')));
+        assert!(g.is_linked((1, 'b'), (1, 'c')));
+        assert!(!g.is_linked((0, 'b'), (1, 'c')));
+    }
+
+    #[test]
+    fn test_subgraphs() {
+        let g = labeled::from_string("[a-b/1, b-c/2, c-a/3, c-d/4]");
+        assert_eq!(
+            induced_subgraph(&g, &['a', 'c', 'd', 'x']),
+            labeled::from_string("[a-c/3, c-d/4]")
+        );
+        // any graph giving its edge labels, e.g. the underlying graph of a digraph
+        let d = P80::digraph_converters::unlabeled::from_string("[a>b, b>a, b>c, c>d]");
+        assert_eq!(
+            induced_subgraph(&graph::UnderlyingGraph::new(&d), &['a', 'b', 'c']),
+            unlabeled::from_string("[a-b, b-c]")
+        );
+        assert_eq!(
+            edge_subgraph(&g, &[('b', 'a'), ('c', 'd')]),
+            labeled::from_string("[a-b/1, c-d/4]")
+        );
+    }
+
+    #[test]
+    fn test_line_graph() {
+        let g = unlabeled::from_string("[a-b, b-c, c-d]");
+        let lg = line_graph(&g);
+        assert_eq!(lg.size(), 3);
+        assert!(lg.is_linked(('a', 'b'), ('b', 'c')));
+        assert!(lg.is_linked(('b', 'c'), ('c', 'd')));
+        assert!(!lg.is_linked(('a', 'b'), ('c', 'd')));
+    }
+
+    #[test]
+    fn test_products() {
+        let g1 = unlabeled::from_string("[a-b]");
+        let g2 = unlabeled::from_string("[x-y, y-z]");
+        let edge_count =
+            |g: &Graph<(char, char)>| g.get_nodes().iter().map(|n| n.degree()).sum::<usize>() / 2;
+
+        let g = cartesian_product(&g1, &g2);
+        assert_eq!(g.size(), 6);
+        assert_eq!(edge_count(&g), 7);
+        assert!(g.is_linked(('a', 'x'), ('b', 'x')));
+        assert!(g.is_linked(('a', 'x'), ('a', 'y')));
+
+        let g = tensor_product(&g1, &g2);
+        assert_eq!(edge_count(&g), 4);
+        assert!(g.is_linked(('a', 'x'), ('b', 'y')));
+
+        let g = strong_product(&g1, &g2);
+        assert_eq!(edge_count(&g), 11);
+    }
+
+    #[test]
+    fn test_contract_edge() {
+        let g = labeled::from_string("[a-b/1, b-c/2, a-c/3, c-d/4]");
+        assert_eq!(
+            contract_edge(&g, 'a', 'b'),
+            labeled::from_string("[a-c/3, c-d/4]")
+        );
+        assert_eq!(
+            contract_edge(&g, 'c', 'd'),
+            labeled::from_string("[a-b/1, b-c/2, a-c/3]")
+        );
+    }
+}
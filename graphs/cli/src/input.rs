@@ -0,0 +1,311 @@
+// Reads graphs written in the forms of P80. Nodes are single characters and
+// labels are integers.
+//
+// - human-friendly form: `[b-c, f-c, g-h, d]`, `[p>q/9, m>q/7, k]`
+// - graph-term form: `([b, c, d], [(b, c), (c, d, 5)])`
+// - adjacency-list form: `[(b, [c]), (c, [b, d])]`, `[(m, [(p, 5)]), (p, [])]`
+// - adjacency matrix: a line of node names followed by one line of integers per row
+//
+// Quotes are ignored, so the debug output of the P80 converters (e.g. `('b', ['c'])`)
+// can be read as is. Only the human-friendly form tells the direction of edges;
+// the other forms are read as digraphs when `directed` is set (an asymmetric
+// adjacency matrix is always read as a digraph).
+
+/// Nodes and edges read from the input
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphData {
+    pub directed: bool,
+    pub nodes: Vec<char>,
+    pub edges: Vec<(char, char, Option<i32>)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Term {
+    Atom(String),
+    List(Vec<Term>),
+    Tuple(Vec<Term>),
+}
+
+pub fn parse(s: &str, directed: bool) -> Result<GraphData, String> {
+    let s = s.trim();
+    let data = if s.starts_with('[') || s.starts_with('(') {
+        let chars: Vec<char> = s.chars().filter(|c| *c != '\'' && *c != '"').collect();
+        let mut pos = 0;
+        let term = parse_term(&chars, &mut pos)?;
+        if chars[pos..].iter().any(|c| !c.is_whitespace()) {
+            return Err(format!("unexpected input after position {}", pos));
+        }
+        from_term(&term, directed)?
+    } else {
+        from_matrix(s, directed)?
+    };
+    for (v1, v2, _) in &data.edges {
+        for v in &[v1, v2] {
+            if !data.nodes.contains(v) {
+                return Err(format!("node {} is not declared", v));
+            }
+        }
+    }
+    Ok(data)
+}
+
+fn parse_term(chars: &[char], pos: &mut usize) -> Result<Term, String> {
+    while *pos < chars.len() && chars[*pos].is_whitespace() {
+        *pos += 1;
+    }
+    match chars.get(*pos) {
+        Some('[') => Ok(Term::List(parse_seq(chars, pos, ']')?)),
+        Some('(') => Ok(Term::Tuple(parse_seq(chars, pos, ')')?)),
+        _ => {
+            let start = *pos;
+            while *pos < chars.len() && !"[](),".contains(chars[*pos]) {
+                *pos += 1;
+            }
+            let atom: String = chars[start..*pos].iter().collect();
+            Ok(Term::Atom(atom.trim().to_string()))
+        }
+    }
+}
+
+// parses comma separated terms after an opening bracket
+fn parse_seq(chars: &[char], pos: &mut usize, close: char) -> Result<Vec<Term>, String> {
+    *pos += 1;
+    let mut items = vec![];
+    loop {
+        let item = parse_term(chars, pos)?;
+        match chars.get(*pos) {
+            Some(',') => {
+                items.push(item);
+                *pos += 1;
+            }
+            Some(c) if *c == close => {
+                if item != Term::Atom(String::new()) || !items.is_empty() {
+                    items.push(item);
+                }
+                *pos += 1;
+                return Ok(items);
+            }
+            Some(c) => return Err(format!("unexpected '{}' at position {}", c, pos)),
+            None => return Err(format!("missing '{}'", close)),
+        }
+    }
+}
+
+fn node(term: &Term) -> Result<char, String> {
+    match term {
+        Term::Atom(s) if s.chars().count() == 1 => Ok(s.chars().next().unwrap()),
+        _ => Err(format!(
+            "invalid node: {:?} (must be a single character)",
+            term
+        )),
+    }
+}
+
+fn label(term: &Term) -> Result<i32, String> {
+    match term {
+        Term::Atom(s) => s.parse().map_err(|_| format!("invalid label: {}", s)),
+        _ => Err(format!("invalid label: {:?}", term)),
+    }
+}
+
+fn from_term(term: &Term, directed: bool) -> Result<GraphData, String> {
+    match term {
+        Term::List(items) if items.iter().all(|t| matches!(t, Term::Atom(_))) => {
+            from_human_friendly(items)
+        }
+        Term::List(items) => from_adjacency_list(items, directed),
+        Term::Tuple(items) => match items.as_slice() {
+            [Term::List(nodes), Term::List(edges)] => from_graph_term(nodes, edges, directed),
+            _ => Err("graph-term form must be a pair of node list and edge list".to_string()),
+        },
+        Term::Atom(_) => Err("unknown graph form".to_string()),
+    }
+}
+
+fn from_human_friendly(items: &[Term]) -> Result<GraphData, String> {
+    let mut data = GraphData {
+        directed: false,
+        nodes: vec![],
+        edges: vec![],
+    };
+    let mut seps = vec![];
+    for item in items {
+        let s = match item {
+            Term::Atom(s) => s,
+            _ => unreachable!(),
+        };
+        let (edge, label) = match s.find('/') {
+            Some(i) => (
+                &s[..i],
+                Some(self::label(&Term::Atom(s[i + 1..].to_string()))?),
+            ),
+            None => (&s[..], None),
+        };
+        match edge.find(&['-', '>'][..]) {
+            Some(i) => {
+                let v1 = node(&Term::Atom(edge[..i].trim().to_string()))?;
+                let v2 = node(&Term::Atom(edge[i + 1..].trim().to_string()))?;
+                seps.push(&edge[i..=i]);
+                add_node(&mut data.nodes, v1);
+                add_node(&mut data.nodes, v2);
+                data.edges.push((v1, v2, label));
+            }
+            None => add_node(&mut data.nodes, node(item)?),
+        }
+    }
+    data.directed = seps.contains(&">");
+    if data.directed && seps.contains(&"-") {
+        return Err("edges (-) and arcs (>) cannot be mixed".to_string());
+    }
+    Ok(data)
+}
+
+fn from_graph_term(nodes: &[Term], edges: &[Term], directed: bool) -> Result<GraphData, String> {
+    let mut data = GraphData {
+        directed,
+        nodes: vec![],
+        edges: vec![],
+    };
+    for v in nodes {
+        add_node(&mut data.nodes, node(v)?);
+    }
+    for edge in edges {
+        match edge {
+            Term::Tuple(e) if e.len() == 2 => data.edges.push((node(&e[0])?, node(&e[1])?, None)),
+            Term::Tuple(e) if e.len() == 3 => {
+                data.edges
+                    .push((node(&e[0])?, node(&e[1])?, Some(label(&e[2])?)))
+            }
+            _ => return Err(format!("invalid edge: {:?}", edge)),
+        }
+    }
+    Ok(data)
+}
+
+fn from_adjacency_list(items: &[Term], directed: bool) -> Result<GraphData, String> {
+    let mut data = GraphData {
+        directed,
+        nodes: vec![],
+        edges: vec![],
+    };
+    for item in items {
+        let (v, adjs) = match item {
+            Term::Tuple(pair) => match pair.as_slice() {
+                [v, Term::List(adjs)] => (node(v)?, adjs),
+                _ => return Err(format!("invalid adjacency list: {:?}", item)),
+            },
+            _ => return Err(format!("invalid adjacency list: {:?}", item)),
+        };
+        add_node(&mut data.nodes, v);
+        for adj in adjs {
+            let (w, l) = match adj {
+                Term::Tuple(pair) if pair.len() == 2 => (node(&pair[0])?, Some(label(&pair[1])?)),
+                _ => (node(adj)?, None),
+            };
+            // an undirected edge is listed by both of its end nodes
+            let edge = if directed || v <= w {
+                (v, w, l)
+            } else {
+                (w, v, l)
+            };
+            if directed || !data.edges.contains(&edge) {
+                data.edges.push(edge);
+            }
+        }
+    }
+    Ok(data)
+}
+
+fn from_matrix(s: &str, directed: bool) -> Result<GraphData, String> {
+    let mut lines = s.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
+    let nodes = match lines.next() {
+        Some(header) => header
+            .split_whitespace()
+            .map(|v| node(&Term::Atom(v.to_string())))
+            .collect::<Result<Vec<char>, String>>()?,
+        None => return Err("empty input".to_string()),
+    };
+    let mut matrix = vec![];
+    for line in lines {
+        let row = line
+            .split_whitespace()
+            .map(|w| label(&Term::Atom(w.to_string())))
+            .collect::<Result<Vec<i32>, String>>()?;
+        if row.len() != nodes.len() {
+            return Err(format!("each row must have {} columns", nodes.len()));
+        }
+        matrix.push(row);
+    }
+    if matrix.len() != nodes.len() {
+        return Err(format!("matrix must have {} rows", nodes.len()));
+    }
+    let n = nodes.len();
+    let symmetric = (0..n).all(|i| (0..n).all(|j| matrix[i][j] == matrix[j][i]));
+    let directed = directed || !symmetric;
+    let mut edges = vec![];
+    for i in 0..n {
+        for j in 0..n {
+            if matrix[i][j] != 0 && (directed || i <= j) {
+                edges.push((nodes[i], nodes[j], Some(matrix[i][j])));
+            }
+        }
+    }
+    Ok(GraphData {
+        directed,
+        nodes,
+        edges,
+    })
+}
+
+fn add_node(nodes: &mut Vec<char>, v: char) {
+    if !nodes.contains(&v) {
+        nodes.push(v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_human_friendly() {
+        let data = parse("[b-c, f-c, d]", false).unwrap();
+        assert!(!data.directed);
+        assert_eq!(data.nodes, vec!['b', 'c', 'f', 'd']);
+        assert_eq!(data.edges, vec![('b', 'c', None), ('f', 'c', None)]);
+
+        let data = parse("[p>q/9, k]", false).unwrap();
+        assert!(data.directed);
+        assert_eq!(data.edges, vec![('p', 'q', Some(9))]);
+
+        assert!(parse("[a-b, b>c]", false).is_err());
+        assert!(parse("[ab-c]", false).is_err());
+        assert_eq!(parse("[]", false).unwrap().nodes, vec![]);
+    }
+
+    #[test]
+    fn test_parse_term_forms() {
+        let data = parse("(['b', 'c', 'd'], [('b', 'c'), ('c', 'd', 5)])", true).unwrap();
+        assert!(data.directed);
+        assert_eq!(data.edges, vec![('b', 'c', None), ('c', 'd', Some(5))]);
+
+        let data = parse("[('m', [('p', 5)]), ('p', [('m', 5)]), ('k', [])]", false).unwrap();
+        assert_eq!(data.nodes, vec!['m', 'p', 'k']);
+        assert_eq!(data.edges, vec![('m', 'p', Some(5))]);
+
+        assert!(parse("([a], [(a, b)])", false).is_err());
+    }
+
+    #[test]
+    fn test_parse_matrix() {
+        let data = parse("a b c\n0 2 0\n2 0 3\n0 3 0\n", false).unwrap();
+        assert!(!data.directed);
+        assert_eq!(data.edges, vec![('a', 'b', Some(2)), ('b', 'c', Some(3))]);
+
+        let data = parse("a b\n0 1\n0 0", false).unwrap();
+        assert!(data.directed);
+        assert_eq!(data.edges, vec![('a', 'b', Some(1))]);
+
+        assert!(parse("a b\n0 1", false).is_err());
+    }
+}
@@ -0,0 +1,513 @@
+use graph::{LabeledDigraph, LabeledGraph};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use P81::PathFinder;
+use P82::CycleFinder;
+
+pub mod input;
+
+pub const USAGE: &str = "usage: r99-graph [--json] [--directed] [FILE] <command> [args...]
+
+Reads a graph in one of the P80 forms from FILE (or stdin if FILE is omitted or '-').
+
+commands:
+  paths <a> <b>        all acyclic paths from a to b (P81)
+  cycles <a>           all cycles starting at a (P82)
+  mst                  minimal spanning trees; unlabeled edges weigh 1 (P84)
+  color                node coloring by Welsh-Powell; arcs are taken as edges (P86)
+  components           connected components; strongly connected for digraphs (P88)
+  bipartite            whether the graph is bipartite; arcs are taken as edges (P89)
+  isomorphic <FILE2>   whether the graph is isomorphic to the graph in FILE2 (P85)
+  degree               node degrees in decreasing order
+
+options:
+  --json               print results as JSON
+  --directed           read graph-term, adjacency-list and matrix forms as digraphs";
+
+/// Subcommands
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Paths(char, char),
+    Cycles(char),
+    Mst,
+    Color,
+    Components,
+    Bipartite,
+    Isomorphic(String),
+    Degree,
+}
+
+/// Parsed command line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub json: bool,
+    pub directed: bool,
+    /// None for stdin
+    pub file: Option<String>,
+    pub command: Command,
+}
+
+const COMMANDS: [&str; 8] = [
+    "paths",
+    "cycles",
+    "mst",
+    "color",
+    "components",
+    "bipartite",
+    "isomorphic",
+    "degree",
+];
+
+/// Parses command line arguments (without the program name).
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut json = false;
+    let mut directed = false;
+    let mut file = None;
+    let mut rest = vec![];
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--directed" => directed = true,
+            a if a.starts_with("--") => return Err(format!("unknown option: {}", a)),
+            a => rest.push(a),
+        }
+    }
+    // the first argument is the input file unless it is a command
+    if !rest.is_empty() && !COMMANDS.contains(&rest[0]) {
+        if rest[0] != "-" {
+            file = Some(rest[0].to_string());
+        }
+        rest.remove(0);
+    }
+    let node = |s: &str| -> Result<char, String> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Ok(c),
+            _ => Err(format!("invalid node: {} (must be a single character)", s)),
+        }
+    };
+    let command = match rest.as_slice() {
+        ["paths", a, b] => Command::Paths(node(a)?, node(b)?),
+        ["cycles", a] => Command::Cycles(node(a)?),
+        ["mst"] => Command::Mst,
+        ["color"] => Command::Color,
+        ["components"] => Command::Components,
+        ["bipartite"] => Command::Bipartite,
+        ["isomorphic", other] => Command::Isomorphic(other.to_string()),
+        ["degree"] => Command::Degree,
+        [] => return Err("no command given".to_string()),
+        [cmd, ..] if COMMANDS.contains(cmd) => {
+            return Err(format!("wrong number of arguments for {}", cmd))
+        }
+        [cmd, ..] => return Err(format!("unknown command: {}", cmd)),
+    };
+    Ok(Options {
+        json,
+        directed,
+        file,
+        command,
+    })
+}
+
+/// A graph read from the input
+pub enum Input {
+    Graph(LabeledGraph<char, i32>),
+    Digraph(LabeledDigraph<char, i32>),
+}
+
+impl Input {
+    /// Reads a graph in one of the P80 forms.
+    pub fn read(s: &str, directed: bool) -> Result<Input, String> {
+        let data = input::parse(s, directed)?;
+        if data.directed {
+            let mut g = LabeledDigraph::new();
+            for v in &data.nodes {
+                g.add_node(*v);
+            }
+            for (v1, v2, l) in &data.edges {
+                g.add_arc_with_value(*v1, *v2, *l);
+            }
+            Ok(Input::Digraph(g))
+        } else {
+            let mut g = LabeledGraph::new();
+            for v in &data.nodes {
+                g.add_node(*v);
+            }
+            for (v1, v2, l) in &data.edges {
+                g.add_edge_with_value(*v1, *v2, *l);
+            }
+            Ok(Input::Graph(g))
+        }
+    }
+
+    fn contains(&self, v: char) -> bool {
+        match self {
+            Input::Graph(g) => g.get_node(&v).is_some(),
+            Input::Digraph(g) => g.get_node(&v).is_some(),
+        }
+    }
+
+    fn undirected(&self, command: &str) -> Result<&LabeledGraph<char, i32>, String> {
+        match self {
+            Input::Graph(g) => Ok(g),
+            Input::Digraph(_) => Err(format!("{} requires an undirected graph", command)),
+        }
+    }
+}
+
+/// Runs a command on the input graph. `other` is the graph to be compared by `isomorphic`.
+pub fn execute(
+    command: &Command,
+    g: &Input,
+    other: Option<&Input>,
+    json: bool,
+) -> Result<String, String> {
+    let check_node = |v: char| {
+        if g.contains(v) {
+            Ok(())
+        } else {
+            Err(format!("node {} is not in the graph", v))
+        }
+    };
+    let res = match command {
+        Command::Paths(a, b) => {
+            check_node(*a)?;
+            check_node(*b)?;
+            let paths = match g {
+                Input::Graph(g) => g.find_paths(*a, *b),
+                Input::Digraph(g) => g.find_paths(*a, *b),
+            };
+            render_paths(&paths, json)
+        }
+        Command::Cycles(a) => {
+            check_node(*a)?;
+            let cycles = match g {
+                Input::Graph(g) => g.find_cycles(*a),
+                Input::Digraph(g) => g.find_cycles(*a),
+            };
+            render_paths(&cycles, json)
+        }
+        Command::Mst => {
+            let g = with_unit_weight(g.undirected("mst")?);
+            let trees = P84::minimal_spanning_trees(&g);
+            if trees.is_empty() && g.size() > 0 {
+                return Err("the graph is not connected".to_string());
+            }
+            let mut trees: Vec<_> = trees
+                .iter()
+                .map(|t| (sorted_edges(t), P84::label_sum(t)))
+                .collect();
+            trees.sort();
+            if json {
+                let trees: Vec<Value> = trees
+                    .iter()
+                    .map(|(edges, sum)| {
+                        let edges: Vec<Value> = edges
+                            .iter()
+                            .map(|(a, b, l)| json!({"source": a, "target": b, "label": l}))
+                            .collect();
+                        json!({"edges": edges, "label_sum": sum})
+                    })
+                    .collect();
+                json!(trees).to_string()
+            } else {
+                trees
+                    .iter()
+                    .map(|(edges, sum)| {
+                        let edges: Vec<String> = edges
+                            .iter()
+                            .map(|(a, b, l)| format!("{}-{}/{}", a, b, l))
+                            .collect();
+                        format!("[{}] (label sum: {})", edges.join(", "), sum)
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            }
+        }
+        Command::Color => {
+            let mut colors = match g {
+                Input::Graph(g) => P86::color_nodes(g),
+                Input::Digraph(g) => P86::color_nodes(g),
+            };
+            colors.sort();
+            if json {
+                let map: BTreeMap<String, u32> =
+                    colors.iter().map(|(v, c)| (v.to_string(), *c)).collect();
+                json!(map).to_string()
+            } else {
+                colors
+                    .iter()
+                    .map(|(v, c)| format!("{}: {}", v, c))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            }
+        }
+        Command::Components => {
+            let mut comps: Vec<Vec<char>> = match g {
+                Input::Graph(g) => P88::split_graph(g)
+                    .iter()
+                    .map(|c| {
+                        let mut values = c.get_node_values();
+                        values.sort();
+                        values
+                    })
+                    .collect(),
+                Input::Digraph(g) => reachability::strongly_connected_components(g),
+            };
+            comps.sort();
+            if json {
+                json!(comps).to_string()
+            } else {
+                comps
+                    .iter()
+                    .map(|c| render_nodes(c, ", "))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            }
+        }
+        Command::Bipartite => {
+            // the empty graph is trivially bipartite (P89 rejects it)
+            let res = match g {
+                Input::Graph(g) => g.size() == 0 || P89::is_bipartite(g),
+                Input::Digraph(g) => g.size() == 0 || P89::is_bipartite(g),
+            };
+            json!(res).to_string()
+        }
+        Command::Isomorphic(_) => {
+            let res = match (g, other) {
+                (Input::Graph(g1), Some(Input::Graph(g2))) => P85::is_isomorphic_to(g1, g2),
+                (Input::Digraph(g1), Some(Input::Digraph(g2))) => P85::is_isomorphic_to(g1, g2),
+                (_, Some(_)) => return Err("cannot compare a graph with a digraph".to_string()),
+                (_, None) => return Err("no graph to compare".to_string()),
+            };
+            json!(res).to_string()
+        }
+        Command::Degree => {
+            let degrees = degrees(g);
+            match (g, json) {
+                (Input::Graph(_), true) => {
+                    let map: BTreeMap<String, usize> = degrees
+                        .iter()
+                        .map(|(v, d, _)| (v.to_string(), *d))
+                        .collect();
+                    json!(map).to_string()
+                }
+                (Input::Digraph(_), true) => {
+                    let map: BTreeMap<String, Value> = degrees
+                        .iter()
+                        .map(|(v, o, i)| (v.to_string(), json!({"out": o, "in": i})))
+                        .collect();
+                    json!(map).to_string()
+                }
+                (Input::Graph(_), false) => degrees
+                    .iter()
+                    .map(|(v, d, _)| format!("{}: {}", v, d))
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+                (Input::Digraph(_), false) => degrees
+                    .iter()
+                    .map(|(v, o, i)| format!("{}: out {}, in {}", v, o, i))
+                    .collect::<Vec<String>>()
+                    .join("\n"),
+            }
+        }
+    };
+    Ok(res)
+}
+
+fn render_nodes(nodes: &[char], sep: &str) -> String {
+    nodes
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(sep)
+}
+
+fn render_paths(paths: &[Vec<char>], json: bool) -> String {
+    let mut paths = paths.to_vec();
+    paths.sort();
+    if json {
+        json!(paths).to_string()
+    } else {
+        paths
+            .iter()
+            .map(|p| render_nodes(p, " -> "))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+// (node, degree or out-degree, in-degree) sorted by decreasing degree
+fn degrees(g: &Input) -> Vec<(char, usize, usize)> {
+    let mut degrees: Vec<(char, usize, usize)> = match g {
+        Input::Graph(g) => g
+            .get_nodes()
+            .iter()
+            .map(|n| (*n.get_value(), n.degree(), n.degree()))
+            .collect(),
+        Input::Digraph(g) => {
+            let mut ins: BTreeMap<char, usize> = BTreeMap::new();
+            for n in g.get_nodes() {
+                for w in n.adjacents() {
+                    *ins.entry(w).or_insert(0) += 1;
+                }
+            }
+            g.get_nodes()
+                .iter()
+                .map(|n| {
+                    let v = *n.get_value();
+                    (v, n.degree(), ins.get(&v).copied().unwrap_or(0))
+                })
+                .collect()
+        }
+    };
+    degrees.sort_by(|(v1, o1, i1), (v2, o2, i2)| (o2 + i2, v1).cmp(&(o1 + i1, v2)));
+    degrees
+}
+
+fn sorted_edges(g: &LabeledGraph<char, i32>) -> Vec<(char, char, i32)> {
+    let mut edges: Vec<(char, char, i32)> = g
+        .get_edges()
+        .into_iter()
+        .map(|(a, b, l)| if a <= b { (a, b, l) } else { (b, a, l) })
+        .map(|(a, b, l)| (a, b, l.unwrap()))
+        .collect();
+    edges.sort();
+    edges
+}
+
+fn with_unit_weight(g: &LabeledGraph<char, i32>) -> LabeledGraph<char, i32> {
+    let mut res = LabeledGraph::new();
+    for v in g.get_node_values() {
+        res.add_node(v);
+    }
+    for (a, b, l) in g.get_edges() {
+        res.add_labeled_edge(a, b, l.unwrap_or(1));
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|a| a.to_string()).collect()
+    }
+
+    fn run(command: &str, graph: &str, json: bool) -> Result<String, String> {
+        let g = Input::read(graph, false)?;
+        let opts = parse_args(&args(command))?;
+        execute(&opts.command, &g, None, json)
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let opts = parse_args(&args("--json g.txt paths a b")).unwrap();
+        assert!(opts.json);
+        assert_eq!(opts.file, Some("g.txt".to_string()));
+        assert_eq!(opts.command, Command::Paths('a', 'b'));
+        let opts = parse_args(&args("- degree")).unwrap();
+        assert_eq!(opts.file, None);
+        assert_eq!(opts.command, Command::Degree);
+        assert!(parse_args(&args("paths a")).is_err());
+        assert!(parse_args(&args("paths ab c")).is_err());
+        assert!(parse_args(&args("g.txt")).is_err());
+        assert!(parse_args(&args("--verbose mst")).is_err());
+    }
+
+    #[test]
+    fn test_paths_and_cycles() {
+        let g = "[p-q/9, m-q/7, k, p-m/5]";
+        assert_eq!(
+            run("paths p q", g, false),
+            Ok("p -> m -> q\np -> q".to_string())
+        );
+        assert_eq!(
+            run("paths p q", g, true),
+            Ok(r#"[["p","m","q"],["p","q"]]"#.to_string())
+        );
+        assert_eq!(run("paths p k", g, false), Ok("".to_string()));
+        assert!(run("paths p x", g, false).is_err());
+        assert_eq!(
+            run("cycles a", "[a>b, b>c, c>a]", false),
+            Ok("a -> b -> c -> a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_mst() {
+        let g = "[a-b/5, a-d/3, b-c/2, b-e/4, c-e/6, d-e/7, d-f/4, d-g/3, e-h/5, f-g/4, g-h/1]";
+        assert_eq!(
+            run("mst", g, false),
+            Ok([
+                "[a-b/5, a-d/3, b-c/2, b-e/4, d-f/4, d-g/3, g-h/1] (label sum: 22)",
+                "[a-d/3, b-c/2, b-e/4, d-g/3, e-h/5, f-g/4, g-h/1] (label sum: 22)"
+            ]
+            .join("\n"))
+        );
+        assert_eq!(
+            run("mst", "[a-b]", true),
+            Ok(r#"[{"edges":[{"label":1,"source":"a","target":"b"}],"label_sum":1}]"#.to_string())
+        );
+        assert!(run("mst", "[a-b, c]", false).is_err());
+        assert!(run("mst", "[a>b]", false).is_err());
+    }
+
+    #[test]
+    fn test_other_commands() {
+        let g = "[a-b, b-c, a-c, a-d, e-f]";
+        assert_eq!(
+            run("color", g, true),
+            Ok(r#"{"a":1,"b":2,"c":3,"d":2,"e":1,"f":2}"#.to_string())
+        );
+        assert_eq!(
+            run("components", g, false),
+            Ok("a, b, c, d\ne, f".to_string())
+        );
+        assert_eq!(
+            run("components", "[a>b, b>a, b>c]", true),
+            Ok(r#"[["a","b"],["c"]]"#.to_string())
+        );
+        assert_eq!(run("bipartite", g, false), Ok("false".to_string()));
+        assert_eq!(
+            run("bipartite", "[a-b, b-c]", false),
+            Ok("true".to_string())
+        );
+        assert_eq!(
+            run("bipartite", "[a>b, c>b, c>a]", false),
+            Ok("false".to_string())
+        );
+        assert_eq!(run("bipartite", "[]", false), Ok("true".to_string()));
+        assert_eq!(
+            run("color", "[a>b, b>c]", false),
+            Ok("a: 2\nb: 1\nc: 2".to_string())
+        );
+        assert_eq!(
+            run("degree", g, false),
+            Ok("a: 3\nb: 2\nc: 2\nd: 1\ne: 1\nf: 1".to_string())
+        );
+        assert_eq!(
+            run("degree", "[a>b, c>b]", true),
+            Ok(r#"{"a":{"in":0,"out":1},"b":{"in":2,"out":0},"c":{"in":0,"out":1}}"#.to_string())
+        );
+    }
+
+    #[test]
+    fn test_isomorphic() {
+        let g1 = Input::read("[a-b, b-c]", false).unwrap();
+        let g2 = Input::read("(['x', 'y', 'z'], [('x', 'z'), ('z', 'y')])", false).unwrap();
+        let g3 = Input::read("[a-b, b-c, c-a]", false).unwrap();
+        let command = Command::Isomorphic("other.txt".to_string());
+        assert_eq!(
+            execute(&command, &g1, Some(&g2), false),
+            Ok("true".to_string())
+        );
+        assert_eq!(
+            execute(&command, &g1, Some(&g3), false),
+            Ok("false".to_string())
+        );
+        let d = Input::read("[a>b, b>c]", false).unwrap();
+        assert!(execute(&command, &g1, Some(&d), false).is_err());
+    }
+}
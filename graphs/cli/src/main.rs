@@ -0,0 +1,45 @@
+use cli::{execute, parse_args, Command, Input, USAGE};
+use std::io::Read;
+use std::process;
+
+fn read_input(file: Option<&str>) -> Result<String, String> {
+    match file {
+        Some(path) => std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e)),
+        None => {
+            let mut s = String::new();
+            std::io::stdin()
+                .read_to_string(&mut s)
+                .map_err(|e| format!("stdin: {}", e))?;
+            Ok(s)
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<String, String> {
+    let opts = parse_args(args)?;
+    let g = Input::read(&read_input(opts.file.as_deref())?, opts.directed)?;
+    let other = match &opts.command {
+        Command::Isomorphic(path) => Some(Input::read(&read_input(Some(path))?, opts.directed)?),
+        _ => None,
+    };
+    execute(&opts.command, &g, other.as_ref(), opts.json)
+}
+
+pub fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() || args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+    match run(&args) {
+        Ok(output) => {
+            if !output.is_empty() {
+                println!("{}", output);
+            }
+        }
+        Err(msg) => {
+            eprintln!("r99-graph: {}", msg);
+            process::exit(2);
+        }
+    }
+}
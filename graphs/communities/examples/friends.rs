@@ -0,0 +1,24 @@
+use communities::*;
+use P80::graph_converters::labeled;
+
+pub fn main() {
+    // who talks to whom, labeled with the number of messages
+    let g = labeled::from_string(
+        "[a-b/4, a-c/5, b-c/3, c-d/1, d-e/6, d-f/4, e-f/5, f-g/1, g-h/3, g-i/4, h-i/2, i-j/1]",
+    );
+    let groups = louvain(&g);
+    println!(
+        "Louvain: {:?} (modularity={:.3})",
+        groups,
+        modularity(&g, &groups)
+    );
+    let groups = label_propagation(&g, 1);
+    println!(
+        "label propagation: {:?} (modularity={:.3})",
+        groups,
+        modularity(&g, &groups)
+    );
+    let (degeneracy, order) = degeneracy_ordering(&g);
+    println!("degeneracy: {}, ordering: {:?}", degeneracy, order);
+    println!("2-core: {:?}", k_core(&g, 2));
+}
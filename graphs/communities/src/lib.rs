@@ -0,0 +1,415 @@
+use graph::{EdgeWeights, GraphBase, Neighbors, UnderlyingGraph};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::Hash;
+
+// asynchronous label propagation usually settles in a few rounds; this only
+// guards against labels that keep oscillating between ties
+const MAX_ROUNDS: usize = 100;
+
+// the graph with nodes numbered by their sorted values, parallel edges merged
+// into one with the summed weight, and self-loops dropped
+struct WeightedAdjacency<T> {
+    values: Vec<T>,
+    adjs: Vec<Vec<(usize, i64)>>,
+}
+
+impl<T: Hash + Copy + Eq + Ord> WeightedAdjacency<T> {
+    fn new<G: EdgeWeights<T, Weight = i32>>(g: &G) -> Self {
+        let mut values = g.node_values().into_owned();
+        values.sort();
+        let adjs = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let mut weights: BTreeMap<usize, i64> = BTreeMap::new();
+                for (w, l) in g.neighbors_with_weight(v) {
+                    let l = l.unwrap_or(1);
+                    assert!(l >= 0, "edge labels must be non-negative");
+                    let j = values.binary_search(&w).unwrap();
+                    if i != j {
+                        *weights.entry(j).or_default() += i64::from(l);
+                    }
+                }
+                weights.into_iter().collect()
+            })
+            .collect();
+        WeightedAdjacency { values, adjs }
+    }
+
+    // groups the nodes by community ids; sorted as described in `louvain()`
+    fn communities(&self, ids: &[usize]) -> Vec<Vec<T>> {
+        let mut groups: BTreeMap<usize, Vec<T>> = BTreeMap::new();
+        for (i, v) in self.values.iter().enumerate() {
+            groups.entry(ids[i]).or_default().push(*v);
+        }
+        let mut communities: Vec<Vec<T>> = groups.values().cloned().collect();
+        communities.sort();
+        communities
+    }
+}
+
+/// Returns the modularity of a partition of an undirected graph into
+/// communities; i.e. the fraction of the edge weights within communities minus
+/// the fraction expected if edges were placed at random keeping the degrees.
+///
+/// Unlabeled edges weigh 1 and self-loops are ignored. Nodes that are not in
+/// any of the given communities are taken as communities by themselves. The
+/// modularity of a graph without edges is 0.
+pub fn modularity<T, G>(g: &G, communities: &[Vec<T>]) -> f64
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let adj = WeightedAdjacency::new(g);
+    let n = adj.values.len();
+    let mut ids: Vec<usize> = (0..n).collect();
+    for (c, community) in communities.iter().enumerate() {
+        for v in community {
+            if let Ok(i) = adj.values.binary_search(v) {
+                ids[i] = n + c;
+            }
+        }
+    }
+    let degrees: Vec<i64> = adj
+        .adjs
+        .iter()
+        .map(|a| a.iter().map(|(_, w)| w).sum())
+        .collect();
+    let total: i64 = degrees.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+    let mut inside: HashMap<usize, i64> = HashMap::new();
+    let mut tot: HashMap<usize, i64> = HashMap::new();
+    for i in 0..n {
+        *tot.entry(ids[i]).or_default() += degrees[i];
+        for (j, w) in &adj.adjs[i] {
+            if ids[i] == ids[*j] {
+                *inside.entry(ids[i]).or_default() += w;
+            }
+        }
+    }
+    let m2 = total as f64;
+    tot.iter()
+        .map(|(c, t)| {
+            let i = *inside.get(c).unwrap_or(&0) as f64;
+            let t = *t as f64;
+            i / m2 - (t / m2) * (t / m2)
+        })
+        .sum()
+}
+
+/// Detects communities of an undirected graph by label propagation.
+///
+/// Every node starts with a label of its own. Then, visiting nodes in a random
+/// order, each node adopts the label with the largest total edge weight among
+/// its neighbours, until no label changes. Ties are broken at random, but a node
+/// keeps its label if that is among the best. The random number generator is
+/// seeded by `seed`, so that the result is reproducible.
+///
+/// Unlabeled edges weigh 1 and self-loops are ignored. Communities are returned
+/// as sorted lists of nodes, ordered by their smallest nodes.
+pub fn label_propagation<T, G>(g: &G, seed: u64) -> Vec<Vec<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let adj = WeightedAdjacency::new(g);
+    let n = adj.values.len();
+    let mut labels: Vec<usize> = (0..n).collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    let mut rng = StdRng::seed_from_u64(seed);
+    for _ in 0..MAX_ROUNDS {
+        order.shuffle(&mut rng);
+        let mut changed = false;
+        for i in &order {
+            let mut weights: BTreeMap<usize, i64> = BTreeMap::new();
+            for (j, w) in &adj.adjs[*i] {
+                *weights.entry(labels[*j]).or_default() += w;
+            }
+            let max = match weights.values().max() {
+                Some(max) => *max,
+                None => continue,
+            };
+            if weights.get(&labels[*i]) == Some(&max) {
+                continue;
+            }
+            let best: Vec<usize> = weights
+                .into_iter()
+                .filter(|(_, w)| *w == max)
+                .map(|(l, _)| l)
+                .collect();
+            labels[*i] = *best.choose(&mut rng).unwrap();
+            changed = true;
+        }
+        if !changed {
+            break;
+        }
+    }
+    adj.communities(&labels)
+}
+
+/// Detects communities of an undirected graph by the Louvain method, which
+/// greedily maximizes the modularity.
+///
+/// Nodes are moved one by one, in sorted order, to the community of a neighbour
+/// where the modularity gains most, until no move improves it. The communities
+/// are then merged into single nodes and the moves are repeated on the smaller
+/// graph, until nothing changes.
+///
+/// Unlabeled edges weigh 1 and self-loops are ignored. Communities are returned
+/// as sorted lists of nodes, ordered by their smallest nodes.
+pub fn louvain<T, G>(g: &G) -> Vec<Vec<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let adj = WeightedAdjacency::new(g);
+    // community of each original node
+    let mut membership: Vec<usize> = (0..adj.values.len()).collect();
+    // the current (aggregated) graph; self-loops hold the weights inside communities
+    let mut adjs = adj.adjs.clone();
+    let mut loops = vec![0i64; adjs.len()];
+    loop {
+        let (ids, moved) = louvain_level(&adjs, &loops);
+        if !moved {
+            break;
+        }
+        for c in membership.iter_mut() {
+            *c = ids[*c];
+        }
+        // merge each community into a node
+        let count = ids.iter().max().map_or(0, |c| c + 1);
+        let mut merged: Vec<BTreeMap<usize, i64>> = vec![BTreeMap::new(); count];
+        let mut merged_loops = vec![0i64; count];
+        for (i, list) in adjs.iter().enumerate() {
+            merged_loops[ids[i]] += loops[i];
+            for (j, w) in list {
+                if ids[i] != ids[*j] {
+                    *merged[ids[i]].entry(ids[*j]).or_default() += w;
+                } else if i < *j {
+                    merged_loops[ids[i]] += w;
+                }
+            }
+        }
+        adjs = merged
+            .into_iter()
+            .map(|m| m.into_iter().collect())
+            .collect();
+        loops = merged_loops;
+    }
+    adj.communities(&membership)
+}
+
+// one level of the Louvain method; returns community ids numbered from 0 and
+// whether any node has moved
+fn louvain_level(adjs: &[Vec<(usize, i64)>], loops: &[i64]) -> (Vec<usize>, bool) {
+    let n = adjs.len();
+    // a self-loop adds twice its weight to the degree
+    let degrees: Vec<i64> = (0..n)
+        .map(|i| adjs[i].iter().map(|(_, w)| w).sum::<i64>() + 2 * loops[i])
+        .collect();
+    let m2: i64 = degrees.iter().sum();
+    let mut community: Vec<usize> = (0..n).collect();
+    let mut tot = degrees.clone();
+    let mut moved = false;
+    if m2 == 0 {
+        return (community, moved);
+    }
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 0..n {
+            let current = community[i];
+            tot[current] -= degrees[i];
+            let mut links: BTreeMap<usize, i64> = BTreeMap::new();
+            links.insert(current, 0);
+            for (j, w) in &adjs[i] {
+                *links.entry(community[*j]).or_default() += w;
+            }
+            // the gain of joining c, up to a common positive factor
+            let gain = |c: usize, w: i64| w * m2 - tot[c] * degrees[i];
+            let mut best = (current, gain(current, links[&current]));
+            for (c, w) in &links {
+                let g = gain(*c, *w);
+                if g > best.1 {
+                    best = (*c, g);
+                }
+            }
+            community[i] = best.0;
+            tot[best.0] += degrees[i];
+            if best.0 != current {
+                improved = true;
+                moved = true;
+            }
+        }
+    }
+    // renumber communities in the order of their first nodes
+    let mut numbers: HashMap<usize, usize> = HashMap::new();
+    let ids = community
+        .iter()
+        .map(|c| {
+            let next = numbers.len();
+            *numbers.entry(*c).or_insert(next)
+        })
+        .collect();
+    (ids, moved)
+}
+
+/// Returns the core number of each node; i.e. the largest `k` such that the
+/// node belongs to the k-core, the maximal subgraph where every node has degree
+/// at least `k`.
+///
+/// Arc directions of digraphs are ignored, parallel edges count once and
+/// self-loops are ignored.
+pub fn core_numbers<T, G>(g: &G) -> HashMap<T, usize>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let (_, order, cores) = peel(g);
+    order.into_iter().zip(cores).collect()
+}
+
+/// Returns the nodes of the k-core in sorted order; empty if there is none.
+/// See `core_numbers()`.
+pub fn k_core<T, G>(g: &G, k: usize) -> Vec<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let mut nodes: Vec<T> = core_numbers(g)
+        .into_iter()
+        .filter(|(_, c)| *c >= k)
+        .map(|(v, _)| v)
+        .collect();
+    nodes.sort();
+    nodes
+}
+
+/// Returns the degeneracy of a graph and a degeneracy ordering of its nodes.
+///
+/// The ordering repeatedly removes a node of the smallest degree (the smallest
+/// node among ties), so that every node has at most `degeneracy` neighbours
+/// later in the ordering. The degeneracy is the largest core number. Arc
+/// directions of digraphs are ignored, parallel edges count once and self-loops
+/// are ignored.
+pub fn degeneracy_ordering<T, G>(g: &G) -> (usize, Vec<T>)
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let (degeneracy, order, _) = peel(g);
+    (degeneracy, order)
+}
+
+// removes nodes of the smallest degree one by one; returns the degeneracy and
+// the removed nodes with their core numbers
+fn peel<T, G>(g: &G) -> (usize, Vec<T>, Vec<usize>)
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let g = UnderlyingGraph::new(g);
+    let mut degrees: HashMap<T, usize> = g
+        .node_values()
+        .iter()
+        .map(|v| (*v, g.neighbors(v).iter().filter(|w| *w != v).count()))
+        .collect();
+    let mut queue: BTreeSet<(usize, T)> = degrees.iter().map(|(v, d)| (*d, *v)).collect();
+    let mut order = vec![];
+    let mut cores = vec![];
+    let mut degeneracy = 0;
+    while let Some((d, v)) = queue.iter().next().copied() {
+        queue.remove(&(d, v));
+        degrees.remove(&v);
+        degeneracy = degeneracy.max(d);
+        order.push(v);
+        cores.push(degeneracy);
+        for w in g.neighbors(&v).iter() {
+            if let Some(dw) = degrees.get_mut(w) {
+                queue.remove(&(*dw, *w));
+                *dw -= 1;
+                queue.insert((*dw, *w));
+            }
+        }
+    }
+    (degeneracy, order, cores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use P80::graph_converters::{labeled, unlabeled};
+
+    // two triangles linked by a single edge
+    fn triangles() -> graph::LabeledGraph<char, i32> {
+        labeled::from_string("[a-b/1, b-c/1, c-a/1, c-d/1, d-e/1, e-f/1, f-d/1]")
+    }
+
+    #[test]
+    fn test_modularity() {
+        let g = triangles();
+        let q = modularity(&g, &[vec!['a', 'b', 'c'], vec!['d', 'e', 'f']]);
+        assert!((q - 5.0 / 14.0).abs() < 1e-9);
+        let q = modularity(&g, &[vec!['a', 'b', 'c', 'd', 'e', 'f']]);
+        assert!(q.abs() < 1e-9);
+        // every node by itself
+        assert!(modularity(&g, &[]) < 0.0);
+        assert_eq!(modularity(&labeled::from_string("[a, b]"), &[]), 0.0);
+    }
+
+    #[test]
+    fn test_label_propagation() {
+        let expected = vec![vec!['a', 'b', 'c'], vec!['d', 'e', 'f']];
+        // heavy edges within the triangles
+        let g = labeled::from_string("[a-b/3, b-c/3, c-a/3, c-d/1, d-e/3, e-f/3, f-d/3]");
+        for seed in 0..10 {
+            assert_eq!(label_propagation(&g, seed), expected);
+        }
+        assert_eq!(
+            label_propagation(&labeled::from_string("[a-b/1, c]"), 0),
+            vec![vec!['a', 'b'], vec!['c']]
+        );
+    }
+
+    #[test]
+    fn test_louvain() {
+        assert_eq!(
+            louvain(&triangles()),
+            vec![vec!['a', 'b', 'c'], vec!['d', 'e', 'f']]
+        );
+        // a ring of four triangles is split at the links between them
+        let g = labeled::from_string(
+            "[a-b/1, b-c/1, c-a/1, c-d/1, d-e/1, e-f/1, f-d/1, f-g/1, \
+             g-h/1, h-i/1, i-g/1, i-j/1, j-k/1, k-l/1, l-j/1, l-a/1]",
+        );
+        let communities = louvain(&g);
+        assert_eq!(communities.len(), 4);
+        assert!(communities.iter().all(|c| c.len() == 3));
+        assert!((modularity(&g, &communities) - 0.5).abs() < 1e-9);
+        assert_eq!(
+            louvain(&labeled::from_string("[a, b]")),
+            vec![vec!['a'], vec!['b']]
+        );
+    }
+
+    #[test]
+    fn test_k_core() {
+        // a clique of four with a tail
+        let g = unlabeled::from_string("[a-b, a-c, a-d, b-c, b-d, c-d, d-e, e-f]");
+        let cores = core_numbers(&g);
+        assert_eq!(cores[&'a'], 3);
+        assert_eq!(cores[&'e'], 1);
+        assert_eq!(cores[&'f'], 1);
+        assert_eq!(k_core(&g, 2), vec!['a', 'b', 'c', 'd']);
+        assert_eq!(k_core(&g, 4), vec![]);
+
+        let (degeneracy, order) = degeneracy_ordering(&g);
+        assert_eq!(degeneracy, 3);
+        assert_eq!(order, vec!['f', 'e', 'a', 'b', 'c', 'd']);
+    }
+}
@@ -0,0 +1,12 @@
+use cuts::*;
+use P80::graph_converters::labeled;
+
+pub fn main() {
+    // two clusters of servers linked by a few slow lines
+    let g = labeled::from_string(
+        "[a-c/5, a-e/4, c-e/6, c-g/1, e-g/5, b-g/1, d-e/2, b-d/6, b-f/5, d-f/4, d-h/3, f-h/6]",
+    );
+    println!("Stoer-Wagner: {:?}", stoer_wagner(&g).unwrap());
+    println!("Karger (64 trials): {:?}", karger(&g, 64, 1).unwrap());
+    println!("Kernighan-Lin: {:?}", kernighan_lin(&g).unwrap());
+}
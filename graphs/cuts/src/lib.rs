@@ -0,0 +1,375 @@
+use graph::EdgeWeights;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::hash::Hash;
+
+/// A partition of the nodes into two non-empty parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut<T> {
+    /// The sum of the labels of the edges between the parts.
+    pub weight: i32,
+    /// Both parts in sorted order; the first one contains the smallest node.
+    pub parts: (Vec<T>, Vec<T>),
+}
+
+// the graph as a matrix of summed edge labels between node indices
+struct Weights<T> {
+    values: Vec<T>,
+    matrix: Vec<Vec<i32>>,
+}
+
+impl<T: Hash + Copy + Eq + Ord> Weights<T> {
+    fn new<G: EdgeWeights<T, Weight = i32>>(g: &G) -> Self {
+        let mut values = g.node_values().into_owned();
+        values.sort();
+        let n = values.len();
+        let mut matrix = vec![vec![0; n]; n];
+        for (i, v) in values.iter().enumerate() {
+            for (w, l) in g.neighbors_with_weight(v) {
+                let l = l.expect("edges must be labeled");
+                assert!(l >= 0, "edge labels must be non-negative");
+                let j = values.binary_search(&w).unwrap();
+                // self-loops never cross a cut
+                if i != j {
+                    matrix[i][j] += l;
+                }
+            }
+        }
+        Weights { values, matrix }
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn cut_weight(&self, in_first: &[bool]) -> i32 {
+        let n = self.len();
+        (0..n)
+            .filter(|i| in_first[*i])
+            .flat_map(|i| (0..n).filter(|j| !in_first[*j]).map(move |j| (i, j)))
+            .map(|(i, j)| self.matrix[i][j])
+            .sum()
+    }
+
+    fn to_cut(&self, in_first: &[bool]) -> Cut<T> {
+        // node indices follow the sorted order, so the parts are sorted as well
+        let mut first = vec![];
+        let mut second = vec![];
+        for (i, v) in self.values.iter().enumerate() {
+            if in_first[i] == in_first[0] {
+                first.push(*v);
+            } else {
+                second.push(*v);
+            }
+        }
+        Cut {
+            weight: self.cut_weight(in_first),
+            parts: (first, second),
+        }
+    }
+}
+
+/// Returns a minimum cut of an undirected graph (Stoer–Wagner algorithm); i.e.
+/// a partition of the nodes into two parts such that the sum of the labels of
+/// the edges between them is minimal. `None` if there are less than two nodes.
+///
+/// Edges must be labeled with non-negative weights; parallel edges add up and
+/// self-loops are ignored. A disconnected graph has a cut of weight 0.
+pub fn stoer_wagner<T, G>(g: &G) -> Option<Cut<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let weights = Weights::new(g);
+    let n = weights.len();
+    if n < 2 {
+        return None;
+    }
+    let mut matrix = weights.matrix.clone();
+    // original nodes merged into each remaining node
+    let mut merged: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+    let mut remaining: Vec<usize> = (0..n).collect();
+    let mut best: Option<(i32, Vec<usize>)> = None;
+    while remaining.len() > 1 {
+        // minimum cut phase: add the most tightly connected node one by one
+        let mut connectivity: Vec<i32> = vec![0; n];
+        let mut added = vec![false; n];
+        let mut prev = remaining[0];
+        let mut last = remaining[0];
+        for _ in 0..remaining.len() {
+            let next = *remaining
+                .iter()
+                .filter(|i| !added[**i])
+                .max_by_key(|i| (connectivity[**i], std::cmp::Reverse(**i)))
+                .unwrap();
+            added[next] = true;
+            prev = last;
+            last = next;
+            for i in &remaining {
+                connectivity[*i] += matrix[next][*i];
+            }
+        }
+        // the cut of the phase separates the last added node from the others
+        let phase_weight = connectivity[last];
+        match best {
+            Some((w, _)) if w <= phase_weight => {}
+            _ => best = Some((phase_weight, merged[last].clone())),
+        }
+        // merge the last two nodes
+        let row = matrix[last].clone();
+        for (i, w) in row.into_iter().enumerate() {
+            matrix[prev][i] += w;
+            matrix[i][prev] = matrix[prev][i];
+        }
+        matrix[prev][prev] = 0;
+        let nodes = std::mem::take(&mut merged[last]);
+        merged[prev].extend(nodes);
+        remaining.retain(|i| *i != last);
+    }
+    let (_, side) = best.unwrap();
+    let mut in_first = vec![false; n];
+    for i in side {
+        in_first[i] = true;
+    }
+    Some(weights.to_cut(&in_first))
+}
+
+/// Returns the smallest cut found by `trials` runs of Karger's randomized
+/// contraction algorithm; `None` if there are less than two nodes.
+///
+/// Each run contracts randomly chosen edges, with probabilities proportional to
+/// their labels, until two nodes remain. A single run finds a minimum cut with
+/// probability at least 2 / (n (n - 1)) for n nodes, so about n² runs are needed
+/// to find one with high probability. The random number generator is seeded by
+/// `seed`, so that the result is reproducible. Edges must be labeled with
+/// non-negative weights.
+pub fn karger<T, G>(g: &G, trials: usize, seed: u64) -> Option<Cut<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let weights = Weights::new(g);
+    let n = weights.len();
+    if n < 2 {
+        return None;
+    }
+    let edges: Vec<(usize, usize, i32)> = (0..n)
+        .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+        .map(|(i, j)| (i, j, weights.matrix[i][j]))
+        .filter(|(_, _, w)| *w > 0)
+        .collect();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut best: Option<Cut<T>> = None;
+    for _ in 0..trials.max(1) {
+        let mut groups = DisjointSets::new(n);
+        let mut count = n;
+        while count > 2 {
+            let total: i32 = edges
+                .iter()
+                .filter(|(i, j, _)| groups.find(*i) != groups.find(*j))
+                .map(|(_, _, w)| *w)
+                .sum();
+            let (i, j) = if total == 0 {
+                // no edges left between the groups; any merge keeps the cut empty
+                let i = groups.find(0);
+                let j = (1..n).find(|j| groups.find(*j) != i).unwrap();
+                (i, j)
+            } else {
+                let mut r = rng.gen_range(0, total);
+                let mut chosen = None;
+                for (i, j, w) in &edges {
+                    if groups.find(*i) == groups.find(*j) {
+                        continue;
+                    }
+                    if r < *w {
+                        chosen = Some((*i, *j));
+                        break;
+                    }
+                    r -= w;
+                }
+                chosen.unwrap()
+            };
+            groups.union(i, j);
+            count -= 1;
+        }
+        let root = groups.find(0);
+        let in_first: Vec<bool> = (0..n).map(|i| groups.find(i) == root).collect();
+        let cut = weights.to_cut(&in_first);
+        match &best {
+            Some(b) if b.weight <= cut.weight => {}
+            _ => best = Some(cut),
+        }
+    }
+    best
+}
+
+struct DisjointSets {
+    parents: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        DisjointSets {
+            parents: (0..n).collect(),
+        }
+    }
+
+    fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut i = i;
+        while self.parents[i] != root {
+            let next = self.parents[i];
+            self.parents[i] = root;
+            i = next;
+        }
+        root
+    }
+
+    fn union(&mut self, i: usize, j: usize) {
+        let (i, j) = (self.find(i), self.find(j));
+        self.parents[j] = i;
+    }
+}
+
+/// Returns a balanced bisection of the nodes with a small cut, by the
+/// Kernighan–Lin heuristic; `None` if there are less than two nodes.
+///
+/// Starting from the sorted nodes split in halves, each pass tentatively swaps
+/// pairs of nodes between the parts in the order of the largest gain, then keeps
+/// the best prefix of the swaps. Passes are repeated while they reduce the cut.
+/// The parts differ in size by at most one, but the cut is only locally
+/// minimal. Edges must be labeled.
+pub fn kernighan_lin<T, G>(g: &G) -> Option<Cut<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let weights = Weights::new(g);
+    let n = weights.len();
+    if n < 2 {
+        return None;
+    }
+    let w = &weights.matrix;
+    let mut in_first: Vec<bool> = (0..n).map(|i| i < n / 2).collect();
+    loop {
+        // external minus internal cost of each node
+        let mut d: Vec<i32> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .filter(|j| *j != i)
+                    .map(|j| {
+                        if in_first[i] == in_first[j] {
+                            -w[i][j]
+                        } else {
+                            w[i][j]
+                        }
+                    })
+                    .sum()
+            })
+            .collect();
+        let mut locked = vec![false; n];
+        let mut side = in_first.clone();
+        let mut swaps: Vec<(usize, usize, i32)> = vec![];
+        for _ in 0..n / 2 {
+            let mut best: Option<(usize, usize, i32)> = None;
+            for a in (0..n).filter(|a| side[*a] && !locked[*a]) {
+                for b in (0..n).filter(|b| !side[*b] && !locked[*b]) {
+                    let gain = d[a] + d[b] - 2 * w[a][b];
+                    match best {
+                        Some((_, _, g)) if g >= gain => {}
+                        _ => best = Some((a, b, gain)),
+                    }
+                }
+            }
+            let (a, b, gain) = match best {
+                Some(best) => best,
+                None => break,
+            };
+            locked[a] = true;
+            locked[b] = true;
+            side[a] = false;
+            side[b] = true;
+            for i in (0..n).filter(|i| !locked[*i]) {
+                // a moved to the second part, b to the first
+                let sign = if side[i] { 1 } else { -1 };
+                d[i] += sign * (2 * w[i][a] - 2 * w[i][b]);
+            }
+            swaps.push((a, b, gain));
+        }
+        // the prefix of swaps with the largest total gain
+        let mut total = 0;
+        let mut best = (0, 0);
+        for (k, (_, _, gain)) in swaps.iter().enumerate() {
+            total += gain;
+            if total > best.1 {
+                best = (k + 1, total);
+            }
+        }
+        if best.0 == 0 {
+            break;
+        }
+        for (a, b, _) in &swaps[..best.0] {
+            in_first[*a] = false;
+            in_first[*b] = true;
+        }
+    }
+    Some(weights.to_cut(&in_first))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::LabeledGraph;
+    use P80::graph_converters::labeled;
+
+    // the example in Stoer and Wagner's paper, with nodes 1..8 renamed to a..h
+    fn example() -> LabeledGraph<char, i32> {
+        labeled::from_string(
+            "[a-b/2, a-e/3, b-c/3, b-e/2, b-f/2, c-d/4, c-g/2, d-g/2, d-h/2, e-f/3, f-g/1, g-h/3]",
+        )
+    }
+
+    #[test]
+    fn test_stoer_wagner() {
+        let expected = Cut {
+            weight: 4,
+            parts: (vec!['a', 'b', 'e', 'f'], vec!['c', 'd', 'g', 'h']),
+        };
+        assert_eq!(stoer_wagner(&example()), Some(expected));
+        assert_eq!(stoer_wagner(&labeled::from_string("[a]")), None);
+
+        let cut = stoer_wagner(&labeled::from_string("[a-b/1, c-d/5, d-e/2]")).unwrap();
+        assert_eq!(cut.weight, 0);
+        assert_eq!(cut.parts.0.len() + cut.parts.1.len(), 5);
+    }
+
+    #[test]
+    fn test_karger() {
+        let cut = karger(&example(), 64, 42).unwrap();
+        assert_eq!(cut, stoer_wagner(&example()).unwrap());
+        // same seed, same result
+        assert_eq!(karger(&example(), 1, 7), karger(&example(), 1, 7));
+        assert_eq!(
+            karger(&labeled::from_string("[a-b/1, c]"), 1, 0)
+                .unwrap()
+                .weight,
+            0
+        );
+    }
+
+    #[test]
+    fn test_kernighan_lin() {
+        let cut = kernighan_lin(&example()).unwrap();
+        assert_eq!(cut.weight, 4);
+        assert_eq!(cut.parts.0.len(), 4);
+
+        // a triangle and a square linked by a light edge; sizes differ by one
+        let g = labeled::from_string("[a-b/3, b-c/3, c-a/3, c-d/1, d-e/3, e-f/3, f-g/3, g-d/3]");
+        let cut = kernighan_lin(&g).unwrap();
+        assert_eq!(cut.weight, 1);
+        assert_eq!(cut.parts, (vec!['a', 'b', 'c'], vec!['d', 'e', 'f', 'g']));
+    }
+}
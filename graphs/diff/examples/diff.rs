@@ -0,0 +1,12 @@
+use diff::*;
+use P80::graph_converters::labeled;
+
+pub fn main() {
+    let g1 = labeled::from_string("[a-b/1, b-c/2, c-d/3, e]");
+    let g2 = labeled::from_string("[a-b/1, b-c/5, b-d/4, f]");
+    let patch = diff(&g1, &g2);
+    println!("{}", patch);
+    let patched = patch.apply(&g1).unwrap();
+    println!("patched == g2: {}", diff(&patched, &g2).is_empty());
+    println!("reverse:\n{}", patch.reverse());
+}
@@ -0,0 +1,268 @@
+use graph::EdgeList;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// Differences between two graphs, which can also be applied as a patch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphDiff<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    pub added_nodes: Vec<T>,
+    pub removed_nodes: Vec<T>,
+    pub added_edges: Vec<(T, T, Option<U>)>,
+    pub removed_edges: Vec<(T, T, Option<U>)>,
+    /// `(v1, v2, old label, new label)`
+    pub changed_labels: Vec<(T, T, Option<U>, Option<U>)>,
+    directed: bool,
+}
+
+/// Errors on applying a patch to a graph it was not made for
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError<T> {
+    /// The node to be removed is not in the graph.
+    MissingNode(T),
+    /// The node to be added is already in the graph.
+    DuplicateNode(T),
+    /// The edge to be removed or relabeled is not in the graph (or has another label).
+    MissingEdge(T, T),
+    /// An edge is attached to a node that is not in the graph.
+    DanglingEdge(T, T),
+}
+
+impl<T: fmt::Display> fmt::Display for PatchError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::MissingNode(v) => write!(f, "node {} is not in the graph", v),
+            PatchError::DuplicateNode(v) => write!(f, "node {} is already in the graph", v),
+            PatchError::MissingEdge(v1, v2) => write!(f, "edge {}-{} is not in the graph", v1, v2),
+            PatchError::DanglingEdge(v1, v2) => {
+                write!(f, "edge {}-{} is attached to a missing node", v1, v2)
+            }
+        }
+    }
+}
+
+/// Computes the differences from `g1` to `g2`.
+///
+/// Edges are matched by their end nodes. If both graphs have exactly one edge
+/// between two nodes and the labels differ, it is reported as a changed label;
+/// otherwise (parallel edges) unmatched edges are reported as removed or added.
+pub fn diff<G, T, U>(g1: &G, g2: &G) -> GraphDiff<T, U>
+where
+    G: EdgeList<T, U>,
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    // both node lists are sorted
+    let (nodes1, nodes2) = (g1.node_list(), g2.node_list());
+    let added_nodes = nodes2
+        .iter()
+        .filter(|v| nodes1.binary_search(v).is_err())
+        .copied()
+        .collect();
+    let removed_nodes = nodes1
+        .iter()
+        .filter(|v| nodes2.binary_search(v).is_err())
+        .copied()
+        .collect();
+
+    // the labels of the edges between two nodes in g1 and in g2
+    let mut labels = BTreeMap::<(T, T), (Vec<_>, Vec<_>)>::new();
+    for (a, b, l) in g1.edge_list() {
+        labels.entry((a, b)).or_default().0.push(l);
+    }
+    for (a, b, l) in g2.edge_list() {
+        labels.entry((a, b)).or_default().1.push(l);
+    }
+    let mut added_edges = vec![];
+    let mut removed_edges = vec![];
+    let mut changed_labels = vec![];
+    for ((a, b), (mut labels1, mut labels2)) in labels {
+        if labels1.len() == 1 && labels2.len() == 1 {
+            if labels1[0] != labels2[0] {
+                changed_labels.push((a, b, labels1[0], labels2[0]));
+            }
+            continue;
+        }
+        // cancel out the common labels
+        labels1.retain(|l| match labels2.iter().position(|l2| l2 == l) {
+            Some(i) => {
+                labels2.remove(i);
+                false
+            }
+            None => true,
+        });
+        removed_edges.extend(labels1.into_iter().map(|l| (a, b, l)));
+        added_edges.extend(labels2.into_iter().map(|l| (a, b, l)));
+    }
+
+    GraphDiff {
+        added_nodes,
+        removed_nodes,
+        added_edges,
+        removed_edges,
+        changed_labels,
+        directed: g1.is_directed(),
+    }
+}
+
+impl<T, U> GraphDiff<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    /// Returns true if there are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.changed_labels.is_empty()
+    }
+
+    /// Returns the patch that undoes this patch.
+    pub fn reverse(&self) -> GraphDiff<T, U> {
+        GraphDiff {
+            added_nodes: self.removed_nodes.clone(),
+            removed_nodes: self.added_nodes.clone(),
+            added_edges: self.removed_edges.clone(),
+            removed_edges: self.added_edges.clone(),
+            changed_labels: self
+                .changed_labels
+                .iter()
+                .map(|(a, b, old, new)| (*a, *b, *new, *old))
+                .collect(),
+            directed: self.directed,
+        }
+    }
+
+    /// Applies this patch to a graph and returns the patched graph; i.e.
+    /// `diff(g1, g2).apply(g1)` is equal to `g2`.
+    pub fn apply<G: EdgeList<T, U>>(&self, g: &G) -> Result<G, PatchError<T>> {
+        let mut nodes = g.node_list();
+        for v in &self.removed_nodes {
+            match nodes.iter().position(|n| n == v) {
+                Some(i) => nodes.remove(i),
+                None => return Err(PatchError::MissingNode(*v)),
+            };
+        }
+        for v in &self.added_nodes {
+            if nodes.contains(v) {
+                return Err(PatchError::DuplicateNode(*v));
+            }
+            nodes.push(*v);
+        }
+
+        let mut edges = g.edge_list();
+        for (a, b, l) in &self.removed_edges {
+            match edges.iter().position(|e| *e == (*a, *b, *l)) {
+                Some(i) => edges.remove(i),
+                None => return Err(PatchError::MissingEdge(*a, *b)),
+            };
+        }
+        for (a, b, old, new) in &self.changed_labels {
+            match edges.iter_mut().find(|e| **e == (*a, *b, *old)) {
+                Some(e) => e.2 = *new,
+                None => return Err(PatchError::MissingEdge(*a, *b)),
+            }
+        }
+        edges.extend(self.added_edges.iter().copied());
+        if let Some((a, b, _)) = edges
+            .iter()
+            .find(|(a, b, _)| !nodes.contains(a) || !nodes.contains(b))
+        {
+            return Err(PatchError::DanglingEdge(*a, *b));
+        }
+        Ok(G::from_lists(&nodes, &edges))
+    }
+}
+
+/// Renders a patch line by line, edges in the human-friendly form of P80
+/// (e.g. `+ a-b/5`, `- a>b`, `~ a-b/5 -> a-b/7`).
+impl<T, U> fmt::Display for GraphDiff<T, U>
+where
+    T: Hash + Copy + Eq + Ord + fmt::Display,
+    U: Copy + Eq + fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sep = if self.directed { ">" } else { "-" };
+        let edge = |a: &T, b: &T, l: &Option<U>| match l {
+            Some(l) => format!("{}{}{}/{}", a, sep, b, l),
+            None => format!("{}{}{}", a, sep, b),
+        };
+        let mut lines = vec![];
+        for v in &self.removed_nodes {
+            lines.push(format!("- {}", v));
+        }
+        for v in &self.added_nodes {
+            lines.push(format!("+ {}", v));
+        }
+        for (a, b, l) in &self.removed_edges {
+            lines.push(format!("- {}", edge(a, b, l)));
+        }
+        for (a, b, l) in &self.added_edges {
+            lines.push(format!("+ {}", edge(a, b, l)));
+        }
+        for (a, b, old, new) in &self.changed_labels {
+            lines.push(format!("~ {} -> {}", edge(a, b, old), edge(a, b, new)));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use P80::{digraph_converters, graph_converters};
+
+    #[test]
+    fn test_diff_graph() {
+        use graph_converters::labeled;
+        let g1 = labeled::from_string("[a-b/1, b-c/2, c-d/3, e]");
+        let g2 = labeled::from_string("[a-b/1, b-c/5, b-d/4, f]");
+        let d = diff(&g1, &g2);
+        assert_eq!(d.added_nodes, vec!['f']);
+        assert_eq!(d.removed_nodes, vec!['e']);
+        assert_eq!(d.added_edges, vec![('b', 'd', Some(4))]);
+        assert_eq!(d.removed_edges, vec![('c', 'd', Some(3))]);
+        assert_eq!(d.changed_labels, vec![('b', 'c', Some(2), Some(5))]);
+        assert!(diff(&g1, &g1).is_empty());
+    }
+
+    #[test]
+    fn test_apply() {
+        use graph_converters::labeled;
+        let g1 = labeled::from_string("[a-b/1, b-c/2, c-d/3, e]");
+        let g2 = labeled::from_string("[a-b/1, b-c/5, b-d/4, f]");
+        let d = diff(&g1, &g2);
+        assert_eq!(d.apply(&g1), Ok(g2.clone()));
+        assert_eq!(d.reverse().apply(&g2), Ok(g1.clone()));
+        assert_eq!(d.apply(&g2), Err(PatchError::MissingNode('e')));
+    }
+
+    #[test]
+    fn test_diff_digraph() {
+        use digraph_converters::unlabeled;
+        let g1 = unlabeled::from_string("[a>b, b>a, c]");
+        let g2 = unlabeled::from_string("[a>b, b>c]");
+        let d = diff(&g1, &g2);
+        assert_eq!(d.added_edges, vec![('b', 'c', None)]);
+        assert_eq!(d.removed_edges, vec![('b', 'a', None)]);
+        let patched = d.apply(&g1).unwrap();
+        assert!(diff(&patched, &g2).is_empty());
+    }
+
+    #[test]
+    fn test_display() {
+        use graph_converters::labeled;
+        let g1 = labeled::from_string("[a-b/1, b-c/2, e]");
+        let g2 = labeled::from_string("[b-c/5, c-d/4]");
+        assert_eq!(
+            format!("{}", diff(&g1, &g2)),
+            "- a\n- e\n+ d\n- a-b/1\n+ c-d/4\n~ b-c/2 -> b-c/5"
+        );
+    }
+}
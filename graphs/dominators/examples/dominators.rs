@@ -0,0 +1,18 @@
+use dominators::*;
+use P80::digraph_converters::unlabeled;
+
+pub fn main() {
+    // a control-flow graph with a loop (b -> c -> e -> b)
+    let cfg = unlabeled::from_string("[a>b, b>c, c>d, c>e, d>f, e>f, e>b, f>g]");
+    let dom = dominators(&cfg, 'a');
+    for v in "abcdefg".chars() {
+        println!(
+            "{}: idom = {:?}, frontier = {:?}",
+            v,
+            dom.immediate_dominator(v),
+            dom.dominance_frontier(v)
+        );
+    }
+    let pdom = post_dominators(&cfg, 'g');
+    println!("post-dominators of c: {:?}", pdom.dominators('c'));
+}
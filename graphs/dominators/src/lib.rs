@@ -0,0 +1,310 @@
+use graph::{Digraph, Neighbors};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Dominator tree of a rooted digraph.
+///
+/// A node `a` dominates `b` if every path from the root to `b` goes through `a`.
+/// The immediate dominator of `b` is its closest strict dominator, and the
+/// immediate dominators form a tree rooted at the root. Nodes that are not
+/// reachable from the root are not dominated by any node.
+#[derive(Debug, Clone)]
+pub struct Dominators<T: Hash + Copy + Eq + Ord> {
+    root: T,
+    idom: HashMap<T, T>,
+    frontiers: HashMap<T, Vec<T>>,
+}
+
+impl<T: Hash + Copy + Eq + Ord> Dominators<T> {
+    /// Returns the root (the entry node, or the exit node for post-dominators).
+    pub fn get_root(&self) -> T {
+        self.root
+    }
+
+    /// Returns the immediate dominator of `v`; None for the root and unreachable nodes.
+    pub fn immediate_dominator(&self, v: T) -> Option<T> {
+        if v == self.root {
+            None
+        } else {
+            self.idom.get(&v).copied()
+        }
+    }
+
+    /// Returns all dominators of `v`, from `v` itself up to the root.
+    pub fn dominators(&self, v: T) -> Vec<T> {
+        if !self.idom.contains_key(&v) {
+            return vec![];
+        }
+        let mut res = vec![v];
+        let mut current = v;
+        while let Some(d) = self.immediate_dominator(current) {
+            res.push(d);
+            current = d;
+        }
+        res
+    }
+
+    /// Checks if `a` dominates `b`. Every reachable node dominates itself.
+    pub fn dominates(&self, a: T, b: T) -> bool {
+        self.dominators(b).contains(&a)
+    }
+
+    /// Returns the dominance frontier of `v`; the nodes where the dominance of `v` ends.
+    /// i.e. `w` is in the frontier if `v` dominates a predecessor of `w` but does not
+    /// strictly dominate `w`.
+    pub fn dominance_frontier(&self, v: T) -> Vec<T> {
+        self.frontiers.get(&v).cloned().unwrap_or_default()
+    }
+
+    /// Returns the dominator tree; there is an arc from the immediate dominator of
+    /// each node to the node.
+    pub fn to_tree(&self) -> Digraph<T> {
+        let mut tree = Digraph::new();
+        for v in self.idom.keys() {
+            tree.add_node(*v);
+        }
+        let mut nodes: Vec<T> = self.idom.keys().copied().collect();
+        nodes.sort();
+        for v in nodes {
+            if let Some(d) = self.immediate_dominator(v) {
+                tree.add_arc(d, v);
+            }
+        }
+        tree
+    }
+}
+
+/// Computes the dominator tree of a digraph with the given entry node, by the
+/// iterative algorithm of Cooper, Harvey and Kennedy.
+pub fn dominators<T, G>(g: &G, entry: T) -> Dominators<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let (values, succs, preds) = adjacency(g);
+    compute(&values, &succs, &preds, entry)
+}
+
+/// Computes the post-dominator tree of a digraph with the given exit node; `a`
+/// post-dominates `b` if every path from `b` to the exit goes through `a`.
+/// This is the dominator tree of the reversed digraph.
+pub fn post_dominators<T, G>(g: &G, exit: T) -> Dominators<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let (values, succs, preds) = adjacency(g);
+    compute(&values, &preds, &succs, exit)
+}
+
+type AdjacencyLists<T> = (Vec<T>, Vec<Vec<usize>>, Vec<Vec<usize>>);
+
+fn adjacency<T, G>(g: &G) -> AdjacencyLists<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let mut values = g.node_values().into_owned();
+    values.sort();
+    let index: HashMap<T, usize> = values.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+    let mut succs = vec![vec![]; values.len()];
+    let mut preds = vec![vec![]; values.len()];
+    for (i, v) in values.iter().enumerate() {
+        for w in g.neighbors(v).iter() {
+            let j = index[w];
+            succs[i].push(j);
+            preds[j].push(i);
+        }
+    }
+    (values, succs, preds)
+}
+
+fn compute<T: Hash + Copy + Eq + Ord>(
+    values: &[T],
+    succs: &[Vec<usize>],
+    preds: &[Vec<usize>],
+    root: T,
+) -> Dominators<T> {
+    let root_idx = match values.iter().position(|v| *v == root) {
+        Some(i) => i,
+        None => panic!("root node must be in the graph"),
+    };
+    let n = values.len();
+
+    // number reachable nodes in reverse postorder
+    let postorder = postorder(succs, root_idx);
+    let mut rpo_num = vec![None; n];
+    for (k, v) in postorder.iter().rev().enumerate() {
+        rpo_num[*v] = Some(k);
+    }
+
+    let intersect = |idom: &[Option<usize>], mut a: usize, mut b: usize| {
+        while a != b {
+            while rpo_num[a] > rpo_num[b] {
+                a = idom[a].unwrap();
+            }
+            while rpo_num[b] > rpo_num[a] {
+                b = idom[b].unwrap();
+            }
+        }
+        a
+    };
+
+    let mut idom: Vec<Option<usize>> = vec![None; n];
+    idom[root_idx] = Some(root_idx);
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for &v in postorder.iter().rev().skip(1) {
+            let mut new_idom = None;
+            for &p in &preds[v] {
+                if idom[p].is_none() {
+                    continue; // not processed yet, or unreachable
+                }
+                new_idom = match new_idom {
+                    None => Some(p),
+                    Some(d) => Some(intersect(&idom, p, d)),
+                };
+            }
+            if new_idom.is_some() && idom[v] != new_idom {
+                idom[v] = new_idom;
+                changed = true;
+            }
+        }
+    }
+
+    // dominance frontiers; walk up from each predecessor of v to the immediate
+    // dominator of v. The root has no immediate dominator, so walk up to the root.
+    let mut frontiers: Vec<Vec<usize>> = vec![vec![]; n];
+    for v in (0..n).filter(|v| idom[*v].is_some()) {
+        for &p in preds[v].iter().filter(|p| idom[**p].is_some()) {
+            let mut runner = p;
+            loop {
+                if v != root_idx && Some(runner) == idom[v] {
+                    break;
+                }
+                if !frontiers[runner].contains(&v) {
+                    frontiers[runner].push(v);
+                }
+                if runner == root_idx {
+                    break;
+                }
+                runner = idom[runner].unwrap();
+            }
+        }
+    }
+
+    let idom_map = (0..n)
+        .filter_map(|v| idom[v].map(|d| (values[v], values[d])))
+        .collect();
+    let frontier_map = (0..n)
+        .filter(|v| idom[*v].is_some())
+        .map(|v| {
+            let mut f: Vec<T> = frontiers[v].iter().map(|w| values[*w]).collect();
+            f.sort();
+            (values[v], f)
+        })
+        .collect();
+    Dominators {
+        root,
+        idom: idom_map,
+        frontiers: frontier_map,
+    }
+}
+
+/// Returns the nodes reachable from `root` in depth-first postorder.
+fn postorder(succs: &[Vec<usize>], root: usize) -> Vec<usize> {
+    let mut visited = vec![false; succs.len()];
+    let mut order = vec![];
+    // (node, position of the next successor to visit)
+    let mut stack = vec![(root, 0)];
+    visited[root] = true;
+    while let Some((v, pos)) = stack.pop() {
+        if pos < succs[v].len() {
+            stack.push((v, pos + 1));
+            let w = succs[v][pos];
+            if !visited[w] {
+                visited[w] = true;
+                stack.push((w, 0));
+            }
+        } else {
+            order.push(v);
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use P80::digraph_converters::unlabeled;
+
+    // a classic control-flow graph with a loop (b -> c -> e -> b) and a branch (c -> d | e)
+    fn make_cfg() -> Digraph<char> {
+        unlabeled::from_string("[a>b, b>c, c>d, c>e, d>f, e>f, e>b, f>g, h>f]")
+    }
+
+    #[test]
+    fn test_dominators() {
+        let dom = dominators(&make_cfg(), 'a');
+        assert_eq!(dom.immediate_dominator('a'), None);
+        assert_eq!(dom.immediate_dominator('b'), Some('a'));
+        assert_eq!(dom.immediate_dominator('c'), Some('b'));
+        assert_eq!(dom.immediate_dominator('d'), Some('c'));
+        assert_eq!(dom.immediate_dominator('e'), Some('c'));
+        assert_eq!(dom.immediate_dominator('f'), Some('c'));
+        assert_eq!(dom.immediate_dominator('g'), Some('f'));
+        assert_eq!(dom.immediate_dominator('h'), None);
+        assert_eq!(dom.dominators('g'), vec!['g', 'f', 'c', 'b', 'a']);
+        assert!(dom.dominates('b', 'e'));
+        assert!(!dom.dominates('d', 'f'));
+        assert!(!dom.dominates('a', 'h'));
+    }
+
+    #[test]
+    fn test_dominance_frontier() {
+        let dom = dominators(&make_cfg(), 'a');
+        assert_eq!(dom.dominance_frontier('a'), vec![]);
+        assert_eq!(dom.dominance_frontier('b'), vec!['b']);
+        assert_eq!(dom.dominance_frontier('d'), vec!['f']);
+        assert_eq!(dom.dominance_frontier('e'), vec!['b', 'f']);
+        assert_eq!(dom.dominance_frontier('c'), vec!['b']);
+    }
+
+    #[test]
+    fn test_post_dominators() {
+        let pdom = post_dominators(&make_cfg(), 'g');
+        assert_eq!(pdom.immediate_dominator('d'), Some('f'));
+        assert_eq!(pdom.immediate_dominator('c'), Some('f'));
+        assert_eq!(pdom.immediate_dominator('a'), Some('b'));
+        assert_eq!(pdom.immediate_dominator('h'), Some('f'));
+        assert_eq!(pdom.dominance_frontier('d'), vec!['c']);
+    }
+
+    #[test]
+    fn test_to_tree() {
+        let tree = dominators(&make_cfg(), 'a').to_tree();
+        let mut arcs = unlabeled::to_term_form(&tree).1;
+        arcs.sort();
+        assert_eq!(
+            arcs,
+            vec![
+                ('a', 'b'),
+                ('b', 'c'),
+                ('c', 'd'),
+                ('c', 'e'),
+                ('c', 'f'),
+                ('f', 'g')
+            ]
+        );
+    }
+
+    #[test]
+    fn test_csr_graph() {
+        use graph::CsrGraph;
+        let cfg = CsrGraph::from_digraph(&make_cfg());
+        let dom = dominators(&cfg, 'a');
+        assert_eq!(dom.dominators('g'), vec!['g', 'f', 'c', 'b', 'a']);
+        assert_eq!(dom.dominance_frontier('e'), vec!['b', 'f']);
+    }
+}
@@ -0,0 +1,13 @@
+use formats::*;
+use graph::LabeledDigraph;
+use P80::digraph_converters::labeled;
+
+pub fn main() {
+    let g = labeled::from_string("[p>q/9, m>q/7, k, p>m/5]");
+    println!("{}", serde_json::to_string(&g).unwrap());
+    println!("{}", to_node_link(&g));
+    let graphml = to_graphml(&g);
+    println!("{}", graphml);
+    let g2: LabeledDigraph<char, i32> = from_graphml(&graphml).unwrap();
+    println!("{}", to_node_link(&g2) == to_node_link(&g));
+}
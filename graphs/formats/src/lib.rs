@@ -0,0 +1,367 @@
+use graph::EdgeList;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::hash::Hash;
+use std::str::FromStr;
+
+mod xml;
+
+/// Errors on reading a graph
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatError {
+    /// The input is not well-formed, or lacks required elements.
+    Syntax(String),
+    /// A node value or label could not be parsed.
+    InvalidValue(String),
+    /// An edge refers to a node that is not declared.
+    UnknownNode(String),
+    /// The input is a digraph but a graph was expected, or vice versa.
+    Directedness,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Syntax(msg) => write!(f, "syntax error: {}", msg),
+            FormatError::InvalidValue(v) => write!(f, "invalid value: {}", v),
+            FormatError::UnknownNode(v) => write!(f, "unknown node: {}", v),
+            FormatError::Directedness => write!(f, "directedness does not match"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+/// Writes a graph in the JSON node-link format (as used by NetworkX and D3.js), e.g.
+/// `{"directed":false,"links":[{"label":5,"source":"a","target":"b"}],"multigraph":false,"nodes":[{"id":"a"},{"id":"b"}]}`.
+/// `multigraph` is true if some nodes are joined by parallel edges.
+pub fn to_node_link<G, T, U>(g: &G) -> String
+where
+    G: EdgeList<T, U>,
+    T: Hash + Copy + Eq + Ord + Serialize,
+    U: Copy + Eq + Serialize,
+{
+    let nodes: Vec<Value> = g.node_list().iter().map(|v| json!({ "id": v })).collect();
+    let edges = g.edge_list();
+    // the edges are sorted, so parallel ones are next to each other
+    let multigraph = edges
+        .windows(2)
+        .any(|w| (w[0].0, w[0].1) == (w[1].0, w[1].1));
+    let links: Vec<Value> = edges
+        .iter()
+        .map(|(v1, v2, l)| match l {
+            Some(l) => json!({ "source": v1, "target": v2, "label": l }),
+            None => json!({ "source": v1, "target": v2 }),
+        })
+        .collect();
+    let mut doc = Map::new();
+    doc.insert("directed".to_string(), Value::Bool(g.is_directed()));
+    doc.insert("multigraph".to_string(), Value::Bool(multigraph));
+    doc.insert("nodes".to_string(), Value::Array(nodes));
+    doc.insert("links".to_string(), Value::Array(links));
+    Value::Object(doc).to_string()
+}
+
+/// Reads a graph in the JSON node-link format. Attributes other than `id`,
+/// `source`, `target` and `label` are ignored.
+pub fn from_node_link<G, T, U>(s: &str) -> Result<G, FormatError>
+where
+    G: EdgeList<T, U>,
+    T: Hash + Copy + Eq + Ord + DeserializeOwned,
+    U: Copy + Eq + DeserializeOwned,
+{
+    let doc: Value = serde_json::from_str(s).map_err(|e| FormatError::Syntax(e.to_string()))?;
+    if doc["directed"].as_bool().unwrap_or(false) != is_directed::<G, T, U>() {
+        return Err(FormatError::Directedness);
+    }
+    let parse = |v: &Value| -> Result<T, FormatError> {
+        serde_json::from_value(v.clone()).map_err(|_| FormatError::InvalidValue(v.to_string()))
+    };
+    let array = |key: &str| -> Result<Vec<Value>, FormatError> {
+        match &doc[key] {
+            Value::Array(values) => Ok(values.clone()),
+            Value::Null => Ok(vec![]),
+            _ => Err(FormatError::Syntax(format!("\"{}\" must be an array", key))),
+        }
+    };
+
+    let mut nodes = vec![];
+    for node in array("nodes")? {
+        nodes.push(parse(&node["id"])?);
+    }
+    let mut edges = vec![];
+    for link in array("links")? {
+        let (v1, v2) = (parse(&link["source"])?, parse(&link["target"])?);
+        for v in &[v1, v2] {
+            if !nodes.contains(v) {
+                return Err(FormatError::UnknownNode(link.to_string()));
+            }
+        }
+        let label = match &link["label"] {
+            Value::Null => None,
+            l => Some(
+                serde_json::from_value(l.clone())
+                    .map_err(|_| FormatError::InvalidValue(l.to_string()))?,
+            ),
+        };
+        edges.push((v1, v2, label));
+    }
+    Ok(G::from_lists(&nodes, &edges))
+}
+
+/// Writes a graph in GraphML. Edge labels are stored as the `label` data of edges.
+pub fn to_graphml<G, T, U>(g: &G) -> String
+where
+    G: EdgeList<T, U>,
+    T: Hash + Copy + Eq + Ord + fmt::Display,
+    U: Copy + Eq + fmt::Display,
+{
+    let mut lines = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_string(),
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#.to_string(),
+        r#"  <key id="label" for="edge" attr.name="label" attr.type="string"/>"#.to_string(),
+        format!(
+            r#"  <graph id="G" edgedefault="{}">"#,
+            if g.is_directed() {
+                "directed"
+            } else {
+                "undirected"
+            }
+        ),
+    ];
+    for v in g.node_list() {
+        lines.push(format!(
+            r#"    <node id="{}"/>"#,
+            xml::escape(&v.to_string())
+        ));
+    }
+    for (v1, v2, l) in g.edge_list() {
+        let (src, dst) = (xml::escape(&v1.to_string()), xml::escape(&v2.to_string()));
+        match l {
+            Some(l) => lines.push(format!(
+                r#"    <edge source="{}" target="{}"><data key="label">{}</data></edge>"#,
+                src,
+                dst,
+                xml::escape(&l.to_string())
+            )),
+            None => lines.push(format!(r#"    <edge source="{}" target="{}"/>"#, src, dst)),
+        }
+    }
+    lines.push("  </graph>".to_string());
+    lines.push("</graphml>".to_string());
+    lines.join("\n")
+}
+
+/// Reads the first graph in a GraphML document. Edge labels are read from the
+/// edge data whose key is declared with `attr.name="label"`; other data are ignored.
+pub fn from_graphml<G, T, U>(s: &str) -> Result<G, FormatError>
+where
+    G: EdgeList<T, U>,
+    T: Hash + Copy + Eq + Ord + FromStr,
+    U: Copy + Eq + FromStr,
+{
+    use xml::Event;
+
+    let parse = |v: &str| -> Result<T, FormatError> {
+        v.parse()
+            .map_err(|_| FormatError::InvalidValue(v.to_string()))
+    };
+    let require = |attrs: &[(String, String)], name: &str| -> Result<String, FormatError> {
+        xml::attr(attrs, name)
+            .map(|v| v.to_string())
+            .ok_or_else(|| FormatError::Syntax(format!("missing attribute \"{}\"", name)))
+    };
+
+    let events = xml::parse(s).map_err(FormatError::Syntax)?;
+    let mut label_key = None;
+    let mut in_graph = false;
+    let mut nodes = vec![];
+    let mut edges = vec![];
+    // end nodes of the edges as written, for error messages
+    let mut names = vec![];
+    // the edge being read, and whether the current data is its label
+    let mut edge: Option<(T, T, Option<U>)> = None;
+    let mut in_label = false;
+    for event in events {
+        match event {
+            Event::Start(name, attrs) => match name.as_str() {
+                "key"
+                    if xml::attr(&attrs, "attr.name") == Some("label")
+                        && matches!(
+                            xml::attr(&attrs, "for"),
+                            None | Some("edge") | Some("all")
+                        ) =>
+                {
+                    label_key = Some(require(&attrs, "id")?);
+                }
+                "graph" if !in_graph => {
+                    let directed = xml::attr(&attrs, "edgedefault") == Some("directed");
+                    if directed != is_directed::<G, T, U>() {
+                        return Err(FormatError::Directedness);
+                    }
+                    in_graph = true;
+                }
+                "node" if in_graph => nodes.push(parse(&require(&attrs, "id")?)?),
+                "edge" if in_graph => {
+                    let (v1, v2) = (require(&attrs, "source")?, require(&attrs, "target")?);
+                    edge = Some((parse(&v1)?, parse(&v2)?, None));
+                    names.push(format!("{}-{}", v1, v2));
+                }
+                "data" => {
+                    in_label = edge.is_some()
+                        && label_key.is_some()
+                        && xml::attr(&attrs, "key") == label_key.as_deref();
+                }
+                _ => (),
+            },
+            Event::Text(text) if in_label => {
+                let label = text
+                    .trim()
+                    .parse()
+                    .map_err(|_| FormatError::InvalidValue(text.clone()))?;
+                if let Some(e) = edge.as_mut() {
+                    e.2 = Some(label);
+                }
+            }
+            Event::End(name) => match name.as_str() {
+                "graph" if in_graph => break,
+                "edge" => edges.extend(edge.take()),
+                "data" => in_label = false,
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+    if !in_graph {
+        return Err(FormatError::Syntax("no graph element".to_string()));
+    }
+    for ((v1, v2, _), name) in edges.iter().zip(names) {
+        if !nodes.contains(v1) || !nodes.contains(v2) {
+            return Err(FormatError::UnknownNode(name));
+        }
+    }
+    Ok(G::from_lists(&nodes, &edges))
+}
+
+// whether graphs of type `G` are directed, asked before reading any edges
+fn is_directed<G, T, U>() -> bool
+where
+    G: EdgeList<T, U>,
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    G::from_lists(&[], &[]).is_directed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::{Graph, LabeledDigraph, LabeledGraph};
+    use P80::{digraph_converters, graph_converters};
+
+    #[test]
+    fn test_node_link() {
+        let g = graph_converters::labeled::from_string("[a-b/5, b-c/3, d]");
+        let json = to_node_link(&g);
+        assert_eq!(
+            json,
+            r#"{"directed":false,"links":[{"label":5,"source":"a","target":"b"},{"label":3,"source":"b","target":"c"}],"multigraph":false,"nodes":[{"id":"a"},{"id":"b"},{"id":"c"},{"id":"d"}]}"#
+        );
+        let g2: LabeledGraph<char, i32> = from_node_link(&json).unwrap();
+        assert!(g == g2);
+
+        // parallel edges
+        let mut g = Graph::new();
+        g.add_node('a');
+        g.add_node('b');
+        g.add_edge('a', 'b');
+        g.add_edge('b', 'a');
+        let json = to_node_link(&g);
+        assert_eq!(
+            json,
+            r#"{"directed":false,"links":[{"source":"a","target":"b"},{"source":"a","target":"b"}],"multigraph":true,"nodes":[{"id":"a"},{"id":"b"}]}"#
+        );
+        let g2: Graph<char> = from_node_link(&json).unwrap();
+        assert_eq!(g2.edge_list(), g.edge_list());
+        assert_eq!(g2.get_node(&'a').unwrap().adjacents(), vec!['b', 'b']);
+    }
+
+    #[test]
+    fn test_node_link_errors() {
+        let json =
+            r#"{"directed":true,"nodes":[{"id":1},{"id":2}],"links":[{"source":1,"target":2}]}"#;
+        let g: LabeledDigraph<u32, ()> = from_node_link(json).unwrap();
+        assert_eq!(g.get_node(&1).unwrap().adjacents(), vec![2]);
+        assert_eq!(
+            from_node_link::<Graph<u32>, _, _>(json),
+            Err(FormatError::Directedness)
+        );
+        let json = r#"{"nodes":[{"id":1}],"links":[{"source":1,"target":2}]}"#;
+        assert!(matches!(
+            from_node_link::<Graph<u32>, _, _>(json),
+            Err(FormatError::UnknownNode(_))
+        ));
+        assert!(matches!(
+            from_node_link::<Graph<u32>, _, _>("{\"nodes\":"),
+            Err(FormatError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn test_graphml() {
+        let g = digraph_converters::labeled::from_string("[p>q/9, m>q/7, k, p>m/5]");
+        let graphml = to_graphml(&g);
+        assert!(graphml.contains(r#"<graph id="G" edgedefault="directed">"#));
+        assert!(
+            graphml.contains(r#"<edge source="p" target="q"><data key="label">9</data></edge>"#)
+        );
+        let g2: LabeledDigraph<char, i32> = from_graphml(&graphml).unwrap();
+        assert_eq!(g2.node_list(), vec!['k', 'm', 'p', 'q']);
+        let mut arcs = g2.get_arcs();
+        arcs.sort();
+        assert_eq!(
+            arcs,
+            vec![
+                ('m', 'q', Some(7)),
+                ('p', 'm', Some(5)),
+                ('p', 'q', Some(9))
+            ]
+        );
+        assert_eq!(
+            from_graphml::<LabeledGraph<char, i32>, _, _>(&graphml),
+            Err(FormatError::Directedness)
+        );
+    }
+
+    #[test]
+    fn test_read_graphml() {
+        let graphml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- written by another tool -->
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="color" attr.type="string"/>
+  <key id="d1" for="edge" attr.name="label" attr.type="int"/>
+  <graph id="G" edgedefault="undirected">
+    <node id="a"><data key="d0">red</data></node>
+    <node id="b"/>
+    <node id="&amp;"/>
+    <edge source="a" target="b"><data key="d1"> 42 </data></edge>
+    <edge id="e1" source="b" target="&amp;"></edge>
+  </graph>
+</graphml>"#;
+        let g: LabeledGraph<char, i32> = from_graphml(graphml).unwrap();
+        assert_eq!(g.node_list(), vec!['&', 'a', 'b']);
+        assert_eq!(g.get_edges(), vec![('a', 'b', Some(42)), ('b', '&', None)]);
+
+        let graphml = graphml.replace(r#"<node id="b"/>"#, "");
+        assert_eq!(
+            from_graphml::<LabeledGraph<char, i32>, _, _>(&graphml),
+            Err(FormatError::UnknownNode("a-b".to_string()))
+        );
+        assert!(matches!(
+            from_graphml::<LabeledGraph<char, i32>, _, _>("<graphml><graph>"),
+            Err(FormatError::Syntax(_))
+        ));
+    }
+}
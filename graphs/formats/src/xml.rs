@@ -0,0 +1,210 @@
+// A minimal XML reader that is just enough for GraphML; it understands
+// elements, attributes, text, comments, processing instructions and the
+// predefined entities, but not DTDs or CDATA sections.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// Start tag with attributes; a self-closing tag emits both Start and End.
+    Start(String, Vec<(String, String)>),
+    End(String),
+    /// Text between tags (with entities resolved); whitespace-only text is skipped.
+    Text(String),
+}
+
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn unescape(s: &str) -> Result<String, String> {
+    let mut res = String::new();
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        res.push_str(&rest[..i]);
+        let end = match rest[i..].find(';') {
+            Some(j) => i + j,
+            None => return Err(format!("unterminated entity in \"{}\"", s)),
+        };
+        let c = match &rest[i + 1..end] {
+            "amp" => '&',
+            "lt" => '<',
+            "gt" => '>',
+            "quot" => '"',
+            "apos" => '\'',
+            e if e.starts_with("#x") => char_from(u32::from_str_radix(&e[2..], 16).ok())?,
+            e if e.starts_with('#') => char_from(e[1..].parse().ok())?,
+            e => return Err(format!("unknown entity &{};", e)),
+        };
+        res.push(c);
+        rest = &rest[end + 1..];
+    }
+    res.push_str(rest);
+    Ok(res)
+}
+
+fn char_from(code: Option<u32>) -> Result<char, String> {
+    code.and_then(std::char::from_u32)
+        .ok_or_else(|| "invalid character reference".to_string())
+}
+
+pub fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// Parses a whole document; tags must be properly nested.
+pub fn parse(s: &str) -> Result<Vec<Event>, String> {
+    let mut events = vec![];
+    let mut open: Vec<String> = vec![];
+    let mut rest = s;
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = &rest[..end];
+            if !text.trim().is_empty() {
+                events.push(Event::Text(unescape(text)?));
+            }
+            rest = &rest[end..];
+            continue;
+        }
+        let (terminator, skip) = if rest.starts_with("<!--") {
+            ("-->", true)
+        } else if rest.starts_with("<?") {
+            ("?>", true)
+        } else if rest.starts_with("<!") {
+            (">", true)
+        } else {
+            (">", false)
+        };
+        let end = if skip {
+            rest.find(terminator)
+        } else {
+            tag_end(rest)
+        };
+        let end = match end {
+            Some(i) => i,
+            None => return Err("unterminated tag".to_string()),
+        };
+        let tag = &rest[1..end];
+        rest = &rest[end + terminator.len()..];
+        if skip {
+            continue;
+        }
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            match open.pop() {
+                Some(ref expected) if expected == name => events.push(Event::End(name.to_string())),
+                _ => return Err(format!("unexpected end tag </{}>", name)),
+            }
+            continue;
+        }
+        let (tag, self_closing) = match tag.strip_suffix('/') {
+            Some(t) => (t, true),
+            None => (tag, false),
+        };
+        let (name, attrs) = parse_tag(tag)?;
+        events.push(Event::Start(name.clone(), attrs));
+        if self_closing {
+            events.push(Event::End(name));
+        } else {
+            open.push(name);
+        }
+    }
+    match open.pop() {
+        Some(name) => Err(format!("element <{}> is not closed", name)),
+        None => Ok(events),
+    }
+}
+
+// the position of the `>` closing the tag at the start of `s`; a `>` in a
+// quoted attribute value does not close it
+fn tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '>') => return Some(i),
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            _ => (),
+        }
+    }
+    None
+}
+
+// parses `name key1="value1" key2='value2'`
+fn parse_tag(tag: &str) -> Result<(String, Vec<(String, String)>), String> {
+    let tag = tag.trim();
+    let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+    let name = &tag[..name_end];
+    if name.is_empty() {
+        return Err("empty tag name".to_string());
+    }
+    let mut attrs = vec![];
+    let mut rest = tag[name_end..].trim_start();
+    while !rest.is_empty() {
+        let eq = match rest.find('=') {
+            Some(i) => i,
+            None => return Err(format!("invalid attribute in <{}>", name)),
+        };
+        let key = rest[..eq].trim().to_string();
+        let value = rest[eq + 1..].trim_start();
+        let quote = match value.chars().next() {
+            Some(q) if q == '"' || q == '\'' => q,
+            _ => return Err(format!("attribute {} is not quoted", key)),
+        };
+        let close = match value[1..].find(quote) {
+            Some(i) => i + 1,
+            None => return Err(format!("attribute {} is not terminated", key)),
+        };
+        attrs.push((key, unescape(&value[1..close])?));
+        rest = value[close + 1..].trim_start();
+    }
+    Ok((name.to_string(), attrs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let events =
+            parse(r#"<?xml version="1.0"?><a x="1 &lt; 2"><!-- c --><b y='&#65;'/>t&amp;u</a>"#);
+        assert_eq!(
+            events,
+            Ok(vec![
+                Event::Start(
+                    "a".to_string(),
+                    vec![("x".to_string(), "1 < 2".to_string())]
+                ),
+                Event::Start("b".to_string(), vec![("y".to_string(), "A".to_string())]),
+                Event::End("b".to_string()),
+                Event::Text("t&u".to_string()),
+                Event::End("a".to_string()),
+            ])
+        );
+        // a `>` in an attribute value
+        assert_eq!(
+            parse(r#"<data key="d0" v="a>b"/>"#),
+            Ok(vec![
+                Event::Start(
+                    "data".to_string(),
+                    vec![
+                        ("key".to_string(), "d0".to_string()),
+                        ("v".to_string(), "a>b".to_string())
+                    ]
+                ),
+                Event::End("data".to_string()),
+            ])
+        );
+        assert!(parse(r#"<a x="1>"#).is_err());
+        assert!(parse("<a><b></a>").is_err());
+        assert!(parse("<a x=1/>").is_err());
+        assert_eq!(unescape(&escape("<\"a&b'>")), Ok("<\"a&b'>".to_string()));
+    }
+}
@@ -0,0 +1,54 @@
+use graph::{LabeledDigraph, MarkovChain};
+
+pub fn main() {
+    // page views following each page on a small shop, counted from logs; a
+    // visit ends when the visitor leaves or buys
+    let clicks = vec![
+        ("home", "search", 40),
+        ("home", "product", 30),
+        ("home", "left", 30),
+        ("search", "product", 60),
+        ("search", "home", 10),
+        ("search", "left", 30),
+        ("product", "cart", 20),
+        ("product", "search", 50),
+        ("product", "left", 30),
+        ("cart", "bought", 50),
+        ("cart", "product", 30),
+        ("cart", "left", 20),
+    ];
+    let mut g = LabeledDigraph::new();
+    for &page in &["home", "search", "product", "cart", "bought", "left"] {
+        g.add_node(page);
+    }
+    for (from, to, count) in clicks {
+        g.add_labeled_arc(from, to, count);
+    }
+    let chain = MarkovChain::new(&g);
+
+    println!("a visit: {:?}", chain.random_walk("home", 6, 42));
+
+    let absorption = chain.absorption().unwrap();
+    println!("visits end in {:?}", absorption.absorbing);
+    for &page in &["home", "search", "product", "cart"] {
+        println!(
+            "from {}: {:.2} more page views, bought with probability {:.2}",
+            page, absorption.steps[page], absorption.probabilities[page]["bought"]
+        );
+    }
+
+    // visitors who return to the home page after leaving or buying
+    g.add_labeled_arc("bought", "home", 1);
+    g.add_labeled_arc("left", "home", 1);
+    let chain = MarkovChain::new(&g);
+    let steps = chain.hitting_time("home", "bought", 10000, 100_000, 42);
+    println!("page views until a purchase: {:.1}", steps.unwrap());
+    let steps = chain.cover_time("home", 10000, 100_000, 42);
+    println!("page views until every page is seen: {:.1}", steps.unwrap());
+    let pi = chain.stationary_distribution();
+    let mut pages: Vec<(&str, f64)> = pi.into_iter().collect();
+    pages.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    for (page, p) in pages {
+        println!("{:>8}: {:.3}", page, p);
+    }
+}
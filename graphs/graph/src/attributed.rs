@@ -0,0 +1,404 @@
+//! Graphs whose nodes and edges carry arbitrary data.
+
+use super::{Directed, EdgeWeights, GraphBase, Neighbors};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Graph or digraph whose nodes and edges carry data of any type (structs,
+/// strings, maps of attributes, ...), not only `Copy` labels.
+///
+/// Nodes are still identified by their values, through which their data and
+/// the data of the edges between them can be read and modified in place.
+/// Algorithms that need edge weights run on the view returned by
+/// [`weighted_by()`](#method.weighted_by), which computes the weight of each edge
+/// from its data.
+///
+/// The graph holds no `Rc` or `RefCell`, so it is `Send` and `Sync` if its
+/// values and data are, and can be shared by threads.
+#[derive(Debug, Clone)]
+pub struct AttributedGraph<T, N, E>
+where
+    T: Hash + Copy + Eq,
+{
+    // node values in the order they were added
+    values: Vec<T>,
+    nodes: HashMap<T, NodeEntry<N>>,
+    edges: Vec<EdgeEntry<T, E>>,
+    directed: bool,
+}
+
+#[derive(Debug, Clone)]
+struct NodeEntry<N> {
+    data: N,
+    // indices of the incident edges (outgoing arcs for digraphs); an undirected
+    // self-loop is listed twice, as it is incident to the node at both ends
+    edges: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+struct EdgeEntry<T, E> {
+    src: T,
+    dst: T,
+    data: E,
+}
+
+impl<T, N, E> AttributedGraph<T, N, E>
+where
+    T: Hash + Copy + Eq,
+{
+    /// Constructs an empty undirected graph.
+    pub fn new() -> Self {
+        Self::with_direction(false)
+    }
+
+    /// Constructs an empty digraph.
+    pub fn new_directed() -> Self {
+        Self::with_direction(true)
+    }
+
+    fn with_direction(directed: bool) -> Self {
+        AttributedGraph {
+            values: vec![],
+            nodes: HashMap::new(),
+            edges: vec![],
+            directed,
+        }
+    }
+
+    /// Returns the number of nodes in this graph.
+    pub fn size(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Adds a node with its data to this graph. If the node is already there,
+    /// only its data is replaced and the old data is returned.
+    pub fn add_node(&mut self, v: T, data: N) -> Option<N> {
+        match self.nodes.get_mut(&v) {
+            Some(node) => Some(std::mem::replace(&mut node.data, data)),
+            None => {
+                self.values.push(v);
+                self.nodes.insert(
+                    v,
+                    NodeEntry {
+                        data,
+                        edges: vec![],
+                    },
+                );
+                None
+            }
+        }
+    }
+
+    /// Adds an edge (an arc from `v1` to `v2` for digraphs) with its data.
+    /// Panics if either node is not in this graph.
+    pub fn add_edge(&mut self, v1: T, v2: T, data: E) {
+        assert!(
+            self.nodes.contains_key(&v1) && self.nodes.contains_key(&v2),
+            "both ends of an edge must be in the graph"
+        );
+        let k = self.edges.len();
+        self.edges.push(EdgeEntry {
+            src: v1,
+            dst: v2,
+            data,
+        });
+        self.nodes.get_mut(&v1).unwrap().edges.push(k);
+        if !self.directed {
+            self.nodes.get_mut(&v2).unwrap().edges.push(k);
+        }
+    }
+
+    /// Returns the data of node `v`.
+    pub fn node(&self, v: &T) -> Option<&N> {
+        self.nodes.get(v).map(|node| &node.data)
+    }
+
+    /// Returns the data of node `v` for modification.
+    pub fn node_mut(&mut self, v: &T) -> Option<&mut N> {
+        self.nodes.get_mut(v).map(|node| &mut node.data)
+    }
+
+    /// Returns the data of the edge (arc) from `v1` to `v2`; the first one
+    /// added if there are several.
+    pub fn edge(&self, v1: &T, v2: &T) -> Option<&E> {
+        self.find_edge(v1, v2).map(|k| &self.edges[k].data)
+    }
+
+    /// Returns the data of the edge (arc) from `v1` to `v2` for modification.
+    pub fn edge_mut(&mut self, v1: &T, v2: &T) -> Option<&mut E> {
+        let k = self.find_edge(v1, v2)?;
+        Some(&mut self.edges[k].data)
+    }
+
+    fn find_edge(&self, v1: &T, v2: &T) -> Option<usize> {
+        let node = self.nodes.get(v1)?;
+        node.edges
+            .iter()
+            .copied()
+            .find(|k| self.other_end(*k, v1) == *v2)
+    }
+
+    // the end of the k-th edge that is not v (v itself for self-loops)
+    fn other_end(&self, k: usize, v: &T) -> T {
+        let e = &self.edges[k];
+        if e.src == *v {
+            e.dst
+        } else {
+            e.src
+        }
+    }
+
+    /// Returns the adjacent nodes (successors for digraphs) of `v` with the data
+    /// of the edges to them, in the order the edges were added. As for
+    /// [`Neighbors`](../traits/trait.Neighbors.html), `v` itself is listed twice
+    /// for an undirected self-loop.
+    pub fn adjacents(&self, v: &T) -> Vec<(T, &E)> {
+        match self.nodes.get(v) {
+            Some(node) => node
+                .edges
+                .iter()
+                .map(|k| (self.other_end(*k, v), &self.edges[*k].data))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Returns all nodes with their data, in the order they were added.
+    pub fn nodes(&self) -> Vec<(T, &N)> {
+        self.values
+            .iter()
+            .map(|v| (*v, &self.nodes[v].data))
+            .collect()
+    }
+
+    /// Returns all edges as `(v1, v2, data)`, in the order they were added.
+    pub fn edges(&self) -> Vec<(T, T, &E)> {
+        self.edges.iter().map(|e| (e.src, e.dst, &e.data)).collect()
+    }
+
+    /// Returns a view of this graph whose edge labels are computed from the
+    /// edge data by `weight`, so that it can be passed to algorithms taking
+    /// [`EdgeWeights`](../traits/trait.EdgeWeights.html).
+    pub fn weighted_by<W, F>(&self, weight: F) -> WeightedBy<'_, T, N, E, F>
+    where
+        W: Copy + Eq,
+        F: Fn(&E) -> W,
+    {
+        WeightedBy {
+            graph: self,
+            weight,
+        }
+    }
+}
+
+impl<T, N, E> Default for AttributedGraph<T, N, E>
+where
+    T: Hash + Copy + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, N, E> GraphBase<T> for AttributedGraph<T, N, E>
+where
+    T: Hash + Copy + Eq,
+{
+    fn node_values(&self) -> Cow<'_, [T]> {
+        Cow::Borrowed(&self.values)
+    }
+
+    fn contains(&self, v: &T) -> bool {
+        self.nodes.contains_key(v)
+    }
+
+    fn node_count(&self) -> usize {
+        self.size()
+    }
+}
+
+impl<T, N, E> Neighbors<T> for AttributedGraph<T, N, E>
+where
+    T: Hash + Copy + Eq,
+{
+    fn neighbors(&self, v: &T) -> Cow<'_, [T]> {
+        match self.nodes.get(v) {
+            Some(node) => Cow::Owned(node.edges.iter().map(|k| self.other_end(*k, v)).collect()),
+            None => Cow::Borrowed(&[]),
+        }
+    }
+}
+
+impl<T, N, E> Directed for AttributedGraph<T, N, E>
+where
+    T: Hash + Copy + Eq,
+{
+    fn is_directed(&self) -> bool {
+        self.directed
+    }
+}
+
+/// View of an [`AttributedGraph`](struct.AttributedGraph.html) labeling every
+/// edge with a weight computed from its data.
+pub struct WeightedBy<'a, T, N, E, F>
+where
+    T: Hash + Copy + Eq,
+{
+    graph: &'a AttributedGraph<T, N, E>,
+    weight: F,
+}
+
+impl<'a, T, N, E, F> GraphBase<T> for WeightedBy<'a, T, N, E, F>
+where
+    T: Hash + Copy + Eq,
+{
+    fn node_values(&self) -> Cow<'_, [T]> {
+        self.graph.node_values()
+    }
+
+    fn contains(&self, v: &T) -> bool {
+        self.graph.contains(v)
+    }
+
+    fn node_count(&self) -> usize {
+        self.graph.size()
+    }
+}
+
+impl<'a, T, N, E, F> Neighbors<T> for WeightedBy<'a, T, N, E, F>
+where
+    T: Hash + Copy + Eq,
+{
+    fn neighbors(&self, v: &T) -> Cow<'_, [T]> {
+        self.graph.neighbors(v)
+    }
+}
+
+impl<'a, T, N, E, F, W> EdgeWeights<T> for WeightedBy<'a, T, N, E, F>
+where
+    T: Hash + Copy + Eq,
+    W: Copy + Eq,
+    F: Fn(&E) -> W,
+{
+    type Weight = W;
+
+    fn neighbors_with_weight(&self, v: &T) -> Vec<(T, Option<W>)> {
+        self.graph
+            .adjacents(v)
+            .into_iter()
+            .map(|(w, e)| (w, Some((self.weight)(e))))
+            .collect()
+    }
+}
+
+impl<'a, T, N, E, F> Directed for WeightedBy<'a, T, N, E, F>
+where
+    T: Hash + Copy + Eq,
+{
+    fn is_directed(&self) -> bool {
+        self.graph.is_directed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Road {
+        name: String,
+        km: u32,
+        toll: bool,
+    }
+
+    fn road(name: &str, km: u32, toll: bool) -> Road {
+        Road {
+            name: name.to_string(),
+            km,
+            toll,
+        }
+    }
+
+    #[test]
+    fn test_attributed_graph() {
+        let mut g: AttributedGraph<&str, Vec<String>, Road> = AttributedGraph::new();
+        g.add_node("lyon", vec!["silk".to_string()]);
+        g.add_node("paris", vec![]);
+        g.add_node("dijon", vec![]);
+        g.add_edge("paris", "lyon", road("A6", 465, true));
+        g.add_edge("paris", "dijon", road("A5", 315, false));
+
+        assert_eq!(g.size(), 3);
+        assert!(!g.is_directed());
+        assert_eq!(g.neighbors(&"lyon").as_ref(), &["paris"]);
+        assert_eq!(g.edge(&"lyon", &"paris").unwrap().name, "A6");
+        assert_eq!(g.edge(&"lyon", &"dijon"), None);
+
+        g.node_mut(&"dijon").unwrap().push("mustard".to_string());
+        g.edge_mut(&"dijon", &"paris").unwrap().toll = true;
+        assert_eq!(g.node(&"dijon").unwrap(), &vec!["mustard".to_string()]);
+        assert_eq!(g.edges()[1], ("paris", "dijon", &road("A5", 315, true)));
+
+        // adding a node again only replaces its data
+        let old = g.add_node("lyon", vec![]);
+        assert_eq!(old, Some(vec!["silk".to_string()]));
+        assert_eq!(g.degree(&"lyon"), 1);
+        assert_eq!(g.nodes().len(), 3);
+    }
+
+    #[test]
+    fn test_self_loops() {
+        use crate::Graph;
+        // an undirected self-loop counts twice, as in LabeledGraph
+        let mut g: AttributedGraph<char, (), ()> = AttributedGraph::new();
+        let mut lg = Graph::new();
+        for v in "ab".chars() {
+            g.add_node(v, ());
+            lg.add_node(v);
+        }
+        for (v1, v2) in &[('a', 'a'), ('a', 'b')] {
+            g.add_edge(*v1, *v2, ());
+            lg.add_edge(*v1, *v2);
+        }
+        assert_eq!(g.neighbors(&'a').as_ref(), &['a', 'a', 'b']);
+        for v in "ab".chars() {
+            assert_eq!(g.degree(&v), lg.degree(&v));
+        }
+        assert_eq!(g.degree(&'a'), 3);
+        assert_eq!(g.edges().len(), 2);
+
+        // a directed self-loop is one outgoing arc
+        let mut g: AttributedGraph<char, (), ()> = AttributedGraph::new_directed();
+        g.add_node('a', ());
+        g.add_edge('a', 'a', ());
+        assert_eq!(g.neighbors(&'a').as_ref(), &['a']);
+    }
+
+    #[test]
+    fn test_weighted_by() {
+        let mut g: AttributedGraph<char, (), Road> = AttributedGraph::new_directed();
+        for v in "abc".chars() {
+            g.add_node(v, ());
+        }
+        g.add_edge('a', 'b', road("x", 10, true));
+        g.add_edge('a', 'c', road("y", 20, false));
+        g.add_edge('c', 'c', road("z", 1, false));
+
+        let km = g.weighted_by(|r| r.km);
+        assert!(km.is_directed());
+        assert_eq!(
+            km.neighbors_with_weight(&'a'),
+            vec![('b', Some(10)), ('c', Some(20))]
+        );
+        assert_eq!(km.weight(&'b', &'a'), None);
+        assert_eq!(km.weight(&'c', &'c'), Some(1));
+
+        let cost = g.weighted_by(|r| if r.toll { r.km * 2 } else { r.km });
+        assert_eq!(cost.weight(&'a', &'b'), Some(20));
+
+        fn assert_send_sync<G: Send + Sync>(_: &G) {}
+        assert_send_sync(&g);
+        assert_send_sync(&cost);
+    }
+}
@@ -0,0 +1,384 @@
+//! Compressed sparse row (CSR) representation of graphs.
+
+use super::{Directed, EdgeWeights, GraphBase, LabeledDigraph, LabeledGraph, Neighbors};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+/// Frozen (immutable) graph or digraph in compressed sparse row layout.
+///
+/// Nodes are numbered by their sorted values. The adjacent nodes of the `i`-th
+/// node are stored contiguously in `targets[offsets[i]..offsets[i + 1]]` and
+/// their labels at the same positions in `labels`, so that a neighbourhood is a
+/// plain slice and the whole graph takes only a few allocations. In an undirected
+/// graph each edge is stored in both directions.
+///
+/// Unlike `LabeledGraph`, which is built on `Rc` and `RefCell`, a `CsrGraph` is
+/// `Send` and `Sync` (if its values and labels are), so it can be shared by
+/// threads, e.g. in an `Arc`, and passed to parallel algorithms.
+#[derive(Debug, Clone)]
+pub struct CsrGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    values: Vec<T>,
+    index: HashMap<T, usize>,
+    offsets: Vec<usize>,
+    targets: Vec<T>,
+    labels: Vec<Option<U>>,
+    directed: bool,
+}
+
+impl<T, U> CsrGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    /// Builds a frozen copy of an undirected graph.
+    pub fn from_graph(g: &LabeledGraph<T, U>) -> Self {
+        let mut values = g.get_node_values();
+        values.sort();
+        let adjs: Vec<Vec<(T, Option<U>)>> = values
+            .iter()
+            .map(|v| g.get_node(v).unwrap().adjacents_with_label())
+            .collect();
+        Self::from_adjacents(values, adjs, false)
+    }
+
+    /// Builds a frozen copy of a digraph.
+    pub fn from_digraph(g: &LabeledDigraph<T, U>) -> Self {
+        let mut values = g.get_node_values();
+        values.sort();
+        let adjs: Vec<Vec<(T, Option<U>)>> = values
+            .iter()
+            .map(|v| g.get_node(v).unwrap().adjacents_with_label())
+            .collect();
+        Self::from_adjacents(values, adjs, true)
+    }
+
+    fn from_adjacents(values: Vec<T>, adjs: Vec<Vec<(T, Option<U>)>>, directed: bool) -> Self {
+        let mut offsets = Vec::with_capacity(values.len() + 1);
+        let mut targets = vec![];
+        let mut labels = vec![];
+        offsets.push(0);
+        for list in adjs {
+            for (w, l) in list {
+                targets.push(w);
+                labels.push(l);
+            }
+            offsets.push(targets.len());
+        }
+        let index = values.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        CsrGraph {
+            values,
+            index,
+            offsets,
+            targets,
+            labels,
+            directed,
+        }
+    }
+
+    /// Returns true if this is a digraph.
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Returns the number of nodes.
+    pub fn size(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the number of edges (or arcs).
+    pub fn edge_count(&self) -> usize {
+        if self.directed {
+            self.targets.len()
+        } else {
+            // every edge is stored twice, self-loops included
+            self.targets.len() / 2
+        }
+    }
+
+    /// Returns all node values in sorted order.
+    pub fn get_node_values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Checks if `v` is in this graph.
+    pub fn contains(&self, v: &T) -> bool {
+        self.index.contains_key(v)
+    }
+
+    /// Returns the position of `v` in the sorted node values.
+    pub fn index_of(&self, v: &T) -> Option<usize> {
+        self.index.get(v).copied()
+    }
+
+    fn range(&self, v: &T) -> std::ops::Range<usize> {
+        match self.index.get(v) {
+            Some(&i) => self.offsets[i]..self.offsets[i + 1],
+            None => 0..0,
+        }
+    }
+
+    /// Returns adjacent node values of `v` (successors for digraphs).
+    pub fn adjacents(&self, v: &T) -> &[T] {
+        &self.targets[self.range(v)]
+    }
+
+    /// Returns the labels of the edges to the adjacent nodes, in the same order as `adjacents()`.
+    pub fn labels(&self, v: &T) -> &[Option<U>] {
+        &self.labels[self.range(v)]
+    }
+
+    /// Returns adjacent node values with edge labels.
+    pub fn adjacents_with_label(&self, v: &T) -> Vec<(T, Option<U>)> {
+        let range = self.range(v);
+        self.targets[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.labels[range].iter().copied())
+            .collect()
+    }
+
+    /// Returns the degree (out-degree for digraphs) of `v`.
+    pub fn degree(&self, v: &T) -> usize {
+        self.range(v).len()
+    }
+}
+
+impl<T, U> CsrGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord + FromStr,
+    U: Copy + Eq + FromStr,
+{
+    /// Reads a graph from an edge list without building any intermediate graph.
+    ///
+    /// Each line is `v1 v2` or `v1 v2 label` separated by whitespace; a line with a
+    /// single node adds an isolated node. Empty lines and lines starting with `#`
+    /// are skipped.
+    pub fn read_edge_list<R: BufRead>(reader: R, directed: bool) -> io::Result<Self> {
+        let invalid = |line_no: usize, msg: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: {}", line_no + 1, msg),
+            )
+        };
+        let mut index: HashMap<T, usize> = HashMap::new();
+        let mut values = vec![];
+        let mut edges: Vec<(usize, usize, Option<U>)> = vec![];
+        for (line_no, line) in reader.lines().enumerate() {
+            let line = line?;
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.is_empty() || tokens[0].starts_with('#') {
+                continue;
+            }
+            if tokens.len() > 3 {
+                return Err(invalid(line_no, "too many fields"));
+            }
+            let mut ids = vec![];
+            for token in tokens.iter().take(2) {
+                let v: T = token
+                    .parse()
+                    .map_err(|_| invalid(line_no, "invalid node"))?;
+                let i = *index.entry(v).or_insert_with(|| {
+                    values.push(v);
+                    values.len() - 1
+                });
+                ids.push(i);
+            }
+            let label = match tokens.get(2) {
+                Some(l) => Some(l.parse().map_err(|_| invalid(line_no, "invalid label"))?),
+                None => None,
+            };
+            if ids.len() == 2 {
+                edges.push((ids[0], ids[1], label));
+            }
+        }
+
+        // renumber nodes by sorted values, then place the edges by counting sort
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by_key(|i| values[*i]);
+        let mut rank = vec![0; values.len()];
+        for (r, i) in order.iter().enumerate() {
+            rank[*i] = r;
+        }
+        let sorted: Vec<T> = order.iter().map(|i| values[*i]).collect();
+        let mut offsets = vec![0; sorted.len() + 1];
+        for (i, j, _) in &edges {
+            offsets[rank[*i] + 1] += 1;
+            if !directed {
+                offsets[rank[*j] + 1] += 1;
+            }
+        }
+        for k in 1..offsets.len() {
+            offsets[k] += offsets[k - 1];
+        }
+        let total = offsets[sorted.len()];
+        let mut next = offsets.clone();
+        let mut targets = vec![None; total];
+        let mut labels = vec![None; total];
+        let mut place = |src: usize, dst: T, l: Option<U>| {
+            targets[next[src]] = Some(dst);
+            labels[next[src]] = l;
+            next[src] += 1;
+        };
+        for (i, j, l) in edges {
+            place(rank[i], sorted[rank[j]], l);
+            if !directed {
+                place(rank[j], sorted[rank[i]], l);
+            }
+        }
+        let index = sorted.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        Ok(CsrGraph {
+            values: sorted,
+            index,
+            offsets,
+            targets: targets.into_iter().map(|t| t.unwrap()).collect(),
+            labels,
+            directed,
+        })
+    }
+}
+
+impl<T, U> GraphBase<T> for CsrGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    fn node_values(&self) -> Cow<'_, [T]> {
+        Cow::Borrowed(&self.values)
+    }
+
+    fn contains(&self, v: &T) -> bool {
+        self.index.contains_key(v)
+    }
+
+    fn node_count(&self) -> usize {
+        self.size()
+    }
+}
+
+impl<T, U> Neighbors<T> for CsrGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    fn neighbors(&self, v: &T) -> Cow<'_, [T]> {
+        Cow::Borrowed(self.adjacents(v))
+    }
+}
+
+impl<T, U> EdgeWeights<T> for CsrGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    type Weight = U;
+
+    fn neighbors_with_weight(&self, v: &T) -> Vec<(T, Option<U>)> {
+        self.adjacents_with_label(v)
+    }
+}
+
+impl<T, U> Directed for CsrGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    fn is_directed(&self) -> bool {
+        self.directed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<G: Send + Sync>(_: &G) {}
+
+    #[test]
+    fn test_from_graph() {
+        let mut g = LabeledGraph::<char, i32>::new();
+        g.add_node('a');
+        g.add_node('b');
+        g.add_node('c');
+        g.add_node('d');
+        g.add_labeled_edge('a', 'b', 2);
+        g.add_labeled_edge('b', 'c', 1);
+        g.add_labeled_edge('c', 'a', 3);
+        let csr = CsrGraph::from_graph(&g);
+        assert!(!csr.is_directed());
+        assert_eq!(csr.size(), 4);
+        assert_eq!(csr.edge_count(), 3);
+        assert_eq!(csr.get_node_values(), &['a', 'b', 'c', 'd']);
+        assert_eq!(csr.adjacents(&'a'), &['b', 'c']);
+        assert_eq!(csr.labels(&'a'), &[Some(2), Some(3)]);
+        assert_eq!(
+            csr.adjacents_with_label(&'c'),
+            vec![('b', Some(1)), ('a', Some(3))]
+        );
+        assert_eq!(csr.degree(&'d'), 0);
+        assert_eq!(csr.adjacents(&'x'), &[] as &[char]);
+    }
+
+    #[test]
+    fn test_share_between_threads() {
+        let mut g = LabeledGraph::<char, i32>::new();
+        g.add_node('a');
+        g.add_node('b');
+        g.add_labeled_edge('a', 'b', 1);
+        let g = std::sync::Arc::new(CsrGraph::from_graph(&g));
+        assert_send_sync(&g);
+        let handles: Vec<_> = "ab"
+            .chars()
+            .map(|v| {
+                let g = g.clone();
+                std::thread::spawn(move || g.degree(&v))
+            })
+            .collect();
+        let degrees: Vec<usize> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert_eq!(degrees, vec![1, 1]);
+    }
+
+    #[test]
+    fn test_from_digraph() {
+        let mut g = LabeledDigraph::<char, ()>::new();
+        g.add_node('a');
+        g.add_node('b');
+        g.add_node('c');
+        g.add_arc_with_value('a', 'b', None);
+        g.add_arc_with_value('a', 'c', None);
+        g.add_arc_with_value('c', 'a', None);
+        let csr = CsrGraph::from_digraph(&g);
+        assert!(csr.is_directed());
+        assert_eq!(csr.edge_count(), 3);
+        assert_eq!(csr.neighbors(&'a').as_ref(), &['b', 'c']);
+        assert_eq!(csr.neighbors(&'b').as_ref(), &[] as &[char]);
+        assert_eq!(csr.index_of(&'c'), Some(2));
+    }
+
+    #[test]
+    fn test_read_edge_list() {
+        let input = "# a small graph\n3 1 5\n1 2 7\n\n4\n2 3 1\n";
+        let csr = CsrGraph::<u32, i32>::read_edge_list(input.as_bytes(), false).unwrap();
+        assert_eq!(csr.get_node_values(), &[1, 2, 3, 4]);
+        assert_eq!(csr.edge_count(), 3);
+        assert_eq!(
+            csr.adjacents_with_label(&1),
+            vec![(3, Some(5)), (2, Some(7))]
+        );
+        assert_eq!(csr.adjacents(&3), &[1, 2]);
+        assert_eq!(csr.degree(&4), 0);
+
+        let csr = CsrGraph::<u32, i32>::read_edge_list(input.as_bytes(), true).unwrap();
+        assert_eq!(csr.adjacents(&3), &[1]);
+        assert_eq!(csr.adjacents(&2), &[3]);
+
+        let err = CsrGraph::<u32, i32>::read_edge_list("1 2\n1 x\n".as_bytes(), true);
+        assert_eq!(err.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,9 +1,25 @@
+use std::borrow::Cow;
 use std::cell::{Ref, RefCell};
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt;
 use std::hash::Hash;
 use std::rc::{Rc, Weak};
 
+pub mod attributed;
+pub mod csr;
+pub mod markov;
+#[cfg(feature = "serde")]
+mod serde_impl;
+pub mod traits;
+pub mod views;
+
+pub use attributed::AttributedGraph;
+pub use csr::CsrGraph;
+pub use markov::MarkovChain;
+pub use traits::{Directed, EdgeList, EdgeWeights, GraphBase, Neighbors};
+pub use views::{ImplicitGraph, UnderlyingGraph};
+
 /// Graph node
 #[derive(Debug, Clone)]
 pub struct Node<T, U>
@@ -99,8 +115,72 @@ where
     fn get_label(&self) -> Option<U> {
         self.label
     }
+
+    fn get_ends(&self) -> Option<(T, T, Option<U>)> {
+        let n1 = self.n1.upgrade()?;
+        let n2 = self.n2.upgrade()?;
+        let (v1, v2) = (*n1.borrow().get_value(), *n2.borrow().get_value());
+        Some((v1, v2, self.label))
+    }
+}
+
+/// Which edges a graph accepts, chosen when it is constructed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphKind {
+    /// Neither parallel edges nor self-loops.
+    Simple,
+    /// Parallel edges (e.g. several routes between two stops), but no self-loops.
+    Multigraph,
+    /// Parallel edges and self-loops.
+    Pseudograph,
+}
+
+impl GraphKind {
+    fn check<T: Copy + Eq>(self, v1: T, v2: T, linked: bool) -> Result<(), EdgeError<T>> {
+        if v1 == v2 && self != GraphKind::Pseudograph {
+            Err(EdgeError::SelfLoop(v1))
+        } else if linked && self == GraphKind::Simple {
+            Err(EdgeError::ParallelEdge(v1, v2))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Errors on adding an edge (arc) that the graph does not accept
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdgeError<T> {
+    /// The node is not in the graph.
+    UnknownNode(T),
+    /// A self-loop at the node in a simple graph or multigraph.
+    SelfLoop(T),
+    /// A second edge (arc) between the nodes in a simple graph.
+    ParallelEdge(T, T),
 }
 
+impl<T> EdgeError<T> {
+    fn reason(&self) -> &'static str {
+        match self {
+            EdgeError::UnknownNode(_) => "unknown node",
+            EdgeError::SelfLoop(_) => "self-loops are not allowed",
+            EdgeError::ParallelEdge(_, _) => "parallel edges are not allowed",
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Display for EdgeError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EdgeError::UnknownNode(v) | EdgeError::SelfLoop(v) => {
+                write!(f, "{}: {:?}", self.reason(), v)
+            }
+            EdgeError::ParallelEdge(v1, v2) => write!(f, "{}: {:?}, {:?}", self.reason(), v1, v2),
+        }
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for EdgeError<T> {}
+
 /// Undirected Labeled Graph
 #[derive(Debug, Clone)]
 pub struct LabeledGraph<T, U>
@@ -110,6 +190,7 @@ where
 {
     nodes: HashMap<T, Rc<RefCell<Node<T, U>>>>,
     edges: Vec<Rc<Edge<T, U>>>,
+    kind: GraphKind,
 }
 
 impl<T, U> LabeledGraph<T, U>
@@ -117,14 +198,25 @@ where
     T: Hash + Copy + Eq + Ord,
     U: Copy + Eq,
 {
-    /// Constructs an empty graph.
+    /// Constructs an empty graph that accepts parallel edges and self-loops.
     pub fn new() -> Self {
+        Self::with_kind(GraphKind::Pseudograph)
+    }
+
+    /// Constructs an empty graph that accepts only the edges allowed by `kind`.
+    pub fn with_kind(kind: GraphKind) -> Self {
         LabeledGraph {
             nodes: HashMap::new(),
             edges: vec![],
+            kind,
         }
     }
 
+    /// Returns which edges this graph accepts.
+    pub fn kind(&self) -> GraphKind {
+        self.kind
+    }
+
     /// Returns the number of nodes in this graph.
     pub fn size(&self) -> usize {
         self.nodes.len()
@@ -179,6 +271,34 @@ where
         }
     }
 
+    /// Returns all edges in this graph as `(v1, v2, label)`, in the order they were added.
+    pub fn get_edges(&self) -> Vec<(T, T, Option<U>)> {
+        self.edges.iter().filter_map(|e| e.get_ends()).collect()
+    }
+
+    /// Returns the number of edges between two nodes (self-loops if they are
+    /// the same node).
+    pub fn multiplicity(&self, v1: &T, v2: &T) -> usize {
+        self.get_edges()
+            .iter()
+            .filter(|(w1, w2, _)| (w1, w2) == (v1, v2) || (w1, w2) == (v2, v1))
+            .count()
+    }
+
+    /// Checks if this graph has neither parallel edges nor self-loops, whatever
+    /// its kind.
+    pub fn is_simple(&self) -> bool {
+        let mut pairs: Vec<(T, T)> = self
+            .get_edges()
+            .iter()
+            .map(|(v1, v2, _)| if v1 < v2 { (*v1, *v2) } else { (*v2, *v1) })
+            .collect();
+        let edges = pairs.len();
+        pairs.sort();
+        pairs.dedup();
+        pairs.len() == edges && pairs.iter().all(|(v1, v2)| v1 != v2)
+    }
+
     /// Adds a node to this graph.
     pub fn add_node(&mut self, v: T) {
         let node = Node {
@@ -193,7 +313,28 @@ where
         self.add_edge_with_value(v1, v2, Some(label));
     }
 
-    fn add_edge_with_value(&mut self, v1: T, v2: T, l: Option<U>) {
+    /// Adds an edge with an optional label to this graph. Panics if a node is
+    /// not in this graph or the edge is not allowed by its kind.
+    pub fn add_edge_with_value(&mut self, v1: T, v2: T, l: Option<U>) {
+        if let Err(e) = self.try_add_edge_with_value(v1, v2, l) {
+            panic!("{}", e.reason());
+        }
+    }
+
+    /// Adds an edge with an optional label to this graph, unless a node is not
+    /// in this graph or the edge is not allowed by its kind.
+    pub fn try_add_edge_with_value(
+        &mut self,
+        v1: T,
+        v2: T,
+        l: Option<U>,
+    ) -> Result<(), EdgeError<T>> {
+        for v in &[v1, v2] {
+            if !self.nodes.contains_key(v) {
+                return Err(EdgeError::UnknownNode(*v));
+            }
+        }
+        self.kind.check(v1, v2, self.is_linked(v1, v2))?;
         let n1 = self.nodes.get(&v1).unwrap();
         let n2 = self.nodes.get(&v2).unwrap();
         let edge = Rc::new(Edge {
@@ -207,9 +348,12 @@ where
             .add_edge(Rc::downgrade(self.edges.last().unwrap()));
         n2.borrow_mut()
             .add_edge(Rc::downgrade(self.edges.last().unwrap()));
+        Ok(())
     }
 }
 
+// graphs are equal if they have the same nodes and edges (with the same
+// multiplicities), whatever their kinds
 impl<T, U> PartialEq for LabeledGraph<T, U>
 where
     T: Hash + Copy + Eq + Ord,
@@ -233,8 +377,10 @@ where
             if my_adjs.len() != other_adjs.len() {
                 return false;
             }
-            for adj in my_adjs {
-                if !other_adjs.contains(&adj) {
+            // parallel edges must be as many in both graphs
+            for adj in &my_adjs {
+                let count = |adjs: &[(T, Option<U>)]| adjs.iter().filter(|a| *a == adj).count();
+                if count(&my_adjs) != count(&other_adjs) {
                     return false;
                 }
             }
@@ -243,6 +389,95 @@ where
     }
 }
 
+impl<T, U> GraphBase<T> for LabeledGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    fn node_values(&self) -> Cow<'_, [T]> {
+        Cow::Owned(self.get_node_values())
+    }
+
+    fn contains(&self, v: &T) -> bool {
+        self.nodes.contains_key(v)
+    }
+
+    fn node_count(&self) -> usize {
+        self.size()
+    }
+}
+
+impl<T, U> Neighbors<T> for LabeledGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    fn neighbors(&self, v: &T) -> Cow<'_, [T]> {
+        match self.get_node(v) {
+            Some(node) => Cow::Owned(node.adjacents()),
+            None => Cow::Borrowed(&[]),
+        }
+    }
+}
+
+impl<T, U> EdgeWeights<T> for LabeledGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    type Weight = U;
+
+    fn neighbors_with_weight(&self, v: &T) -> Vec<(T, Option<U>)> {
+        match self.get_node(v) {
+            Some(node) => node.adjacents_with_label(),
+            None => vec![],
+        }
+    }
+}
+
+impl<T, U> Directed for LabeledGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    fn is_directed(&self) -> bool {
+        false
+    }
+}
+
+impl<T, U> EdgeList<T, U> for LabeledGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    fn node_list(&self) -> Vec<T> {
+        let mut values = self.get_node_values();
+        values.sort();
+        values
+    }
+
+    fn edge_list(&self) -> Vec<(T, T, Option<U>)> {
+        let mut edges: Vec<(T, T, Option<U>)> = self
+            .get_edges()
+            .into_iter()
+            .map(|(v1, v2, l)| if v1 <= v2 { (v1, v2, l) } else { (v2, v1, l) })
+            .collect();
+        edges.sort_by(|(a1, b1, _), (a2, b2, _)| (a1, b1).cmp(&(a2, b2)));
+        edges
+    }
+
+    fn from_lists(nodes: &[T], edges: &[(T, T, Option<U>)]) -> Self {
+        let mut g = LabeledGraph::new();
+        for v in nodes {
+            g.add_node(*v);
+        }
+        for (v1, v2, l) in edges {
+            g.add_edge_with_value(*v1, *v2, *l);
+        }
+        g
+    }
+}
+
 /// Undirected Graph
 #[allow(dead_code)]
 pub type Graph<T> = LabeledGraph<T, ()>;
@@ -263,6 +498,7 @@ where
 {
     nodes: HashMap<T, Rc<RefCell<Node<T, U>>>>,
     edges: Vec<Rc<Edge<T, U>>>,
+    kind: GraphKind,
 }
 
 impl<T, U> LabeledDigraph<T, U>
@@ -270,14 +506,26 @@ where
     T: Hash + Copy + Eq,
     U: Copy + Eq,
 {
-    /// Constructs an empty digraph.
+    /// Constructs an empty digraph that accepts parallel arcs and self-loops.
     pub fn new() -> Self {
+        Self::with_kind(GraphKind::Pseudograph)
+    }
+
+    /// Constructs an empty digraph that accepts only the arcs allowed by `kind`;
+    /// arcs in opposite directions are not parallel.
+    pub fn with_kind(kind: GraphKind) -> Self {
         LabeledDigraph {
             nodes: HashMap::new(),
             edges: vec![],
+            kind,
         }
     }
 
+    /// Returns which arcs this digraph accepts.
+    pub fn kind(&self) -> GraphKind {
+        self.kind
+    }
+
     /// Returns the number of nodes in this digraph.
     pub fn size(&self) -> usize {
         self.nodes.len()
@@ -301,6 +549,19 @@ where
         self.nodes.iter().map(|(&v, _)| v).collect()
     }
 
+    /// Returns all arcs in this digraph as `(src, dst, label)`, in the order they were added.
+    pub fn get_arcs(&self) -> Vec<(T, T, Option<U>)> {
+        self.edges.iter().filter_map(|e| e.get_ends()).collect()
+    }
+
+    /// Returns the number of arcs from `src` to `dst`.
+    pub fn multiplicity(&self, src: &T, dst: &T) -> usize {
+        match self.get_node(src) {
+            Some(node) => node.adjacents().iter().filter(|w| *w == dst).count(),
+            None => 0,
+        }
+    }
+
     /// Adds a node to this digraph.
     pub fn add_node(&mut self, v: T) {
         let node = Node {
@@ -315,7 +576,29 @@ where
         self.add_arc_with_value(src, dst, Some(label));
     }
 
-    fn add_arc_with_value(&mut self, src: T, dst: T, l: Option<U>) {
+    /// Adds an arc with an optional label to this digraph. Panics if a node is
+    /// not in this digraph or the arc is not allowed by its kind.
+    pub fn add_arc_with_value(&mut self, src: T, dst: T, l: Option<U>) {
+        if let Err(e) = self.try_add_arc_with_value(src, dst, l) {
+            panic!("{}", e.reason());
+        }
+    }
+
+    /// Adds an arc with an optional label to this digraph, unless a node is not
+    /// in this digraph or the arc is not allowed by its kind.
+    pub fn try_add_arc_with_value(
+        &mut self,
+        src: T,
+        dst: T,
+        l: Option<U>,
+    ) -> Result<(), EdgeError<T>> {
+        for v in &[src, dst] {
+            if !self.nodes.contains_key(v) {
+                return Err(EdgeError::UnknownNode(*v));
+            }
+        }
+        let linked = self.multiplicity(&src, &dst) > 0;
+        self.kind.check(src, dst, linked)?;
         let n1 = self.nodes.get(&src).unwrap();
         let n2 = self.nodes.get(&dst).unwrap();
         let edge = Rc::new(Edge {
@@ -327,6 +610,92 @@ where
 
         n1.borrow_mut()
             .add_edge(Rc::downgrade(self.edges.last().unwrap()));
+        Ok(())
+    }
+}
+
+impl<T, U> GraphBase<T> for LabeledDigraph<T, U>
+where
+    T: Hash + Copy + Eq,
+    U: Copy + Eq,
+{
+    fn node_values(&self) -> Cow<'_, [T]> {
+        Cow::Owned(self.get_node_values())
+    }
+
+    fn contains(&self, v: &T) -> bool {
+        self.nodes.contains_key(v)
+    }
+
+    fn node_count(&self) -> usize {
+        self.size()
+    }
+}
+
+impl<T, U> Neighbors<T> for LabeledDigraph<T, U>
+where
+    T: Hash + Copy + Eq,
+    U: Copy + Eq,
+{
+    fn neighbors(&self, v: &T) -> Cow<'_, [T]> {
+        match self.get_node(v) {
+            Some(node) => Cow::Owned(node.adjacents()),
+            None => Cow::Borrowed(&[]),
+        }
+    }
+}
+
+impl<T, U> EdgeWeights<T> for LabeledDigraph<T, U>
+where
+    T: Hash + Copy + Eq,
+    U: Copy + Eq,
+{
+    type Weight = U;
+
+    fn neighbors_with_weight(&self, v: &T) -> Vec<(T, Option<U>)> {
+        match self.get_node(v) {
+            Some(node) => node.adjacents_with_label(),
+            None => vec![],
+        }
+    }
+}
+
+impl<T, U> Directed for LabeledDigraph<T, U>
+where
+    T: Hash + Copy + Eq,
+    U: Copy + Eq,
+{
+    fn is_directed(&self) -> bool {
+        true
+    }
+}
+
+impl<T, U> EdgeList<T, U> for LabeledDigraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    fn node_list(&self) -> Vec<T> {
+        let mut values = self.get_node_values();
+        values.sort();
+        values
+    }
+
+    fn edge_list(&self) -> Vec<(T, T, Option<U>)> {
+        let mut edges = self.get_arcs();
+        edges.sort_by(|(a1, b1, _), (a2, b2, _)| (a1, b1).cmp(&(a2, b2)));
+        edges
+    }
+
+    fn from_lists(nodes: &[T], edges: &[(T, T, Option<U>)]) -> Self {
+        let mut g = LabeledDigraph::new();
+        for v in nodes {
+            g.add_node(*v);
+        }
+        for (v1, v2, l) in edges {
+            g.add_arc_with_value(*v1, *v2, *l);
+        }
+        g
     }
 }
 
@@ -447,6 +816,28 @@ mod tests {
         assert_eq!(n3.adjacents_with_label(), vec![('a', Some(3))]);
     }
 
+    #[test]
+    fn test_edge_list() {
+        let mut g = LabeledGraph::<char, i32>::new();
+        for v in "cab".chars() {
+            g.add_node(v);
+        }
+        g.add_labeled_edge('c', 'a', 1);
+        g.add_labeled_edge('b', 'b', 2);
+        assert_eq!(g.node_list(), vec!['a', 'b', 'c']);
+        // normalized, and a self-loop once
+        assert_eq!(
+            g.edge_list(),
+            vec![('a', 'c', Some(1)), ('b', 'b', Some(2))]
+        );
+        let g2 = LabeledGraph::from_lists(&g.node_list(), &g.edge_list());
+        assert!(g2 == g);
+
+        let d = LabeledDigraph::<char, i32>::from_lists(&['a', 'b'], &[('b', 'a', None)]);
+        assert!(d.is_directed());
+        assert_eq!(d.edge_list(), vec![('b', 'a', None)]);
+    }
+
     #[test]
     fn test_degree() {
         let mut g = Graph::<char>::new();
@@ -480,4 +871,103 @@ mod tests {
         assert_eq!(nodes.get(2).unwrap().get_value(), &'c');
         assert_eq!(nodes.get(3).unwrap().get_value(), &'d');
     }
+
+    #[test]
+    fn test_graph_kinds() {
+        let mut g = Graph::<char>::with_kind(GraphKind::Simple);
+        g.add_node('a');
+        g.add_node('b');
+        g.add_edge('a', 'b');
+        assert_eq!(
+            g.try_add_edge_with_value('b', 'a', None),
+            Err(EdgeError::ParallelEdge('b', 'a'))
+        );
+        let e = g.try_add_edge_with_value('a', 'a', None).unwrap_err();
+        assert_eq!(e.to_string(), "self-loops are not allowed: 'a'");
+        assert_eq!(
+            g.try_add_edge_with_value('a', 'c', None),
+            Err(EdgeError::UnknownNode('c'))
+        );
+        assert_eq!(g.get_edges().len(), 1);
+        assert!(g.is_simple());
+
+        let mut g = LabeledGraph::<char, i32>::with_kind(GraphKind::Multigraph);
+        g.add_node('a');
+        g.add_node('b');
+        g.add_labeled_edge('a', 'b', 1);
+        g.add_labeled_edge('b', 'a', 2);
+        assert_eq!(g.multiplicity(&'a', &'b'), 2);
+        assert_eq!(g.get_node(&'a').unwrap().degree(), 2);
+        assert!(g.try_add_edge_with_value('b', 'b', Some(3)).is_err());
+        assert!(!g.is_simple());
+
+        // a self-loop adds 2 to the degree
+        let mut g = Graph::<char>::new();
+        assert_eq!(g.kind(), GraphKind::Pseudograph);
+        g.add_node('a');
+        g.add_edge('a', 'a');
+        assert_eq!(g.multiplicity(&'a', &'a'), 1);
+        assert_eq!(g.get_node(&'a').unwrap().degree(), 2);
+
+        // opposite arcs are not parallel
+        let mut g = Digraph::<char>::with_kind(GraphKind::Simple);
+        g.add_node('a');
+        g.add_node('b');
+        g.add_arc('a', 'b');
+        g.add_arc('b', 'a');
+        assert!(g.try_add_arc_with_value('a', 'b', None).is_err());
+        assert_eq!(g.multiplicity(&'a', &'b'), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "parallel edges are not allowed")]
+    fn test_add_parallel_edge_to_simple_graph() {
+        let mut g = Graph::<char>::with_kind(GraphKind::Simple);
+        g.add_node('a');
+        g.add_node('b');
+        g.add_edge('a', 'b');
+        g.add_edge('a', 'b');
+    }
+
+    #[test]
+    fn test_equality_with_parallel_edges() {
+        let build = |edges: &[(char, char)]| {
+            let mut g = Graph::<char>::new();
+            for v in "abc".chars() {
+                g.add_node(v);
+            }
+            for (v, w) in edges {
+                g.add_edge(*v, *w);
+            }
+            g
+        };
+        let g1 = build(&[('a', 'b'), ('a', 'b'), ('a', 'c')]);
+        let g2 = build(&[('a', 'b'), ('a', 'c'), ('a', 'c')]);
+        let g3 = build(&[('b', 'a'), ('a', 'c'), ('a', 'b')]);
+        assert!(g1 != g2);
+        assert!(g1 == g3);
+    }
+
+    #[test]
+    fn test_graph_traits() {
+        let mut g = LabeledDigraph::<char, i32>::new();
+        g.add_node('a');
+        g.add_node('b');
+        g.add_node('c');
+        g.add_labeled_arc('a', 'b', 2);
+        g.add_labeled_arc('b', 'c', 1);
+
+        assert!(g.is_directed());
+        assert!(g.contains(&'a'));
+        assert!(!g.contains(&'d'));
+        assert_eq!(g.node_count(), 3);
+        assert_eq!(g.neighbors(&'a').as_ref(), &['b']);
+        assert_eq!(g.neighbors_with_weight(&'b'), vec![('c', Some(1))]);
+        assert_eq!(g.weight(&'a', &'b'), Some(2));
+        assert_eq!(g.weight(&'b', &'a'), None);
+
+        let u = UnderlyingGraph::new(&g);
+        assert!(!u.is_directed());
+        assert_eq!(u.degree(&'b'), 2);
+    }
 }
@@ -0,0 +1,434 @@
+//! Random walks and Markov chain analysis.
+//!
+//! A graph is taken as a transition system: from each node, a walker moves
+//! along one of the outgoing arcs with probability proportional to its label.
+
+use super::EdgeWeights;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// power iteration stops when the distribution changes less than this (L1 norm)
+const TOLERANCE: f64 = 1e-12;
+const MAX_ITERATIONS: usize = 100_000;
+// pivots below this are taken as zero when solving linear systems
+const EPSILON: f64 = 1e-12;
+
+/// Markov chain given by the arcs of a graph with numeric labels.
+///
+/// The transition probability from `v` to `w` is the label of the arc from `v`
+/// to `w` divided by the sum of the labels of all arcs leaving `v`; unlabeled
+/// arcs weigh 1 and parallel arcs add up. A node without outgoing arcs (or
+/// whose labels sum to 0) keeps the walker, as if it had a self-loop. For
+/// undirected graphs, each edge can be taken in either direction.
+#[derive(Debug, Clone)]
+pub struct MarkovChain<T>
+where
+    T: Hash + Copy + Eq + Ord,
+{
+    values: Vec<T>,
+    index: HashMap<T, usize>,
+    // successors with transition probabilities, by node index
+    transitions: Vec<Vec<(usize, f64)>>,
+}
+
+/// Result of the analysis of an absorbing Markov chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Absorption<T>
+where
+    T: Hash + Copy + Eq + Ord,
+{
+    /// The absorbing nodes, which are never left, in sorted order.
+    pub absorbing: Vec<T>,
+    /// The expected number of steps until absorption from each transient node.
+    pub steps: HashMap<T, f64>,
+    /// The probabilities of ending in each absorbing node, from each transient
+    /// node.
+    pub probabilities: HashMap<T, HashMap<T, f64>>,
+}
+
+impl<T: Hash + Copy + Eq + Ord> MarkovChain<T> {
+    /// Builds the chain of a graph. Panics if a label is negative.
+    pub fn new<G>(g: &G) -> Self
+    where
+        G: EdgeWeights<T>,
+        G::Weight: Into<f64>,
+    {
+        let mut values = g.node_values().into_owned();
+        values.sort();
+        let index: HashMap<T, usize> = values.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let transitions = values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let mut row: Vec<(usize, f64)> = vec![];
+                for (w, l) in g.neighbors_with_weight(v) {
+                    let l = l.map_or(1.0, |l| l.into());
+                    assert!(l >= 0.0, "labels must be non-negative");
+                    let j = index[&w];
+                    match row.iter_mut().find(|(k, _)| *k == j) {
+                        Some((_, p)) => *p += l,
+                        None => row.push((j, l)),
+                    }
+                }
+                row.retain(|(_, p)| *p > 0.0);
+                row.sort_by_key(|(j, _)| *j);
+                let total: f64 = row.iter().map(|(_, p)| p).sum();
+                if row.is_empty() {
+                    vec![(i, 1.0)]
+                } else {
+                    row.into_iter().map(|(j, p)| (j, p / total)).collect()
+                }
+            })
+            .collect();
+        MarkovChain {
+            values,
+            index,
+            transitions,
+        }
+    }
+
+    fn index_of(&self, v: &T) -> usize {
+        *self.index.get(v).expect("unknown node")
+    }
+
+    /// Returns the probability of moving from `v` to `w` in one step; 0 if
+    /// either is not in the graph.
+    pub fn probability(&self, v: &T, w: &T) -> f64 {
+        match (self.index.get(v), self.index.get(w)) {
+            (Some(i), Some(j)) => self.transitions[*i]
+                .iter()
+                .find(|(k, _)| k == j)
+                .map_or(0.0, |(_, p)| *p),
+            _ => 0.0,
+        }
+    }
+
+    // one step from node index i
+    fn step(&self, i: usize, rng: &mut StdRng) -> usize {
+        let mut r: f64 = rng.gen();
+        for (j, p) in &self.transitions[i] {
+            if r < *p {
+                return *j;
+            }
+            r -= p;
+        }
+        // rounding errors may leave a tiny remainder
+        self.transitions[i].last().unwrap().0
+    }
+
+    /// Returns the nodes visited by a random walk of `steps` steps from
+    /// `start`, including `start`. The random number generator is seeded by
+    /// `seed`, so that the walk is reproducible.
+    ///
+    /// Panics if `start` is not in the graph; this holds for all methods that
+    /// take nodes, unless noted otherwise.
+    pub fn random_walk(&self, start: T, steps: usize, seed: u64) -> Vec<T> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut i = self.index_of(&start);
+        let mut walk = vec![start];
+        for _ in 0..steps {
+            i = self.step(i, &mut rng);
+            walk.push(self.values[i]);
+        }
+        walk
+    }
+
+    /// Returns the stationary distribution, i.e. the probabilities of being at
+    /// each node in the long run, by power iteration from the uniform
+    /// distribution.
+    ///
+    /// The iteration uses the lazy chain, which stays put with probability 1/2,
+    /// so that it converges for periodic chains too. If the chain is not
+    /// irreducible (not strongly connected), the distribution is one of several
+    /// and depends on the start.
+    pub fn stationary_distribution(&self) -> HashMap<T, f64> {
+        let n = self.values.len();
+        let mut pi = vec![1.0 / n as f64; n];
+        for _ in 0..MAX_ITERATIONS {
+            let mut next: Vec<f64> = pi.iter().map(|p| p / 2.0).collect();
+            for (i, row) in self.transitions.iter().enumerate() {
+                for (j, p) in row {
+                    next[*j] += pi[i] * p / 2.0;
+                }
+            }
+            let change: f64 = pi.iter().zip(&next).map(|(a, b)| (a - b).abs()).sum();
+            pi = next;
+            if change < TOLERANCE {
+                break;
+            }
+        }
+        self.values.iter().copied().zip(pi).collect()
+    }
+
+    /// Estimates the expected number of steps for a walk from `start` to first
+    /// reach `target`, as the mean over `walks` random walks. `None` if one of
+    /// the walks does not reach `target` within `max_steps` steps (which is
+    /// certain if `target` is unreachable).
+    pub fn hitting_time(
+        &self,
+        start: T,
+        target: T,
+        walks: usize,
+        max_steps: usize,
+        seed: u64,
+    ) -> Option<f64> {
+        let target = self.index_of(&target);
+        self.mean_steps(start, walks, max_steps, seed, |i, _| i == target)
+    }
+
+    /// Estimates the expected number of steps for a walk from `start` to visit
+    /// every node, as the mean over `walks` random walks. `None` if one of the
+    /// walks does not visit all nodes within `max_steps` steps.
+    pub fn cover_time(&self, start: T, walks: usize, max_steps: usize, seed: u64) -> Option<f64> {
+        let n = self.values.len();
+        self.mean_steps(start, walks, max_steps, seed, |_, visited| visited == n)
+    }
+
+    // mean number of steps of random walks until done(current node, number of
+    // distinct nodes visited) holds
+    fn mean_steps<F>(
+        &self,
+        start: T,
+        walks: usize,
+        max_steps: usize,
+        seed: u64,
+        done: F,
+    ) -> Option<f64>
+    where
+        F: Fn(usize, usize) -> bool,
+    {
+        let start = self.index_of(&start);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut total = 0;
+        for _ in 0..walks.max(1) {
+            let mut i = start;
+            let mut visited = vec![false; self.values.len()];
+            visited[i] = true;
+            let mut count = 1;
+            let mut steps = 0;
+            while !done(i, count) {
+                if steps == max_steps {
+                    return None;
+                }
+                i = self.step(i, &mut rng);
+                if !visited[i] {
+                    visited[i] = true;
+                    count += 1;
+                }
+                steps += 1;
+            }
+            total += steps;
+        }
+        Some(total as f64 / walks.max(1) as f64)
+    }
+
+    /// Analyzes the chain as an absorbing chain, whose absorbing nodes are those
+    /// never left (without outgoing arcs other than self-loops).
+    ///
+    /// `None` if there are no absorbing nodes, or if a walk from some transient
+    /// node may never be absorbed (i.e. cannot reach an absorbing node).
+    pub fn absorption(&self) -> Option<Absorption<T>> {
+        let n = self.values.len();
+        let is_absorbing: Vec<bool> = (0..n).map(|i| self.transitions[i] == [(i, 1.0)]).collect();
+        let absorbing: Vec<usize> = (0..n).filter(|i| is_absorbing[*i]).collect();
+        let transient: Vec<usize> = (0..n).filter(|i| !is_absorbing[*i]).collect();
+        if absorbing.is_empty() {
+            return None;
+        }
+        let mut position = vec![0; n];
+        for (k, i) in transient.iter().chain(absorbing.iter()).enumerate() {
+            position[*i] = k;
+        }
+        // solve (I - Q) X = [1 R], where Q holds the transient and R the
+        // absorbing columns of the transient rows
+        let t = transient.len();
+        let rows: Vec<Vec<f64>> = transient
+            .iter()
+            .enumerate()
+            .map(|(k, i)| {
+                let mut row = vec![0.0; t + 1 + absorbing.len()];
+                row[k] = 1.0;
+                row[t] = 1.0;
+                for (j, p) in &self.transitions[*i] {
+                    if is_absorbing[*j] {
+                        row[1 + position[*j]] += p;
+                    } else {
+                        row[position[*j]] -= p;
+                    }
+                }
+                row
+            })
+            .collect();
+        let solution = solve(rows)?;
+
+        let steps = transient
+            .iter()
+            .zip(&solution)
+            .map(|(i, x)| (self.values[*i], x[0]))
+            .collect();
+        let probabilities = transient
+            .iter()
+            .zip(&solution)
+            .map(|(i, x)| {
+                let probs = absorbing
+                    .iter()
+                    .zip(&x[1..])
+                    .map(|(j, p)| (self.values[*j], *p))
+                    .collect();
+                (self.values[*i], probs)
+            })
+            .collect();
+        Some(Absorption {
+            absorbing: absorbing.iter().map(|i| self.values[*i]).collect(),
+            steps,
+            probabilities,
+        })
+    }
+}
+
+// Gauss-Jordan elimination with partial pivoting on an augmented matrix of n
+// rows whose first n columns are the coefficients; returns the right hand sides
+// of the solution per row, or None if the matrix is singular
+fn solve(mut rows: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let n = rows.len();
+    for c in 0..n {
+        let pivot =
+            (c..n).max_by(|a, b| rows[*a][c].abs().partial_cmp(&rows[*b][c].abs()).unwrap())?;
+        if rows[pivot][c].abs() < EPSILON {
+            return None;
+        }
+        rows.swap(c, pivot);
+        let pivot_row = rows[c].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != c && row[c] != 0.0 {
+                let f = row[c] / pivot_row[c];
+                for (x, y) in row.iter_mut().zip(&pivot_row) {
+                    *x -= f * y;
+                }
+            }
+        }
+    }
+    Some(
+        rows.iter()
+            .enumerate()
+            .map(|(c, row)| row[n..].iter().map(|x| x / row[c]).collect())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EdgeList, LabeledDigraph, LabeledGraph};
+
+    // a graph on the endpoints of `edges`
+    fn from_edges<G: EdgeList<char, i32>>(edges: &[(char, char, Option<i32>)]) -> G {
+        let mut nodes: Vec<char> = edges.iter().flat_map(|(v, w, _)| vec![*v, *w]).collect();
+        nodes.sort();
+        nodes.dedup();
+        G::from_lists(&nodes, edges)
+    }
+
+    #[test]
+    fn test_transitions() {
+        let chain = MarkovChain::new(&from_edges::<LabeledDigraph<_, _>>(&[
+            ('a', 'b', Some(1)),
+            ('a', 'c', Some(3)),
+            ('a', 'c', Some(1)),
+        ]));
+        assert_eq!(chain.probability(&'a', &'b'), 0.2);
+        assert_eq!(chain.probability(&'a', &'c'), 0.8);
+        assert_eq!(chain.probability(&'b', &'b'), 1.0);
+        assert_eq!(chain.probability(&'a', &'x'), 0.0);
+
+        let walk = chain.random_walk('a', 5, 7);
+        assert_eq!(walk.len(), 6);
+        assert_eq!(walk, chain.random_walk('a', 5, 7));
+        assert!(walk[1..].iter().all(|v| *v == walk[1]));
+    }
+
+    #[test]
+    fn test_stationary_distribution() {
+        // periodic: the walk alternates between a and the others
+        let chain = MarkovChain::new(&from_edges::<LabeledDigraph<_, _>>(&[
+            ('a', 'b', Some(1)),
+            ('a', 'c', Some(1)),
+            ('b', 'a', Some(1)),
+            ('c', 'a', Some(1)),
+        ]));
+        let pi = chain.stationary_distribution();
+        assert!((pi[&'a'] - 0.5).abs() < 1e-9);
+        assert!((pi[&'b'] - 0.25).abs() < 1e-9);
+
+        // on an undirected graph, proportional to the degrees
+        let g = from_edges::<LabeledGraph<_, _>>(&[
+            ('a', 'b', None),
+            ('a', 'c', None),
+            ('a', 'd', None),
+            ('b', 'c', None),
+        ]);
+        let pi = MarkovChain::new(&g).stationary_distribution();
+        assert!((pi[&'a'] - 3.0 / 8.0).abs() < 1e-9);
+        assert!((pi[&'d'] - 1.0 / 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hitting_and_cover_time() {
+        // a walk on a path of three nodes; from a, b is hit after 1 step and c
+        // after 4 steps on average
+        let chain = MarkovChain::new(&from_edges::<LabeledGraph<_, _>>(&[
+            ('a', 'b', None),
+            ('b', 'c', None),
+        ]));
+        assert_eq!(chain.hitting_time('a', 'b', 100, 1000, 1), Some(1.0));
+        let t = chain.hitting_time('a', 'c', 10000, 1000, 1).unwrap();
+        assert!((t - 4.0).abs() < 0.2);
+        assert_eq!(
+            chain.cover_time('b', 100, 1000, 1),
+            chain.cover_time('b', 100, 1000, 1)
+        );
+        assert!(chain.cover_time('a', 100, 1000, 1).unwrap() >= 2.0);
+
+        let chain = MarkovChain::new(&from_edges::<LabeledDigraph<_, _>>(&[
+            ('a', 'b', Some(1)),
+            ('c', 'b', Some(1)),
+        ]));
+        assert_eq!(chain.hitting_time('a', 'c', 10, 1000, 1), None);
+        assert_eq!(chain.cover_time('a', 10, 1000, 1), None);
+    }
+
+    #[test]
+    fn test_absorption() {
+        // gambler's ruin with 0..4 coins, fair bets
+        let chain = MarkovChain::new(&from_edges::<LabeledDigraph<_, _>>(&[
+            ('1', '0', Some(1)),
+            ('1', '2', Some(1)),
+            ('2', '1', Some(1)),
+            ('2', '3', Some(1)),
+            ('3', '2', Some(1)),
+            ('3', '4', Some(1)),
+        ]));
+        let a = chain.absorption().unwrap();
+        assert_eq!(a.absorbing, vec!['0', '4']);
+        assert!((a.steps[&'1'] - 3.0).abs() < 1e-9);
+        assert!((a.steps[&'2'] - 4.0).abs() < 1e-9);
+        assert!((a.probabilities[&'1'][&'4'] - 0.25).abs() < 1e-9);
+        assert!((a.probabilities[&'2'][&'0'] - 0.5).abs() < 1e-9);
+
+        // b and c may circle forever
+        let chain = MarkovChain::new(&from_edges::<LabeledDigraph<_, _>>(&[
+            ('a', 'b', Some(1)),
+            ('a', 'd', Some(1)),
+            ('b', 'c', Some(1)),
+            ('c', 'b', Some(1)),
+        ]));
+        assert_eq!(chain.absorption(), None);
+        let chain = MarkovChain::new(&from_edges::<LabeledDigraph<_, _>>(&[
+            ('a', 'b', Some(1)),
+            ('b', 'a', Some(1)),
+        ]));
+        assert_eq!(chain.absorption(), None);
+    }
+}
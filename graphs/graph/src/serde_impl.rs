@@ -0,0 +1,200 @@
+// Serialization of graphs and digraphs (enabled by the `serde` feature).
+// A graph is serialized as the list of node values and the list of edges,
+// both sorted by node values so that the output is stable, and its kind, e.g.
+// for JSON:
+// {"nodes":["a","b","c"],"edges":[{"source":"a","target":"b","label":5}],"kind":"simple"}
+// Unlabeled edges have no "label" field. Without "kind", a pseudograph is
+// read, as constructed by `new()`. Edges that the kind does not allow are
+// errors.
+use super::{GraphKind, LabeledDigraph, LabeledGraph};
+use serde::de::{Deserializer, Error};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+
+#[derive(Serialize, Deserialize)]
+struct GraphData<T, U> {
+    nodes: Vec<T>,
+    edges: Vec<EdgeData<T, U>>,
+    #[serde(default = "pseudograph", with = "GraphKindDef")]
+    kind: GraphKind,
+}
+
+// kinds are written in lowercase, e.g. "multigraph"
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "GraphKind", rename_all = "lowercase")]
+enum GraphKindDef {
+    Simple,
+    Multigraph,
+    Pseudograph,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EdgeData<T, U> {
+    source: T,
+    target: T,
+    #[serde(default = "no_label", skip_serializing_if = "Option::is_none")]
+    label: Option<U>,
+}
+
+fn no_label<U>() -> Option<U> {
+    None
+}
+
+fn pseudograph() -> GraphKind {
+    GraphKind::Pseudograph
+}
+
+impl<T, U> GraphData<T, U>
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    fn new(mut nodes: Vec<T>, mut edges: Vec<(T, T, Option<U>)>, kind: GraphKind) -> Self {
+        nodes.sort();
+        edges.sort_by(|(a1, b1, _), (a2, b2, _)| (a1, b1).cmp(&(a2, b2)));
+        let edges = edges
+            .into_iter()
+            .map(|(source, target, label)| EdgeData {
+                source,
+                target,
+                label,
+            })
+            .collect();
+        GraphData { nodes, edges, kind }
+    }
+}
+
+impl<T, U> Serialize for LabeledGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord + Serialize,
+    U: Copy + Eq + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GraphData::new(self.get_node_values(), self.get_edges(), self.kind()).serialize(serializer)
+    }
+}
+
+impl<'de, T, U> Deserialize<'de> for LabeledGraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord + Deserialize<'de>,
+    U: Copy + Eq + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = GraphData::<T, U>::deserialize(deserializer)?;
+        let mut g = LabeledGraph::with_kind(data.kind);
+        for v in data.nodes {
+            g.add_node(v);
+        }
+        for e in data.edges {
+            g.try_add_edge_with_value(e.source, e.target, e.label)
+                .map_err(|e| D::Error::custom(e.reason()))?;
+        }
+        Ok(g)
+    }
+}
+
+impl<T, U> Serialize for LabeledDigraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord + Serialize,
+    U: Copy + Eq + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GraphData::new(self.get_node_values(), self.get_arcs(), self.kind()).serialize(serializer)
+    }
+}
+
+impl<'de, T, U> Deserialize<'de> for LabeledDigraph<T, U>
+where
+    T: Hash + Copy + Eq + Ord + Deserialize<'de>,
+    U: Copy + Eq + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = GraphData::<T, U>::deserialize(deserializer)?;
+        let mut g = LabeledDigraph::with_kind(data.kind);
+        for v in data.nodes {
+            g.add_node(v);
+        }
+        for e in data.edges {
+            g.try_add_arc_with_value(e.source, e.target, e.label)
+                .map_err(|e| D::Error::custom(e.reason()))?;
+        }
+        Ok(g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Digraph, Graph};
+    use super::*;
+
+    #[test]
+    fn test_serialize_graph() {
+        let mut g = LabeledGraph::<char, i32>::new();
+        g.add_node('a');
+        g.add_node('b');
+        g.add_node('c');
+        g.add_labeled_edge('a', 'b', 2);
+        g.add_labeled_edge('b', 'c', 1);
+        let json = serde_json::to_string(&g).unwrap();
+        assert_eq!(
+            json,
+            r#"{"nodes":["a","b","c"],"edges":[{"source":"a","target":"b","label":2},{"source":"b","target":"c","label":1}],"kind":"pseudograph"}"#
+        );
+        let g2: LabeledGraph<char, i32> = serde_json::from_str(&json).unwrap();
+        assert!(g == g2);
+    }
+
+    #[test]
+    fn test_serialize_unlabeled() {
+        let mut g = Graph::<u32>::new();
+        g.add_node(10);
+        g.add_node(200);
+        g.add_edge(10, 200);
+        let json = serde_json::to_string(&g).unwrap();
+        assert_eq!(
+            json,
+            r#"{"nodes":[10,200],"edges":[{"source":10,"target":200}],"kind":"pseudograph"}"#
+        );
+        let g2: Graph<u32> = serde_json::from_str(&json).unwrap();
+        assert!(g == g2);
+
+        let mut d = Digraph::<char>::new();
+        d.add_node('a');
+        d.add_node('b');
+        d.add_arc('b', 'a');
+        let json = serde_json::to_string(&d).unwrap();
+        let d2: Digraph<char> = serde_json::from_str(&json).unwrap();
+        assert_eq!(d2.get_node(&'b').unwrap().adjacents(), vec!['a']);
+        assert_eq!(d2.get_node(&'a').unwrap().adjacents(), vec![]);
+    }
+
+    #[test]
+    fn test_deserialize_unknown_node() {
+        let json = r#"{"nodes":["a"],"edges":[{"source":"a","target":"b"}]}"#;
+        assert!(serde_json::from_str::<Graph<char>>(json).is_err());
+    }
+
+    #[test]
+    fn test_serialize_kind() {
+        let mut g = Graph::<char>::with_kind(GraphKind::Simple);
+        g.add_node('a');
+        g.add_node('b');
+        g.add_edge('a', 'b');
+        let json = serde_json::to_string(&g).unwrap();
+        assert!(json.ends_with(r#""kind":"simple"}"#));
+        let g2: Graph<char> = serde_json::from_str(&json).unwrap();
+        assert_eq!(g2.kind(), GraphKind::Simple);
+
+        // edges that the kind does not allow are errors, not panics
+        let json = r#"{"nodes":["a","b"],"edges":[{"source":"a","target":"b"},{"source":"b","target":"a"}],"kind":"simple"}"#;
+        let err = serde_json::from_str::<Graph<char>>(json).unwrap_err();
+        assert!(err.to_string().contains("parallel edges are not allowed"));
+        let json = r#"{"nodes":["a"],"edges":[{"source":"a","target":"a"}],"kind":"multigraph"}"#;
+        assert!(serde_json::from_str::<Digraph<char>>(json).is_err());
+        // a pseudograph without "kind"
+        let json = r#"{"nodes":["a"],"edges":[{"source":"a","target":"a"}]}"#;
+        let d: Digraph<char> = serde_json::from_str(json).unwrap();
+        assert_eq!(d.kind(), GraphKind::Pseudograph);
+    }
+}
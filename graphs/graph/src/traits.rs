@@ -0,0 +1,113 @@
+//! Core traits shared by all graph representations.
+//!
+//! Algorithms are written against these traits rather than a concrete graph
+//! type, so that they run on undirected and directed graphs, labeled graphs,
+//! frozen [`CsrGraph`](../csr/struct.CsrGraph.html)s and implicit graphs alike.
+
+use std::borrow::Cow;
+use std::hash::Hash;
+
+/// Node access common to all graphs.
+pub trait GraphBase<T: Hash + Copy + Eq> {
+    /// Returns all node values.
+    fn node_values(&self) -> Cow<'_, [T]>;
+
+    /// Checks if `v` is in this graph.
+    fn contains(&self, v: &T) -> bool;
+
+    /// Returns the number of nodes.
+    fn node_count(&self) -> usize {
+        self.node_values().len()
+    }
+}
+
+/// Neighbourhood access, so that traversal algorithms can run on any graph.
+pub trait Neighbors<T: Hash + Copy + Eq>: GraphBase<T> {
+    /// Returns adjacent node values of `v` (successors for digraphs); empty if
+    /// `v` is not in the graph. A node is listed once per edge to it, so twice
+    /// for a double edge, and `v` itself twice for an undirected self-loop.
+    fn neighbors(&self, v: &T) -> Cow<'_, [T]>;
+
+    /// Returns the number of adjacent nodes (out-degree for digraphs) of `v`.
+    fn degree(&self, v: &T) -> usize {
+        self.neighbors(v).len()
+    }
+}
+
+/// Access to edge labels, used as weights by the algorithms.
+pub trait EdgeWeights<T: Hash + Copy + Eq>: Neighbors<T> {
+    type Weight: Copy + Eq;
+
+    /// Returns adjacent node values of `v` with the labels of the edges, in the
+    /// same order as `neighbors()`.
+    fn neighbors_with_weight(&self, v: &T) -> Vec<(T, Option<Self::Weight>)>;
+
+    /// Returns the label of the edge (arc) from `v1` to `v2`; `None` if they are
+    /// not linked or the edge has no label.
+    fn weight(&self, v1: &T, v2: &T) -> Option<Self::Weight> {
+        self.neighbors_with_weight(v1)
+            .into_iter()
+            .find(|(w, _)| w == v2)
+            .and_then(|(_, l)| l)
+    }
+}
+
+/// Tells whether edges have directions.
+pub trait Directed {
+    /// Returns true if this is a digraph.
+    fn is_directed(&self) -> bool;
+}
+
+/// Graphs given by a node list and an edge list, so that they can be written
+/// out and read back, or compared and patched.
+pub trait EdgeList<T, U>: Directed + Sized
+where
+    T: Hash + Copy + Eq + Ord,
+    U: Copy + Eq,
+{
+    /// Returns all node values in sorted order.
+    fn node_list(&self) -> Vec<T>;
+
+    /// Returns all edges (arcs) as `(v1, v2, label)`, each edge once and sorted by
+    /// end nodes. Undirected edges are normalized to `v1 <= v2`.
+    fn edge_list(&self) -> Vec<(T, T, Option<U>)>;
+
+    /// Constructs a graph from nodes and edges; all end nodes must be in `nodes`.
+    fn from_lists(nodes: &[T], edges: &[(T, T, Option<U>)]) -> Self;
+}
+
+// references to graphs are graphs as well, so that algorithms can take either
+
+impl<T: Hash + Copy + Eq, G: GraphBase<T> + ?Sized> GraphBase<T> for &G {
+    fn node_values(&self) -> Cow<'_, [T]> {
+        (**self).node_values()
+    }
+
+    fn contains(&self, v: &T) -> bool {
+        (**self).contains(v)
+    }
+
+    fn node_count(&self) -> usize {
+        (**self).node_count()
+    }
+}
+
+impl<T: Hash + Copy + Eq, G: Neighbors<T> + ?Sized> Neighbors<T> for &G {
+    fn neighbors(&self, v: &T) -> Cow<'_, [T]> {
+        (**self).neighbors(v)
+    }
+}
+
+impl<T: Hash + Copy + Eq, G: EdgeWeights<T> + ?Sized> EdgeWeights<T> for &G {
+    type Weight = G::Weight;
+
+    fn neighbors_with_weight(&self, v: &T) -> Vec<(T, Option<Self::Weight>)> {
+        (**self).neighbors_with_weight(v)
+    }
+}
+
+impl<G: Directed + ?Sized> Directed for &G {
+    fn is_directed(&self) -> bool {
+        (**self).is_directed()
+    }
+}
@@ -0,0 +1,175 @@
+//! Graphs that are not stored as nodes and edges: implicit graphs given by a
+//! successor function, and the underlying undirected graph of a digraph.
+
+use super::{Directed, EdgeWeights, GraphBase, Neighbors};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Graph whose edges are computed on demand by a successor function.
+///
+/// Only the node values are stored; `successors(v)` is called every time the
+/// neighbours of `v` are needed. For an undirected graph the function must be
+/// symmetric.
+pub struct ImplicitGraph<T, F>
+where
+    T: Hash + Copy + Eq,
+    F: Fn(&T) -> Vec<T>,
+{
+    values: Vec<T>,
+    index: HashSet<T>,
+    successors: F,
+    directed: bool,
+}
+
+impl<T, F> ImplicitGraph<T, F>
+where
+    T: Hash + Copy + Eq,
+    F: Fn(&T) -> Vec<T>,
+{
+    /// Constructs a graph with given nodes and successor function.
+    pub fn new(values: Vec<T>, directed: bool, successors: F) -> Self {
+        let index = values.iter().copied().collect();
+        ImplicitGraph {
+            values,
+            index,
+            successors,
+            directed,
+        }
+    }
+}
+
+impl<T, F> GraphBase<T> for ImplicitGraph<T, F>
+where
+    T: Hash + Copy + Eq,
+    F: Fn(&T) -> Vec<T>,
+{
+    fn node_values(&self) -> Cow<'_, [T]> {
+        Cow::Borrowed(&self.values)
+    }
+
+    fn contains(&self, v: &T) -> bool {
+        self.index.contains(v)
+    }
+}
+
+impl<T, F> Neighbors<T> for ImplicitGraph<T, F>
+where
+    T: Hash + Copy + Eq,
+    F: Fn(&T) -> Vec<T>,
+{
+    fn neighbors(&self, v: &T) -> Cow<'_, [T]> {
+        if self.contains(v) {
+            Cow::Owned((self.successors)(v))
+        } else {
+            Cow::Borrowed(&[])
+        }
+    }
+}
+
+impl<T, F> Directed for ImplicitGraph<T, F>
+where
+    T: Hash + Copy + Eq,
+    F: Fn(&T) -> Vec<T>,
+{
+    fn is_directed(&self) -> bool {
+        self.directed
+    }
+}
+
+/// The underlying undirected graph; i.e. the graph with arc directions ignored.
+///
+/// Two nodes are adjacent if there is an edge (or an arc in either direction)
+/// between them. Parallel edges are merged.
+#[derive(Debug, Clone)]
+pub struct UnderlyingGraph<T: Hash + Copy + Eq> {
+    values: Vec<T>,
+    adjs: HashMap<T, Vec<T>>,
+}
+
+impl<T: Hash + Copy + Eq> UnderlyingGraph<T> {
+    /// Builds the underlying undirected graph of `g`.
+    pub fn new<G: Neighbors<T>>(g: &G) -> Self {
+        let values = g.node_values().into_owned();
+        let mut adjs: HashMap<T, Vec<T>> = values.iter().map(|v| (*v, vec![])).collect();
+        for v in &values {
+            for w in g.neighbors(v).iter() {
+                if !adjs[v].contains(w) {
+                    adjs.get_mut(v).unwrap().push(*w);
+                }
+                if !adjs[w].contains(v) {
+                    adjs.get_mut(w).unwrap().push(*v);
+                }
+            }
+        }
+        UnderlyingGraph { values, adjs }
+    }
+}
+
+impl<T: Hash + Copy + Eq> GraphBase<T> for UnderlyingGraph<T> {
+    fn node_values(&self) -> Cow<'_, [T]> {
+        Cow::Borrowed(&self.values)
+    }
+
+    fn contains(&self, v: &T) -> bool {
+        self.adjs.contains_key(v)
+    }
+}
+
+impl<T: Hash + Copy + Eq> Neighbors<T> for UnderlyingGraph<T> {
+    fn neighbors(&self, v: &T) -> Cow<'_, [T]> {
+        match self.adjs.get(v) {
+            Some(adjs) => Cow::Borrowed(adjs),
+            None => Cow::Borrowed(&[]),
+        }
+    }
+}
+
+// merged edges carry no labels
+impl<T: Hash + Copy + Eq> EdgeWeights<T> for UnderlyingGraph<T> {
+    type Weight = ();
+
+    fn neighbors_with_weight(&self, v: &T) -> Vec<(T, Option<()>)> {
+        self.neighbors(v).iter().map(|w| (*w, None)).collect()
+    }
+}
+
+impl<T: Hash + Copy + Eq> Directed for UnderlyingGraph<T> {
+    fn is_directed(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Digraph;
+    use super::*;
+
+    #[test]
+    fn test_implicit_graph() {
+        // the cycle 0 -> 1 -> ... -> 5 -> 0
+        let g = ImplicitGraph::new((0..6).collect(), true, |v: &u32| vec![(v + 1) % 6]);
+        assert_eq!(g.node_count(), 6);
+        assert!(g.contains(&5));
+        assert!(!g.contains(&6));
+        assert_eq!(g.neighbors(&5).as_ref(), &[0]);
+        assert_eq!(g.neighbors(&6).as_ref(), &[] as &[u32]);
+        assert!(g.is_directed());
+    }
+
+    #[test]
+    fn test_underlying_graph() {
+        let mut g = Digraph::<char>::new();
+        g.add_node('a');
+        g.add_node('b');
+        g.add_node('c');
+        g.add_arc('a', 'b');
+        g.add_arc('b', 'a');
+        g.add_arc('c', 'b');
+        let u = UnderlyingGraph::new(&g);
+        assert!(!u.is_directed());
+        assert_eq!(u.neighbors(&'a').as_ref(), &['b']);
+        assert_eq!(u.degree(&'b'), 2);
+        assert_eq!(u.neighbors(&'c').as_ref(), &['b']);
+    }
+}
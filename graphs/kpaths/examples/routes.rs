@@ -0,0 +1,29 @@
+use kpaths::*;
+use P80::graph_converters::labeled;
+
+pub fn main() {
+    let g = labeled::from_term_form(
+        &vec!['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'],
+        &vec![
+            ('a', 'b', 5),
+            ('a', 'd', 3),
+            ('b', 'c', 2),
+            ('b', 'e', 4),
+            ('c', 'e', 6),
+            ('d', 'e', 7),
+            ('d', 'f', 4),
+            ('d', 'g', 3),
+            ('e', 'h', 5),
+            ('f', 'g', 4),
+            ('g', 'h', 1),
+        ],
+    );
+    println!("paths from a to h:");
+    for (path, cost) in k_shortest_paths(&g, 'a', 'h', 4) {
+        println!("{:?} (cost={})", path, cost);
+    }
+    println!("walks from a to h:");
+    for (walk, cost) in k_shortest_walks(&g, 'a', 'h', 4) {
+        println!("{:?} (cost={})", walk, cost);
+    }
+}
@@ -0,0 +1,335 @@
+use graph::EdgeWeights;
+use search::{uniform_cost, Cost};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+/// Returns up to `k` shortest loopless paths from `start` to `end` with their
+/// costs, in increasing order of cost (Yen's algorithm). Paths of the same cost
+/// are ordered by their nodes.
+///
+/// Edge labels are the costs; they must be present and non-negative. Paths are
+/// sequences of nodes, so parallel edges count once with the cheapest label.
+pub fn k_shortest_paths<T, G>(g: &G, start: T, end: T, k: usize) -> Vec<(Vec<T>, G::Weight)>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T>,
+    G::Weight: Cost,
+{
+    let mut found: Vec<(Vec<T>, G::Weight)> = vec![];
+    if k == 0 || !g.contains(&start) || !g.contains(&end) {
+        return found;
+    }
+    match shortest_path(g, start, end, &HashSet::new(), &HashSet::new()) {
+        Some(path) => found.push(path),
+        None => return found,
+    }
+    let mut candidates: Vec<(Vec<T>, G::Weight)> = vec![];
+    while found.len() < k {
+        let prev = found.last().unwrap().0.clone();
+        for i in 0..prev.len() - 1 {
+            // deviate from the previous path at the i-th node (the spur node)
+            let root = &prev[..=i];
+            let removed_edges: HashSet<(T, T)> = found
+                .iter()
+                .filter(|(p, _)| p.len() > i + 1 && p[..=i] == *root)
+                .map(|(p, _)| (p[i], p[i + 1]))
+                .collect();
+            let removed_nodes: HashSet<T> = root[..i].iter().copied().collect();
+            if let Some((spur_path, spur_cost)) =
+                shortest_path(g, prev[i], end, &removed_nodes, &removed_edges)
+            {
+                let mut path = root.to_vec();
+                path.extend_from_slice(&spur_path[1..]);
+                let cost = path_cost(g, root) + spur_cost;
+                if !candidates.iter().any(|(p, _)| *p == path) {
+                    candidates.push((path, cost));
+                }
+            }
+        }
+        let best = candidates
+            .iter()
+            .enumerate()
+            .min_by(|(_, (p1, c1)), (_, (p2, c2))| (c1, p1).cmp(&(c2, p2)))
+            .map(|(i, _)| i);
+        match best {
+            Some(i) => found.push(candidates.swap_remove(i)),
+            None => break,
+        }
+    }
+    found
+}
+
+// the cheapest path avoiding given nodes and edges
+fn shortest_path<T, G>(
+    g: &G,
+    start: T,
+    end: T,
+    removed_nodes: &HashSet<T>,
+    removed_edges: &HashSet<(T, T)>,
+) -> Option<(Vec<T>, G::Weight)>
+where
+    T: Hash + Copy + Eq,
+    G: EdgeWeights<T>,
+    G::Weight: Cost,
+{
+    let successors = |v: &T| {
+        let v = *v;
+        g.neighbors_with_weight(&v)
+            .into_iter()
+            .filter(|(w, _)| !removed_nodes.contains(w) && !removed_edges.contains(&(v, *w)))
+            .map(|(w, l)| (w, l.expect("edges must be labeled")))
+            .collect::<Vec<_>>()
+    };
+    uniform_cost(start, successors, |v| *v == end).map(|s| (s.path, s.cost))
+}
+
+fn path_cost<T, G>(g: &G, path: &[T]) -> G::Weight
+where
+    T: Hash + Copy + Eq,
+    G: EdgeWeights<T>,
+    G::Weight: Cost,
+{
+    path.windows(2).fold(G::Weight::default(), |sum, e| {
+        let cheapest = g
+            .neighbors_with_weight(&e[0])
+            .into_iter()
+            .filter(|(w, _)| *w == e[1])
+            .map(|(_, l)| l.expect("edges must be labeled"))
+            .min()
+            .unwrap();
+        sum + cheapest
+    })
+}
+
+/// Returns up to `k` shortest walks from `start` to `end` with their costs, in
+/// increasing order of cost. Unlike paths, walks may visit nodes (and edges)
+/// more than once, so that there are infinitely many walks if `end` is on a
+/// cycle reachable from `start`.
+///
+/// As in Eppstein's algorithm, a walk is represented by its "sidetracks"; the
+/// edges that leave the tree of shortest paths to `end`. Walks are enumerated
+/// best first by extending the sidetrack sequences, each of which costs no less
+/// than its prefix. Edge labels must be present and non-negative.
+pub fn k_shortest_walks<T, G>(g: &G, start: T, end: T, k: usize) -> Vec<(Vec<T>, G::Weight)>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T>,
+    G::Weight: Cost,
+{
+    let mut walks = vec![];
+    if k == 0 || !g.contains(&start) || !g.contains(&end) {
+        return walks;
+    }
+    let tree = ShortestPathTree::new(g, end);
+    if !tree.dist.contains_key(&start) {
+        return walks;
+    }
+
+    // sidetrack sequences with the cost of the walk up to the head of the last sidetrack
+    let mut states: Vec<(Sidetracks<T>, G::Weight)> = vec![(vec![], G::Weight::default())];
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((tree.dist[&start], 0)));
+    while let Some(Reverse((cost, i))) = heap.pop() {
+        let (sidetracks, prefix) = states[i].clone();
+        walks.push((tree.walk(start, &sidetracks), cost));
+        if walks.len() == k {
+            break;
+        }
+        // next sidetracks can leave anywhere on the tree path from the current position
+        let mut prefix = prefix;
+        let mut v = match sidetracks.last() {
+            Some(&(x, j)) => tree.adjs[&x][j].0,
+            None => start,
+        };
+        loop {
+            for (j, &(w, l)) in tree.adjs[&v].iter().enumerate() {
+                if tree.next.get(&v) == Some(&j) || !tree.dist.contains_key(&w) {
+                    continue;
+                }
+                let mut next = sidetracks.clone();
+                next.push((v, j));
+                heap.push(Reverse((prefix + l + tree.dist[&w], states.len())));
+                states.push((next, prefix + l));
+            }
+            match tree.next.get(&v) {
+                Some(&j) => {
+                    let (w, l) = tree.adjs[&v][j];
+                    prefix = prefix + l;
+                    v = w;
+                }
+                None => break,
+            }
+        }
+    }
+    walks
+}
+
+/// Edges leaving the shortest path tree, as (node, index in its adjacency list).
+type Sidetracks<T> = Vec<(T, usize)>;
+
+/// Shortest paths from all nodes to a target node.
+struct ShortestPathTree<T, W> {
+    target: T,
+    adjs: HashMap<T, Vec<(T, W)>>,
+    /// distances to the target from the nodes that can reach it
+    dist: HashMap<T, W>,
+    /// index of the tree edge in the adjacency list of each node but the target
+    next: HashMap<T, usize>,
+}
+
+impl<T: Hash + Copy + Eq + Ord, W: Cost> ShortestPathTree<T, W> {
+    fn new<G: EdgeWeights<T, Weight = W>>(g: &G, target: T) -> Self {
+        let adjs: HashMap<T, Vec<(T, W)>> = g
+            .node_values()
+            .iter()
+            .map(|v| {
+                let adj = g
+                    .neighbors_with_weight(v)
+                    .into_iter()
+                    .map(|(w, l)| (w, l.expect("edges must be labeled")))
+                    .collect();
+                (*v, adj)
+            })
+            .collect();
+        // reversed edges as (source, weight, index in the adjacency list of the source)
+        // (visited in the order of node values, so that ties are broken the same way)
+        let mut values: Vec<T> = adjs.keys().copied().collect();
+        values.sort();
+        let mut rev: HashMap<T, Vec<(T, W, usize)>> = HashMap::new();
+        for v in &values {
+            for (j, (w, l)) in adjs[v].iter().enumerate() {
+                rev.entry(*w).or_default().push((*v, *l, j));
+            }
+        }
+
+        // Dijkstra's algorithm on the reversed graph
+        let mut dist = HashMap::new();
+        let mut next = HashMap::new();
+        let mut heap = BinaryHeap::new();
+        dist.insert(target, W::default());
+        heap.push(Reverse((W::default(), target)));
+        while let Some(Reverse((d, v))) = heap.pop() {
+            if d > dist[&v] {
+                continue;
+            }
+            for &(u, l, j) in rev.get(&v).into_iter().flatten() {
+                let nd = d + l;
+                let shorter = match dist.get(&u) {
+                    Some(&du) => nd < du,
+                    None => true,
+                };
+                if u != target && shorter {
+                    dist.insert(u, nd);
+                    next.insert(u, j);
+                    heap.push(Reverse((nd, u)));
+                }
+            }
+        }
+        ShortestPathTree {
+            target,
+            adjs,
+            dist,
+            next,
+        }
+    }
+
+    // the walk from `start` that follows the tree except for the sidetracks
+    fn walk(&self, start: T, sidetracks: &[(T, usize)]) -> Vec<T> {
+        let mut walk = vec![start];
+        let mut v = start;
+        for &(x, j) in sidetracks {
+            while v != x {
+                v = self.adjs[&v][self.next[&v]].0;
+                walk.push(v);
+            }
+            v = self.adjs[&x][j].0;
+            walk.push(v);
+        }
+        while v != self.target {
+            v = self.adjs[&v][self.next[&v]].0;
+            walk.push(v);
+        }
+        walk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use P80::digraph_converters;
+    use P80::graph_converters;
+
+    #[test]
+    fn test_k_shortest_paths_digraph() {
+        let g = digraph_converters::labeled::from_string(
+            "[c>d/3, c>e/2, d>f/4, e>d/1, e>f/2, e>g/3, f>g/2, f>h/1, g>h/2]",
+        );
+        let paths = k_shortest_paths(&g, 'c', 'h', 3);
+        assert_eq!(
+            paths,
+            vec![
+                (vec!['c', 'e', 'f', 'h'], 5),
+                (vec!['c', 'e', 'g', 'h'], 7),
+                (vec!['c', 'd', 'f', 'h'], 8),
+            ]
+        );
+        // there are 7 loopless paths
+        let paths = k_shortest_paths(&g, 'c', 'h', 10);
+        assert_eq!(paths.len(), 7);
+        assert!(paths.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert_eq!(
+            paths.last().unwrap(),
+            &(vec!['c', 'e', 'd', 'f', 'g', 'h'], 11)
+        );
+
+        assert_eq!(k_shortest_paths(&g, 'h', 'c', 3), vec![]);
+        assert_eq!(k_shortest_paths(&g, 'c', 'c', 3), vec![(vec!['c'], 0)]);
+    }
+
+    #[test]
+    fn test_k_shortest_paths_graph() {
+        let g = graph_converters::labeled::from_string("[a-b/1, b-c/1, a-c/3, c-d/1, b-d/5]");
+        let paths = k_shortest_paths(&g, 'a', 'd', 5);
+        assert_eq!(
+            paths,
+            vec![
+                (vec!['a', 'b', 'c', 'd'], 3),
+                (vec!['a', 'c', 'd'], 4),
+                (vec!['a', 'b', 'd'], 6),
+                (vec!['a', 'c', 'b', 'd'], 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_k_shortest_walks() {
+        let g = digraph_converters::labeled::from_string("[a>b/1, b>a/1, b>c/1]");
+        assert_eq!(
+            k_shortest_walks(&g, 'a', 'c', 3),
+            vec![
+                (vec!['a', 'b', 'c'], 2),
+                (vec!['a', 'b', 'a', 'b', 'c'], 4),
+                (vec!['a', 'b', 'a', 'b', 'a', 'b', 'c'], 6),
+            ]
+        );
+        assert_eq!(k_shortest_walks(&g, 'c', 'a', 3), vec![]);
+
+        // walks in a DAG are paths
+        let g = digraph_converters::labeled::from_string(
+            "[c>d/3, c>e/2, d>f/4, e>d/1, e>f/2, e>g/3, f>g/2, f>h/1, g>h/2]",
+        );
+        let walks = k_shortest_walks(&g, 'c', 'h', 10);
+        let paths = k_shortest_paths(&g, 'c', 'h', 10);
+        let costs = |ps: &[(Vec<char>, i32)]| ps.iter().map(|(_, c)| *c).collect::<Vec<_>>();
+        assert_eq!(costs(&walks), costs(&paths));
+        assert!(paths.iter().all(|p| walks.contains(p)));
+
+        // a walk may pass through the end node
+        let g = graph_converters::labeled::from_string("[a-b/2, b-c/1]");
+        assert_eq!(
+            k_shortest_walks(&g, 'a', 'b', 2),
+            vec![(vec!['a', 'b'], 2), (vec!['a', 'b', 'c', 'b'], 4)]
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use layout::*;
+use std::env;
+use std::fs;
+use std::path::Path;
+use P80::{digraph_converters, graph_converters};
+
+// writes pictures of a graph and a digraph to the directory given as the
+// argument (the current directory by default)
+pub fn main() {
+    let args: Vec<String> = env::args().collect();
+    let dir = Path::new(args.get(1).map(|s| s.as_str()).unwrap_or("."));
+
+    let g = graph_converters::unlabeled::from_string(
+        "[a-b, a-c, b-c, c-d, d-e, d-f, e-f, f-g, g-h, g-i, h-i, i-j]",
+    );
+    let groups = vec![
+        vec!['a', 'b', 'c'],
+        vec!['d', 'e', 'f'],
+        vec!['g', 'h', 'i'],
+    ];
+    let layouts = vec![
+        ("circular", circular_layout(&g)),
+        ("fruchterman_reingold", fruchterman_reingold(&g, 300, 1)),
+        ("spectral", spectral_layout(&g)),
+    ];
+    for (name, positions) in layouts {
+        let path = dir.join(format!("{}.svg", name));
+        fs::write(&path, to_svg(&g, &positions, &groups, (300, 300))).unwrap();
+        println!("{}", path.display());
+    }
+
+    let g = digraph_converters::unlabeled::from_string(
+        "[a>b, a>c, b>d, c>d, c>e, d>f, e>f, f>g, b>g, g>c]",
+    );
+    let positions = sugiyama_layout(&g);
+    let path = dir.join("sugiyama.svg");
+    fs::write(&path, to_svg(&g, &positions, &[vec!['g', 'c']], (300, 400))).unwrap();
+    println!("{}", path.display());
+}
@@ -0,0 +1,285 @@
+use graph::{GraphBase, Neighbors, UnderlyingGraph};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::hash::Hash;
+
+mod sugiyama;
+mod svg;
+
+pub use sugiyama::sugiyama_layout;
+pub use svg::to_svg;
+
+/// Coordinates of nodes. All layouts place nodes in the unit square; x grows to
+/// the right and y grows downwards, as in SVG.
+pub type Positions<T> = HashMap<T, (f64, f64)>;
+
+/// Places the nodes in sorted order on a circle, clockwise from the top.
+pub fn circular_layout<T, G>(g: &G) -> Positions<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: GraphBase<T>,
+{
+    let mut values = g.node_values().into_owned();
+    values.sort();
+    let n = values.len() as f64;
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let angle = 2.0 * PI * i as f64 / n;
+            (v, (0.5 + 0.5 * angle.sin(), 0.5 - 0.5 * angle.cos()))
+        })
+        .collect()
+}
+
+/// Places the nodes by the force-directed algorithm of Fruchterman and
+/// Reingold: all nodes repel each other and adjacent nodes attract each other,
+/// and the nodes move along the forces for `iterations` steps of decreasing
+/// length. Arc directions of digraphs are ignored.
+///
+/// Initial positions are random; the random number generator is seeded by
+/// `seed`, so that the result is reproducible.
+pub fn fruchterman_reingold<T, G>(g: &G, iterations: usize, seed: u64) -> Positions<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let (values, adjs) = indexed(g);
+    let n = values.len();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut pos: Vec<(f64, f64)> = (0..n).map(|_| (rng.gen(), rng.gen())).collect();
+    // the ideal edge length for the unit square
+    let k = (1.0 / n.max(1) as f64).sqrt();
+    let start_temperature = 0.1;
+    for step in 0..iterations {
+        let temperature = start_temperature * (1.0 - step as f64 / iterations as f64);
+        let mut disp = vec![(0.0, 0.0); n];
+        for i in 0..n {
+            for j in 0..n {
+                if i == j {
+                    continue;
+                }
+                let (dx, dy, d) = delta(pos[i], pos[j]);
+                let force = k * k / d;
+                disp[i].0 += dx / d * force;
+                disp[i].1 += dy / d * force;
+            }
+            for j in &adjs[i] {
+                let (dx, dy, d) = delta(pos[i], pos[*j]);
+                let force = d * d / k;
+                disp[i].0 -= dx / d * force;
+                disp[i].1 -= dy / d * force;
+            }
+        }
+        for (p, (dx, dy)) in pos.iter_mut().zip(disp) {
+            let d = (dx * dx + dy * dy).sqrt();
+            if d > 0.0 {
+                let step = d.min(temperature);
+                p.0 += dx / d * step;
+                p.1 += dy / d * step;
+            }
+        }
+    }
+    normalize(values, pos)
+}
+
+// the difference of two points and their distance, which is kept away from zero
+fn delta(p: (f64, f64), q: (f64, f64)) -> (f64, f64, f64) {
+    let (dx, dy) = (p.0 - q.0, p.1 - q.1);
+    (dx, dy, (dx * dx + dy * dy).sqrt().max(1e-3))
+}
+
+/// Places the nodes by the eigenvectors of the graph Laplacian for the second
+/// and third smallest eigenvalues, so that adjacent nodes tend to be close. Arc
+/// directions of digraphs are ignored. Nodes with the same neighbours are
+/// placed at the same position.
+///
+/// The eigenvectors are computed exactly by the Jacobi method, which takes
+/// O(n³) time for n nodes; use `fruchterman_reingold()` for large graphs.
+pub fn spectral_layout<T, G>(g: &G) -> Positions<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let (values, adjs) = indexed(g);
+    let n = values.len();
+    let mut laplacian = vec![vec![0.0; n]; n];
+    for (i, list) in adjs.iter().enumerate() {
+        laplacian[i][i] = list.len() as f64;
+        for j in list {
+            laplacian[i][*j] = -1.0;
+        }
+    }
+    let (eigenvalues, eigenvectors) = symmetric_eigen(laplacian);
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|a, b| eigenvalues[*a].partial_cmp(&eigenvalues[*b]).unwrap());
+    let coordinate = |k: usize| -> Vec<f64> {
+        match order.get(k) {
+            Some(c) => {
+                let column: Vec<f64> = (0..n).map(|i| eigenvectors[i][*c]).collect();
+                // eigenvectors have no sign; make the largest component positive
+                let largest =
+                    column.iter().copied().fold(
+                        0.0,
+                        |m: f64, x| if x.abs() > m.abs() + 1e-9 { x } else { m },
+                    );
+                column.iter().map(|x| x * largest.signum()).collect()
+            }
+            None => vec![0.0; n],
+        }
+    };
+    let (xs, ys) = (coordinate(1), coordinate(2));
+    normalize(values, xs.into_iter().zip(ys).collect())
+}
+
+// eigenvalues and eigenvectors (as columns) of a symmetric matrix, by cyclic
+// Jacobi rotations
+fn symmetric_eigen(mut a: Vec<Vec<f64>>) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = a.len();
+    let mut v: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+    for _ in 0..100 {
+        let off: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |j| *j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        if off < 1e-20 {
+            break;
+        }
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q].abs() < 1e-15 {
+                    continue;
+                }
+                // the rotation that zeroes a[p][q]
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+                for row in a.iter_mut().chain(v.iter_mut()) {
+                    let (kp, kq) = (row[p], row[q]);
+                    row[p] = c * kp - s * kq;
+                    row[q] = s * kp + c * kq;
+                }
+                let (row_p, row_q) = (a[p].clone(), a[q].clone());
+                for (k, (pk, qk)) in row_p.into_iter().zip(row_q).enumerate() {
+                    a[p][k] = c * pk - s * qk;
+                    a[q][k] = s * pk + c * qk;
+                }
+            }
+        }
+    }
+    ((0..n).map(|i| a[i][i]).collect(), v)
+}
+
+// sorted node values and the adjacency lists of the underlying undirected
+// graph by node indices, without self-loops
+fn indexed<T, G>(g: &G) -> (Vec<T>, Vec<Vec<usize>>)
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let g = UnderlyingGraph::new(g);
+    let mut values = g.node_values().into_owned();
+    values.sort();
+    let adjs = values
+        .iter()
+        .map(|v| {
+            let mut list: Vec<usize> = g
+                .neighbors(v)
+                .iter()
+                .filter(|w| *w != v)
+                .map(|w| values.binary_search(w).unwrap())
+                .collect();
+            list.sort_unstable();
+            list
+        })
+        .collect();
+    (values, adjs)
+}
+
+// scales and moves points into the unit square keeping the aspect ratio; the
+// points are centered along the shorter side
+fn normalize<T: Hash + Copy + Eq>(values: Vec<T>, points: Vec<(f64, f64)>) -> Positions<T> {
+    let min = |f: fn(&(f64, f64)) -> f64| points.iter().map(f).fold(f64::INFINITY, f64::min);
+    let max = |f: fn(&(f64, f64)) -> f64| points.iter().map(f).fold(f64::NEG_INFINITY, f64::max);
+    let (min_x, max_x) = (min(|p| p.0), max(|p| p.0));
+    let (min_y, max_y) = (min(|p| p.1), max(|p| p.1));
+    let scale = (max_x - min_x).max(max_y - min_y);
+    let place = |v: f64, lo: f64, hi: f64| {
+        if scale > 1e-9 {
+            (v - lo) / scale + (1.0 - (hi - lo) / scale) / 2.0
+        } else {
+            0.5
+        }
+    };
+    values
+        .into_iter()
+        .zip(points.iter())
+        .map(|(v, (x, y))| (v, (place(*x, min_x, max_x), place(*y, min_y, max_y))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use P80::graph_converters::unlabeled;
+
+    fn distance(pos: &Positions<char>, v: char, w: char) -> f64 {
+        let ((x1, y1), (x2, y2)) = (pos[&v], pos[&w]);
+        ((x1 - x2).powi(2) + (y1 - y2).powi(2)).sqrt()
+    }
+
+    fn in_unit_square(pos: &Positions<char>) -> bool {
+        pos.values()
+            .all(|(x, y)| (-1e-9..=1.0 + 1e-9).contains(x) && (-1e-9..=1.0 + 1e-9).contains(y))
+    }
+
+    #[test]
+    fn test_circular_layout() {
+        let pos = circular_layout(&unlabeled::from_string("[a-b, c, d]"));
+        assert_eq!(pos.len(), 4);
+        let close = |(x1, y1): (f64, f64), (x2, y2): (f64, f64)| {
+            (x1 - x2).abs() < 1e-9 && (y1 - y2).abs() < 1e-9
+        };
+        assert!(close(pos[&'a'], (0.5, 0.0)));
+        assert!(close(pos[&'b'], (1.0, 0.5)));
+        assert!(close(pos[&'c'], (0.5, 1.0)));
+        assert!(close(pos[&'d'], (0.0, 0.5)));
+    }
+
+    #[test]
+    fn test_fruchterman_reingold() {
+        let g = unlabeled::from_string("[a-b, b-c, c-d, d-e]");
+        let pos = fruchterman_reingold(&g, 200, 3);
+        assert_eq!(pos, fruchterman_reingold(&g, 200, 3));
+        assert!(in_unit_square(&pos));
+        assert!(distance(&pos, 'a', 'b') < distance(&pos, 'a', 'e'));
+        assert!(distance(&pos, 'c', 'd') < distance(&pos, 'b', 'e'));
+
+        let pos = fruchterman_reingold(&unlabeled::from_string("[a]"), 10, 0);
+        assert_eq!(pos[&'a'], (0.5, 0.5));
+    }
+
+    #[test]
+    fn test_spectral_layout() {
+        // the Fiedler vector of a path is monotonic along the path
+        let pos = spectral_layout(&unlabeled::from_string("[a-b, b-c, c-d, d-e]"));
+        assert!(in_unit_square(&pos));
+        let xs: Vec<f64> = "abcde".chars().map(|v| pos[&v].0).collect();
+        assert!(xs.windows(2).all(|w| w[0] < w[1]) || xs.windows(2).all(|w| w[0] > w[1]));
+
+        // a cycle is drawn as a regular polygon
+        let pos = spectral_layout(&unlabeled::from_string("[a-b, b-c, c-d, d-e, e-f, f-a]"));
+        let sides: Vec<f64> = "abcdefa"
+            .chars()
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|w| distance(&pos, w[0], w[1]))
+            .collect();
+        assert!(sides.iter().all(|s| (s - sides[0]).abs() < 1e-6));
+    }
+}
@@ -0,0 +1,327 @@
+use super::Positions;
+use graph::Neighbors;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::hash::Hash;
+
+// the number of barycenter sweeps in crossing reduction
+const SWEEPS: usize = 8;
+
+/// Places the nodes of a digraph in horizontal layers so that most arcs point
+/// downwards (Sugiyama's layered drawing).
+///
+/// 1. Cycles are broken by reversing a small set of arcs, chosen by the greedy
+///    heuristic of Eades, Lin and Smyth. Self-loops are ignored.
+/// 2. Each node is put in the layer next below its lowest predecessor (longest
+///    path layering); sources are in the top layer.
+/// 3. Arcs spanning several layers are split by dummy nodes, and nodes are
+///    reordered within layers by the barycenters of their neighbours in the
+///    adjacent layer, keeping the order with the fewest crossings.
+/// 4. Nodes are spread evenly over each layer and centered.
+///
+/// Layers are placed at equal intervals from y = 0 to y = 1.
+pub fn sugiyama_layout<T, G>(g: &G) -> Positions<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let mut values = g.node_values().into_owned();
+    values.sort();
+    let n = values.len();
+    let arcs = acyclic_arcs(g, &values);
+
+    // longest path layering, in topological order
+    let mut succs: Vec<Vec<usize>> = vec![vec![]; n];
+    let mut in_degrees = vec![0; n];
+    for (i, j) in &arcs {
+        succs[*i].push(*j);
+        in_degrees[*j] += 1;
+    }
+    let mut layer_of = vec![0; n];
+    let mut ready: BTreeSet<usize> = (0..n).filter(|i| in_degrees[*i] == 0).collect();
+    while let Some(i) = ready.iter().next().copied() {
+        ready.remove(&i);
+        for j in &succs[i] {
+            layer_of[*j] = layer_of[*j].max(layer_of[i] + 1);
+            in_degrees[*j] -= 1;
+            if in_degrees[*j] == 0 {
+                ready.insert(*j);
+            }
+        }
+    }
+
+    // the layered graph with dummy nodes numbered from n
+    let mut layers: Vec<Vec<usize>> = vec![vec![]; layer_of.iter().max().map_or(0, |l| l + 1)];
+    for i in 0..n {
+        layers[layer_of[i]].push(i);
+    }
+    let mut edges: Vec<(usize, usize)> = vec![];
+    let mut next_id = n;
+    for (i, j) in &arcs {
+        let mut prev = *i;
+        for layer in layers.iter_mut().take(layer_of[*j]).skip(layer_of[*i] + 1) {
+            layer.push(next_id);
+            edges.push((prev, next_id));
+            prev = next_id;
+            next_id += 1;
+        }
+        edges.push((prev, *j));
+    }
+
+    reduce_crossings(&mut layers, &edges, next_id);
+
+    let width = layers.iter().map(|l| l.len()).max().unwrap_or(0);
+    let height = layers.len();
+    let mut pos = vec![(0.0, 0.0); n];
+    for (l, layer) in layers.iter().enumerate() {
+        let offset = (width - layer.len()) as f64 / 2.0;
+        for (k, i) in layer.iter().enumerate() {
+            if *i < n {
+                pos[*i] = (scale(k as f64 + offset, width), scale(l as f64, height));
+            }
+        }
+    }
+    values.into_iter().zip(pos).collect()
+}
+
+// maps 0..count - 1 to 0..1; a single slot is centered
+fn scale(x: f64, count: usize) -> f64 {
+    if count > 1 {
+        x / (count - 1) as f64
+    } else {
+        0.5
+    }
+}
+
+// arcs between node indices, with the arcs that go backwards in the order of
+// Eades, Lin and Smyth's greedy heuristic for feedback arc sets reversed;
+// parallel arcs and self-loops are dropped
+fn acyclic_arcs<T, G>(g: &G, values: &[T]) -> Vec<(usize, usize)>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let n = values.len();
+    let mut arcs: BTreeSet<(usize, usize)> = BTreeSet::new();
+    for (i, v) in values.iter().enumerate() {
+        for w in g.neighbors(v).iter() {
+            let j = values.binary_search(w).unwrap();
+            if i != j {
+                arcs.insert((i, j));
+            }
+        }
+    }
+    let mut succs: Vec<Vec<usize>> = vec![vec![]; n];
+    let mut preds: Vec<Vec<usize>> = vec![vec![]; n];
+    for (i, j) in &arcs {
+        succs[*i].push(*j);
+        preds[*j].push(*i);
+    }
+    // sinks go to the back and sources to the front; if there are neither,
+    // the node with the largest surplus of outgoing arcs goes to the front.
+    // The degrees count arcs between remaining nodes only, and every node is
+    // kept in the bucket of its surplus.
+    let mut outs: Vec<i64> = succs.iter().map(|s| s.len() as i64).collect();
+    let mut ins: Vec<i64> = preds.iter().map(|p| p.len() as i64).collect();
+    let mut sinks: BTreeSet<usize> = (0..n).filter(|i| outs[*i] == 0).collect();
+    let mut sources: BTreeSet<usize> = (0..n).filter(|i| ins[*i] == 0).collect();
+    let mut buckets: BTreeMap<i64, BTreeSet<usize>> = BTreeMap::new();
+    for i in 0..n {
+        buckets.entry(outs[i] - ins[i]).or_default().insert(i);
+    }
+    let mut removed = vec![false; n];
+    let mut front = vec![];
+    let mut back = vec![];
+    for _ in 0..n {
+        let (i, to_front) = if let Some(i) = sinks.iter().next() {
+            (*i, false)
+        } else if let Some(i) = sources.iter().next() {
+            (*i, true)
+        } else {
+            let (_, nodes) = buckets.iter().next_back().unwrap();
+            (*nodes.iter().next().unwrap(), true)
+        };
+        if to_front {
+            front.push(i);
+        } else {
+            back.push(i);
+        }
+        removed[i] = true;
+        sinks.remove(&i);
+        sources.remove(&i);
+        move_to_bucket(&mut buckets, i, outs[i] - ins[i], None);
+        for j in succs[i].iter().filter(|j| !removed[**j]) {
+            let surplus = outs[*j] - ins[*j];
+            ins[*j] -= 1;
+            move_to_bucket(&mut buckets, *j, surplus, Some(surplus + 1));
+            if ins[*j] == 0 {
+                sources.insert(*j);
+            }
+        }
+        for j in preds[i].iter().filter(|j| !removed[**j]) {
+            let surplus = outs[*j] - ins[*j];
+            outs[*j] -= 1;
+            move_to_bucket(&mut buckets, *j, surplus, Some(surplus - 1));
+            if outs[*j] == 0 {
+                sinks.insert(*j);
+            }
+        }
+    }
+    back.reverse();
+    front.extend(back);
+    let mut rank = vec![0; n];
+    for (r, i) in front.iter().enumerate() {
+        rank[*i] = r;
+    }
+    let acyclic: BTreeSet<(usize, usize)> = arcs
+        .into_iter()
+        .map(|(i, j)| if rank[i] < rank[j] { (i, j) } else { (j, i) })
+        .collect();
+    acyclic.into_iter().collect()
+}
+
+// moves node `i` from the bucket `from` to the bucket `to` (if any), dropping
+// emptied buckets
+fn move_to_bucket(
+    buckets: &mut BTreeMap<i64, BTreeSet<usize>>,
+    i: usize,
+    from: i64,
+    to: Option<i64>,
+) {
+    let bucket = buckets.get_mut(&from).unwrap();
+    bucket.remove(&i);
+    if bucket.is_empty() {
+        buckets.remove(&from);
+    }
+    if let Some(to) = to {
+        buckets.entry(to).or_default().insert(i);
+    }
+}
+
+fn reduce_crossings(layers: &mut Vec<Vec<usize>>, edges: &[(usize, usize)], count: usize) {
+    let mut preds: Vec<Vec<usize>> = vec![vec![]; count];
+    let mut succs: Vec<Vec<usize>> = vec![vec![]; count];
+    for (i, j) in edges {
+        succs[*i].push(*j);
+        preds[*j].push(*i);
+    }
+    let mut best = layers.clone();
+    let mut best_crossings = crossings(layers, &succs, count);
+    for sweep in 0..SWEEPS {
+        if sweep % 2 == 0 {
+            for l in 1..layers.len() {
+                let (above, rest) = layers.split_at_mut(l);
+                order_by_barycenter(&mut rest[0], &above[l - 1], &preds);
+            }
+        } else {
+            for l in (0..layers.len().saturating_sub(1)).rev() {
+                let (rest, below) = layers.split_at_mut(l + 1);
+                order_by_barycenter(&mut rest[l], &below[0], &succs);
+            }
+        }
+        let c = crossings(layers, &succs, count);
+        if c < best_crossings {
+            best = layers.clone();
+            best_crossings = c;
+        }
+    }
+    *layers = best;
+}
+
+// sorts a layer by the mean positions of the neighbours in the fixed layer;
+// nodes without such neighbours keep their positions
+fn order_by_barycenter(layer: &mut Vec<usize>, fixed: &[usize], adjs: &[Vec<usize>]) {
+    let position: HashMap<usize, f64> = fixed
+        .iter()
+        .enumerate()
+        .map(|(k, i)| (*i, k as f64))
+        .collect();
+    let mut keyed: Vec<(f64, usize)> = layer
+        .iter()
+        .enumerate()
+        .map(|(k, i)| {
+            let adjacent: Vec<f64> = adjs[*i]
+                .iter()
+                .filter_map(|j| position.get(j).copied())
+                .collect();
+            if adjacent.is_empty() {
+                (k as f64, *i)
+            } else {
+                (adjacent.iter().sum::<f64>() / adjacent.len() as f64, *i)
+            }
+        })
+        .collect();
+    // stable, so that ties keep their order
+    keyed.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    *layer = keyed.into_iter().map(|(_, i)| i).collect();
+}
+
+// the number of crossing edges between all adjacent layers
+fn crossings(layers: &[Vec<usize>], succs: &[Vec<usize>], count: usize) -> usize {
+    let mut position = vec![0; count];
+    for layer in layers {
+        for (k, i) in layer.iter().enumerate() {
+            position[*i] = k;
+        }
+    }
+    let mut total = 0;
+    for layer in layers {
+        let mut edges: Vec<(usize, usize)> = layer
+            .iter()
+            .flat_map(|i| succs[*i].iter().map(move |j| (*i, *j)))
+            .map(|(i, j)| (position[i], position[j]))
+            .collect();
+        // two edges cross if one starts left of the other and ends right of
+        // it; counted for each edge over the edges before it in this order,
+        // with the ends seen so far in a Fenwick tree
+        edges.sort_unstable();
+        let width = edges.iter().map(|(_, k)| k + 1).max().unwrap_or(0);
+        let mut tree = vec![0; width + 1];
+        for (seen, (_, k)) in edges.iter().enumerate() {
+            // the number of ends seen at positions up to k
+            let mut not_right = 0;
+            let mut x = k + 1;
+            while x > 0 {
+                not_right += tree[x];
+                x &= x - 1;
+            }
+            total += seen - not_right;
+            let mut x = k + 1;
+            while x <= width {
+                tree[x] += 1;
+                x += x & x.wrapping_neg();
+            }
+        }
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use P80::digraph_converters::unlabeled;
+
+    #[test]
+    fn test_sugiyama_layout() {
+        let pos = sugiyama_layout(&unlabeled::from_string("[a>b, a>c, b>d, c>d]"));
+        assert_eq!(pos[&'a'], (0.5, 0.0));
+        assert_eq!(pos[&'b'], (0.0, 0.5));
+        assert_eq!(pos[&'c'], (1.0, 0.5));
+        assert_eq!(pos[&'d'], (0.5, 1.0));
+
+        // the arcs are uncrossed by swapping c and d
+        let pos = sugiyama_layout(&unlabeled::from_string("[a>d, b>c]"));
+        assert_eq!(pos[&'a'].0, pos[&'d'].0);
+        assert_eq!(pos[&'b'].0, pos[&'c'].0);
+    }
+
+    #[test]
+    fn test_sugiyama_layout_cycles() {
+        // a cycle is drawn top down from its smallest node; the long arc c>a
+        // (reversed) does not change the layers
+        let pos = sugiyama_layout(&unlabeled::from_string("[a>b, b>c, c>a, c>c, d]"));
+        assert_eq!(pos[&'a'].1, 0.0);
+        assert_eq!(pos[&'b'].1, 0.5);
+        assert_eq!(pos[&'c'].1, 1.0);
+        assert_eq!(pos[&'d'].1, 0.0);
+    }
+}
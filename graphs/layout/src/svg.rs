@@ -0,0 +1,157 @@
+use super::Positions;
+use graph::{Directed, Neighbors};
+use std::fmt;
+use std::hash::Hash;
+
+const NODE_RADIUS: f64 = 12.0;
+// space around the unit square so that nodes on its border are not cut off
+const MARGIN: f64 = 2.0 * NODE_RADIUS;
+// colors of highlighted subsets, reused in turn if there are more subsets
+const COLORS: [&str; 6] = [
+    "#e41a1c", "#377eb8", "#4daf4a", "#984ea3", "#ff7f00", "#a65628",
+];
+
+/// Draws a graph in SVG with nodes at `positions` (e.g. returned by one of the
+/// layouts) scaled to a picture of `width` x `height` pixels.
+///
+/// Nodes are circles labeled with their values. The nodes of each subset in
+/// `highlights` are filled with a color of their own, as are the edges between
+/// them; the first subset that contains a node wins. Arcs of digraphs end with
+/// arrowheads. Self-loops are not drawn.
+///
+/// Panics if a node has no position.
+pub fn to_svg<T, G>(
+    g: &G,
+    positions: &Positions<T>,
+    highlights: &[Vec<T>],
+    size: (u32, u32),
+) -> String
+where
+    T: Hash + Copy + Eq + Ord + fmt::Display,
+    G: Neighbors<T> + Directed,
+{
+    let (width, height) = (f64::from(size.0), f64::from(size.1));
+    let point = |v: &T| {
+        let (x, y) = positions.get(v).expect("every node must have a position");
+        (
+            MARGIN + x * (width - 2.0 * MARGIN),
+            MARGIN + y * (height - 2.0 * MARGIN),
+        )
+    };
+    let color = |v: &T| {
+        highlights
+            .iter()
+            .position(|h| h.contains(v))
+            .map(|i| COLORS[i % COLORS.len()])
+    };
+    let mut values = g.node_values().into_owned();
+    values.sort();
+
+    let mut lines = vec![format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        size.0, size.1, size.0, size.1
+    )];
+    if g.is_directed() {
+        lines.push(
+            r#"  <defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="6" markerHeight="6" orient="auto"><path d="M0,0 L10,5 L0,10 z"/></marker></defs>"#
+                .to_string(),
+        );
+    }
+    for v in &values {
+        let mut adjs = g.neighbors(v).into_owned();
+        adjs.sort();
+        adjs.dedup();
+        for w in adjs {
+            if w == *v || (!g.is_directed() && w < *v) {
+                continue;
+            }
+            let ((x1, y1), (x2, y2)) = (point(v), point(&w));
+            let d = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+            if d <= 2.0 * NODE_RADIUS {
+                continue;
+            }
+            // lines run between the borders of the circles
+            let (ux, uy) = ((x2 - x1) / d, (y2 - y1) / d);
+            let stroke = match (color(v), color(&w)) {
+                (Some(c1), Some(c2)) if c1 == c2 => c1,
+                _ => "#999999",
+            };
+            lines.push(format!(
+                r#"  <line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}"{}/>"#,
+                x1 + ux * NODE_RADIUS,
+                y1 + uy * NODE_RADIUS,
+                x2 - ux * NODE_RADIUS,
+                y2 - uy * NODE_RADIUS,
+                stroke,
+                if g.is_directed() {
+                    r#" marker-end="url(#arrow)""#
+                } else {
+                    ""
+                }
+            ));
+        }
+    }
+    for v in &values {
+        let (x, y) = point(v);
+        lines.push(format!(
+            r##"  <circle cx="{:.1}" cy="{:.1}" r="{}" fill="{}" stroke="#333333"/>"##,
+            x,
+            y,
+            NODE_RADIUS,
+            color(v).unwrap_or("#ffffff")
+        ));
+        lines.push(format!(
+            r#"  <text x="{:.1}" y="{:.1}" text-anchor="middle" dominant-baseline="central" font-family="sans-serif" font-size="12">{}</text>"#,
+            x,
+            y,
+            escape(&v.to_string())
+        ));
+    }
+    lines.push("</svg>".to_string());
+    lines.join("\n")
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use P80::{digraph_converters, graph_converters};
+
+    #[test]
+    fn test_to_svg() {
+        let g = graph_converters::unlabeled::from_string("[a-b, b-c, c-a, c-d]");
+        let mut pos = Positions::new();
+        pos.insert('a', (0.0, 0.0));
+        pos.insert('b', (1.0, 0.0));
+        pos.insert('c', (0.0, 1.0));
+        pos.insert('d', (1.0, 1.0));
+        let svg = to_svg(&g, &pos, &[vec!['a', 'b']], (100, 100));
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="100""#));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<line").count(), 4);
+        assert_eq!(svg.matches("<circle").count(), 4);
+        assert!(!svg.contains("marker"));
+        assert!(
+            svg.contains(r##"<line x1="36.0" y1="24.0" x2="64.0" y2="24.0" stroke="#e41a1c"/>"##)
+        );
+        assert!(svg.contains(r##"<circle cx="24.0" cy="24.0" r="12" fill="#e41a1c""##));
+        assert!(svg.contains(r##"<circle cx="76.0" cy="76.0" r="12" fill="#ffffff""##));
+        assert!(svg.contains(">d</text>"));
+    }
+
+    #[test]
+    fn test_to_svg_digraph() {
+        let g = digraph_converters::unlabeled::from_string("[a>b, b>a, b>b]");
+        let mut pos = Positions::new();
+        pos.insert('a', (0.0, 0.0));
+        pos.insert('b', (1.0, 0.0));
+        let svg = to_svg(&g, &pos, &[], (100, 50));
+        assert_eq!(svg.matches("<line").count(), 2);
+        assert_eq!(svg.matches(r#"marker-end="url(#arrow)""#).count(), 2);
+    }
+}
@@ -0,0 +1,11 @@
+use metrics::*;
+use P80::graph_converters::unlabeled;
+
+pub fn main() {
+    let g = unlabeled::from_string("[a-b, a-c, b-c, c-d, d-e]");
+    println!("degree: {:?}", ranking(&g.degree_centrality()));
+    println!("closeness: {:?}", ranking(&g.closeness_centrality()));
+    println!("betweenness: {:?}", ranking(&g.betweenness_centrality(true)));
+    println!("clustering: {:?}", ranking(&g.clustering()));
+    println!("transitivity: {}", g.transitivity());
+}
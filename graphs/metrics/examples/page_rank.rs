@@ -0,0 +1,8 @@
+use metrics::*;
+use P80::digraph_converters::unlabeled;
+
+pub fn main() {
+    let g = unlabeled::from_string("[a>b, a>c, b>c, c>a, d>c]");
+    let ranks = g.page_rank(0.85, 100, 1e-6).unwrap();
+    println!("{:?}", ranking(&ranks));
+}
@@ -0,0 +1,46 @@
+use graph::{CsrGraph, LabeledGraph};
+use metrics::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+
+pub fn main() {
+    // a 21x21 grid of streets, frozen into a graph that threads can share
+    let mut g = LabeledGraph::<u32, ()>::new();
+    for v in 0..441 {
+        g.add_node(v);
+    }
+    for v in 0..441 {
+        if v % 21 < 20 {
+            g.add_edge(v, v + 1);
+        }
+        if v < 420 {
+            g.add_edge(v, v + 21);
+        }
+    }
+    let g = Arc::new(CsrGraph::from_graph(&g));
+
+    // plain threads read it at the same time
+    let handles: Vec<_> = (0..4)
+        .map(|k| {
+            let g = Arc::clone(&g);
+            thread::spawn(move || (k..441).step_by(4).map(|v| g.degree(&v)).sum::<usize>())
+        })
+        .collect();
+    let degrees: usize = handles.into_iter().map(|h| h.join().unwrap()).sum();
+    println!("streets: {}", degrees / 2);
+
+    // the expensive metrics run on all cores
+    let distances = g.par_all_pairs_distances();
+    println!("from a corner to the center: {}", distances[&0][&220]);
+    let diameter = distances.values().flat_map(|d| d.values()).max().unwrap();
+    println!("diameter: {}", diameter);
+    // rounded, as sums in parallel may differ in the last digits
+    let betweenness: HashMap<u32, f64> = g
+        .par_betweenness_centrality(true)
+        .into_iter()
+        .map(|(v, c)| (v, (c * 1000.0).round() / 1000.0))
+        .collect();
+    let top: Vec<(u32, f64)> = ranking(&betweenness).into_iter().take(3).collect();
+    println!("busiest crossings: {:?}", top);
+}
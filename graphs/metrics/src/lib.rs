@@ -0,0 +1,545 @@
+use graph::{Directed, Neighbors};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Centrality and ranking metrics over graphs and digraphs.
+///
+/// All metrics are provided for any graph that tells its nodes, neighbours
+/// and directedness. Edge labels are ignored, i.e. every edge (arc) has
+/// length 1.
+///
+/// With the `rayon` feature, the `par_` methods compute the most expensive
+/// metrics from all source nodes in parallel. The graph itself is only read
+/// before the threads start, so it does not need to be `Sync`.
+pub trait Metrics<T: Hash + Copy + Eq + Ord>: Neighbors<T> + Directed {
+    /// Degree centrality; the number of (in and out) neighbors divided by `n - 1`.
+    fn degree_centrality(&self) -> HashMap<T, f64> {
+        let adj = Adjacency::new(self);
+        let n = adj.size();
+        let scale = if n > 1 { 1.0 / (n - 1) as f64 } else { 1.0 };
+        (0..n)
+            .map(|i| {
+                let degree = if adj.directed {
+                    adj.outs[i].len() + adj.ins[i].len()
+                } else {
+                    adj.outs[i].len()
+                };
+                (adj.values[i], degree as f64 * scale)
+            })
+            .collect()
+    }
+
+    /// Closeness centrality; the inverse of the average distance from a node to
+    /// the nodes reachable from it, scaled by the fraction of reachable nodes
+    /// (Wasserman and Faust) so that disconnected graphs are handled.
+    fn closeness_centrality(&self) -> HashMap<T, f64> {
+        let adj = Adjacency::new(self);
+        let n = adj.size();
+        (0..n)
+            .map(|i| {
+                let dists = adj.distances_from(i);
+                let reachable: Vec<usize> = dists.iter().filter_map(|d| *d).collect();
+                let total: usize = reachable.iter().sum();
+                let score = if total > 0 && n > 1 {
+                    let r = (reachable.len() - 1) as f64;
+                    (r / total as f64) * (r / (n - 1) as f64)
+                } else {
+                    0.0
+                };
+                (adj.values[i], score)
+            })
+            .collect()
+    }
+
+    /// Distances (numbers of edges on shortest paths) from every node to every
+    /// node reachable from it, itself included.
+    fn all_pairs_distances(&self) -> HashMap<T, HashMap<T, usize>> {
+        let adj = Adjacency::new(self);
+        (0..adj.size())
+            .map(|s| (adj.values[s], adj.distance_map(s)))
+            .collect()
+    }
+
+    /// Same as `all_pairs_distances()`, searching from the nodes in parallel.
+    #[cfg(feature = "rayon")]
+    fn par_all_pairs_distances(&self) -> HashMap<T, HashMap<T, usize>>
+    where
+        T: Send + Sync,
+    {
+        let adj = Adjacency::new(self);
+        (0..adj.size())
+            .into_par_iter()
+            .map(|s| (adj.values[s], adj.distance_map(s)))
+            .collect()
+    }
+
+    /// Betweenness centrality by Brandes' algorithm.
+    ///
+    /// If `normalized` is true, scores are divided by the number of node pairs
+    /// not including the node itself.
+    fn betweenness_centrality(&self, normalized: bool) -> HashMap<T, f64> {
+        let adj = Adjacency::new(self);
+        let mut centrality = vec![0.0; adj.size()];
+        for s in 0..adj.size() {
+            for (c, d) in centrality.iter_mut().zip(adj.dependencies(s)) {
+                *c += d;
+            }
+        }
+        adj.betweenness(&centrality, normalized)
+    }
+
+    /// Same as `betweenness_centrality()`, accumulating the dependencies on the
+    /// paths from each node in parallel.
+    #[cfg(feature = "rayon")]
+    fn par_betweenness_centrality(&self, normalized: bool) -> HashMap<T, f64>
+    where
+        T: Send + Sync,
+    {
+        let adj = Adjacency::new(self);
+        let n = adj.size();
+        let centrality = (0..n).into_par_iter().map(|s| adj.dependencies(s)).reduce(
+            || vec![0.0; n],
+            |mut sum, delta| {
+                for (c, d) in sum.iter_mut().zip(delta) {
+                    *c += d;
+                }
+                sum
+            },
+        );
+        adj.betweenness(&centrality, normalized)
+    }
+
+    /// Eigenvector centrality by power iteration; a node is important if it is
+    /// pointed to by important nodes.
+    ///
+    /// Returns None if the iteration does not converge within `max_iter` rounds.
+    fn eigenvector_centrality(&self, max_iter: usize, tolerance: f64) -> Option<HashMap<T, f64>> {
+        let adj = Adjacency::new(self);
+        let n = adj.size();
+        if n == 0 {
+            return Some(HashMap::new());
+        }
+        let mut x = vec![1.0 / n as f64; n];
+        for _ in 0..max_iter {
+            // iterate with (A + I) to avoid oscillation on bipartite graphs
+            let mut next = x.clone();
+            for (xv, outs) in x.iter().zip(adj.outs.iter()) {
+                for &w in outs {
+                    next[w] += xv;
+                }
+            }
+            let norm = next.iter().map(|a| a * a).sum::<f64>().sqrt();
+            let norm = if norm == 0.0 { 1.0 } else { norm };
+            next.iter_mut().for_each(|a| *a /= norm);
+            let err: f64 = next.iter().zip(x.iter()).map(|(a, b)| (a - b).abs()).sum();
+            x = next;
+            if err < n as f64 * tolerance {
+                return Some(adj.to_map(&x));
+            }
+        }
+        None
+    }
+
+    /// PageRank with the given damping factor. Dangling nodes (nodes without
+    /// successors) distribute their rank evenly to all nodes.
+    ///
+    /// Returns None if the iteration does not converge within `max_iter` rounds.
+    fn page_rank(&self, damping: f64, max_iter: usize, tolerance: f64) -> Option<HashMap<T, f64>> {
+        assert!(
+            (0.0..=1.0).contains(&damping),
+            "damping factor must be in [0, 1]"
+        );
+        let adj = Adjacency::new(self);
+        let n = adj.size();
+        if n == 0 {
+            return Some(HashMap::new());
+        }
+        let mut x = vec![1.0 / n as f64; n];
+        for _ in 0..max_iter {
+            let dangling: f64 = (0..n)
+                .filter(|&v| adj.outs[v].is_empty())
+                .map(|v| x[v])
+                .sum();
+            let base = (1.0 - damping) / n as f64 + damping * dangling / n as f64;
+            let mut next = vec![base; n];
+            for (xv, outs) in x.iter().zip(adj.outs.iter()) {
+                let share = xv / outs.len() as f64;
+                for &w in outs {
+                    next[w] += damping * share;
+                }
+            }
+            let err: f64 = next.iter().zip(x.iter()).map(|(a, b)| (a - b).abs()).sum();
+            x = next;
+            if err < n as f64 * tolerance {
+                return Some(adj.to_map(&x));
+            }
+        }
+        None
+    }
+
+    /// Local clustering coefficient; the fraction of pairs of neighbors that are
+    /// linked to each other. Directions are ignored for digraphs.
+    fn clustering(&self) -> HashMap<T, f64> {
+        let adj = Adjacency::new(self).undirected();
+        (0..adj.size())
+            .map(|v| {
+                let (triangles, triads) = adj.triangles_and_triads(v);
+                let c = if triads > 0 {
+                    triangles as f64 / triads as f64
+                } else {
+                    0.0
+                };
+                (adj.values[v], c)
+            })
+            .collect()
+    }
+
+    /// Average of the local clustering coefficients.
+    fn average_clustering(&self) -> f64 {
+        let c = self.clustering();
+        if c.is_empty() {
+            0.0
+        } else {
+            c.values().sum::<f64>() / c.len() as f64
+        }
+    }
+
+    /// Transitivity; the fraction of all connected triples that are closed
+    /// (form triangles). Directions are ignored for digraphs.
+    fn transitivity(&self) -> f64 {
+        let adj = Adjacency::new(self).undirected();
+        let (triangles, triads) = (0..adj.size())
+            .map(|v| adj.triangles_and_triads(v))
+            .fold((0, 0), |(t1, t2), (a, b)| (t1 + a, t2 + b));
+        if triads > 0 {
+            triangles as f64 / triads as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+impl<T, G> Metrics<T> for G
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T> + Directed,
+{
+}
+
+/// Ranks nodes by the given scores in decreasing order; if there are nodes
+/// that have a same score, sort them by their values.
+pub fn ranking<T: Hash + Copy + Eq + Ord>(scores: &HashMap<T, f64>) -> Vec<(T, f64)> {
+    let mut ranks: Vec<(T, f64)> = scores.iter().map(|(&v, &s)| (v, s)).collect();
+    ranks.sort_by(|(v1, s1), (v2, s2)| match s2.partial_cmp(s1) {
+        Some(Ordering::Equal) | None => v1.cmp(v2),
+        Some(ord) => ord,
+    });
+    ranks
+}
+
+/// Index based adjacency lists; nodes are numbered by their sorted values so
+/// that all metrics are deterministic.
+struct Adjacency<T> {
+    values: Vec<T>,
+    outs: Vec<Vec<usize>>,
+    ins: Vec<Vec<usize>>,
+    directed: bool,
+}
+
+impl<T: Hash + Copy + Eq + Ord> Adjacency<T> {
+    fn new<G: Metrics<T> + ?Sized>(g: &G) -> Self {
+        let mut values = g.node_values().into_owned();
+        values.sort();
+        let index: HashMap<T, usize> = values.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        let mut outs = vec![vec![]; values.len()];
+        let mut ins = vec![vec![]; values.len()];
+        for (i, v) in values.iter().enumerate() {
+            for w in g.neighbors(v).iter() {
+                let j = index[w];
+                // parallel edges and self-loops do not count
+                if i != j && !outs[i].contains(&j) {
+                    outs[i].push(j);
+                    ins[j].push(i);
+                }
+            }
+        }
+        Adjacency {
+            values,
+            outs,
+            ins,
+            directed: g.is_directed(),
+        }
+    }
+
+    /// Forgets the directions of arcs.
+    fn undirected(mut self) -> Self {
+        if self.directed {
+            for i in 0..self.values.len() {
+                let ins = self.ins[i].clone();
+                for j in ins {
+                    if !self.outs[i].contains(&j) {
+                        self.outs[i].push(j);
+                    }
+                }
+            }
+            self.ins = self.outs.clone();
+            self.directed = false;
+        }
+        self
+    }
+
+    fn size(&self) -> usize {
+        self.values.len()
+    }
+
+    fn distances_from(&self, s: usize) -> Vec<Option<usize>> {
+        let mut dist = vec![None; self.size()];
+        dist[s] = Some(0);
+        let mut queue = VecDeque::new();
+        queue.push_back(s);
+        while let Some(v) = queue.pop_front() {
+            for &w in &self.outs[v] {
+                if dist[w].is_none() {
+                    dist[w] = Some(dist[v].unwrap() + 1);
+                    queue.push_back(w);
+                }
+            }
+        }
+        dist
+    }
+
+    fn distance_map(&self, s: usize) -> HashMap<T, usize> {
+        self.distances_from(s)
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, d)| d.map(|d| (self.values[i], d)))
+            .collect()
+    }
+
+    /// Returns the dependencies of all nodes on the shortest paths from `s`
+    /// (Brandes); 0 for `s` itself.
+    fn dependencies(&self, s: usize) -> Vec<f64> {
+        let n = self.size();
+        // single-source shortest paths (BFS)
+        let mut stack = vec![];
+        let mut preds: Vec<Vec<usize>> = vec![vec![]; n];
+        let mut sigma = vec![0.0; n];
+        let mut dist: Vec<Option<usize>> = vec![None; n];
+        sigma[s] = 1.0;
+        dist[s] = Some(0);
+        let mut queue = VecDeque::new();
+        queue.push_back(s);
+        while let Some(v) = queue.pop_front() {
+            stack.push(v);
+            let dv = dist[v].unwrap();
+            for &w in &self.outs[v] {
+                if dist[w].is_none() {
+                    dist[w] = Some(dv + 1);
+                    queue.push_back(w);
+                }
+                if dist[w] == Some(dv + 1) {
+                    sigma[w] += sigma[v];
+                    preds[w].push(v);
+                }
+            }
+        }
+
+        // accumulate dependencies in order of non-increasing distance
+        let mut delta = vec![0.0; n];
+        while let Some(w) = stack.pop() {
+            for &v in &preds[w] {
+                delta[v] += sigma[v] / sigma[w] * (1.0 + delta[w]);
+            }
+        }
+        delta[s] = 0.0;
+        delta
+    }
+
+    /// Scales the summed dependencies to betweenness centralities.
+    fn betweenness(&self, centrality: &[f64], normalized: bool) -> HashMap<T, f64> {
+        let n = self.size();
+        // each path was counted from both ends in undirected graphs, and there
+        // are (n - 1)(n - 2) ordered pairs of the other nodes.
+        let scale = if normalized && n > 2 {
+            1.0 / ((n - 1) * (n - 2)) as f64
+        } else if self.directed {
+            1.0
+        } else {
+            0.5
+        };
+        let scores: Vec<f64> = centrality.iter().map(|c| c * scale).collect();
+        self.to_map(&scores)
+    }
+
+    /// Returns the number of linked neighbor pairs and all neighbor pairs of `v`.
+    fn triangles_and_triads(&self, v: usize) -> (usize, usize) {
+        let nbrs = &self.outs[v];
+        let k = nbrs.len();
+        let mut triangles = 0;
+        for (i, a) in nbrs.iter().enumerate() {
+            for b in &nbrs[i + 1..] {
+                if self.outs[*a].contains(b) {
+                    triangles += 1;
+                }
+            }
+        }
+        (triangles, k * k.saturating_sub(1) / 2)
+    }
+
+    fn to_map(&self, scores: &[f64]) -> HashMap<T, f64> {
+        self.values
+            .iter()
+            .copied()
+            .zip(scores.iter().copied())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::CsrGraph;
+    use P80::digraph_converters;
+    use P80::graph_converters::unlabeled;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "expected {}, but was {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_degree_centrality() {
+        let g = unlabeled::from_string("[a-b, a-c, a-d, c-d]");
+        let c = g.degree_centrality();
+        assert_close(c[&'a'], 1.0);
+        assert_close(c[&'b'], 1.0 / 3.0);
+        assert_close(c[&'c'], 2.0 / 3.0);
+
+        let g = digraph_converters::unlabeled::from_string("[a>b, a>c, c>a]");
+        let c = g.degree_centrality();
+        assert_close(c[&'a'], 1.5);
+        assert_close(c[&'b'], 0.5);
+    }
+
+    #[test]
+    fn test_closeness_centrality() {
+        let g = unlabeled::from_string("[a-b, b-c, c-d]");
+        let c = g.closeness_centrality();
+        assert_close(c[&'a'], 3.0 / 6.0);
+        assert_close(c[&'b'], 3.0 / 4.0);
+
+        let g = unlabeled::from_string("[a-b, c]");
+        let c = g.closeness_centrality();
+        assert_close(c[&'a'], 0.5);
+        assert_close(c[&'c'], 0.0);
+    }
+
+    #[test]
+    fn test_betweenness_centrality() {
+        let g = unlabeled::from_string("[a-b, b-c, c-d]");
+        let c = g.betweenness_centrality(false);
+        assert_close(c[&'a'], 0.0);
+        assert_close(c[&'b'], 2.0);
+        assert_close(c[&'c'], 2.0);
+        let c = g.betweenness_centrality(true);
+        assert_close(c[&'b'], 2.0 / 3.0);
+
+        let g = digraph_converters::unlabeled::from_string("[a>b, b>c]");
+        let c = g.betweenness_centrality(false);
+        assert_close(c[&'b'], 1.0);
+        let c = g.betweenness_centrality(true);
+        assert_close(c[&'b'], 0.5);
+    }
+
+    #[test]
+    fn test_all_pairs_distances() {
+        let g = unlabeled::from_string("[a-b, b-c, c-d, e]");
+        let d = g.all_pairs_distances();
+        assert_eq!(d[&'a'][&'d'], 3);
+        assert_eq!(d[&'c'][&'a'], 2);
+        assert_eq!(d[&'b'][&'b'], 0);
+        assert_eq!(d[&'e'].len(), 1);
+        assert!(!d[&'a'].contains_key(&'e'));
+
+        let g = digraph_converters::unlabeled::from_string("[a>b, b>c]");
+        let d = g.all_pairs_distances();
+        assert_eq!(d[&'a'][&'c'], 2);
+        assert!(!d[&'c'].contains_key(&'a'));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_parallel_metrics() {
+        let g = unlabeled::from_string("[a-b, b-c, c-d, d-a, a-c, c-e, e-f, g-h]");
+        assert_eq!(g.par_all_pairs_distances(), g.all_pairs_distances());
+        let c1 = g.betweenness_centrality(true);
+        let c2 = g.par_betweenness_centrality(true);
+        for v in "abcdefgh".chars() {
+            assert_close(c2[&v], c1[&v]);
+        }
+        let g = digraph_converters::unlabeled::from_string("[a>b, b>c, c>a, c>d]");
+        assert_close(g.par_betweenness_centrality(false)[&'c'], 3.0);
+    }
+
+    #[test]
+    fn test_csr_graph() {
+        let g = CsrGraph::from_graph(&unlabeled::from_string("[a-b, b-c, c-d]"));
+        let c = g.closeness_centrality();
+        assert_close(c[&'b'], 3.0 / 4.0);
+        let c = g.betweenness_centrality(false);
+        assert_close(c[&'c'], 2.0);
+
+        let g = CsrGraph::from_digraph(&digraph_converters::unlabeled::from_string("[a>b, b>c]"));
+        let c = g.betweenness_centrality(true);
+        assert_close(c[&'b'], 0.5);
+    }
+
+    #[test]
+    fn test_eigenvector_centrality() {
+        let g = unlabeled::from_string("[a-b, a-c, a-d]");
+        let c = g.eigenvector_centrality(100, 1e-8).unwrap();
+        assert_close(c[&'a'], std::f64::consts::FRAC_1_SQRT_2);
+        assert_close(c[&'b'], 1.0 / 6f64.sqrt());
+    }
+
+    #[test]
+    fn test_page_rank() {
+        let g = digraph_converters::unlabeled::from_string("[a>b, b>c, c>a]");
+        let r = g.page_rank(0.85, 100, 1e-6).unwrap();
+        assert_close(r[&'a'], 1.0 / 3.0);
+
+        let g = digraph_converters::unlabeled::from_string("[a>c, b>c, c>a]");
+        let r = g.page_rank(0.85, 100, 1e-6).unwrap();
+        assert_close(r.values().sum(), 1.0);
+        assert!(r[&'c'] > r[&'a'] && r[&'a'] > r[&'b']);
+        assert_eq!(g.page_rank(0.85, 1, 1e-6), None);
+    }
+
+    #[test]
+    fn test_clustering() {
+        let g = unlabeled::from_string("[a-b, a-c, b-c, c-d]");
+        let c = g.clustering();
+        assert_close(c[&'a'], 1.0);
+        assert_close(c[&'c'], 1.0 / 3.0);
+        assert_close(c[&'d'], 0.0);
+        assert_close(g.average_clustering(), (1.0 + 1.0 + 1.0 / 3.0) / 4.0);
+        assert_close(g.transitivity(), 0.6);
+    }
+
+    #[test]
+    fn test_ranking() {
+        let g = unlabeled::from_string("[a-b, a-c, b-c, c-d]");
+        let ranks: Vec<char> = ranking(&g.degree_centrality())
+            .into_iter()
+            .map(|(v, _)| v)
+            .collect();
+        assert_eq!(ranks, vec!['c', 'a', 'b', 'd']);
+    }
+}
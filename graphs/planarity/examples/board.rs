@@ -0,0 +1,33 @@
+use planarity::*;
+use P80::graph_converters::unlabeled;
+
+pub fn main() {
+    // components and the wires between them on a single-layer circuit board
+    let boards = vec![
+        "[a-b, b-c, c-d, d-a, a-e, b-e, c-e, d-e]",
+        "[a-b, a-x, a-y, a-z, b-x, b-y, b-z, c-x, c-y, c-w, w-z, y-v]",
+    ];
+    for board in boards {
+        println!("{}", board);
+        match check_planarity(&unlabeled::from_string(board)) {
+            Planarity::Planar(embedding) => {
+                for v in "abcde".chars() {
+                    println!(
+                        "  wires around {} (clockwise): {:?}",
+                        v,
+                        embedding.rotation(&v)
+                    );
+                }
+                println!("  faces: {:?}", embedding.faces());
+            }
+            Planarity::NonPlanar(witness) => {
+                let wires: Vec<(char, char)> = witness
+                    .get_edges()
+                    .iter()
+                    .map(|(v, w, _)| (*v, *w))
+                    .collect();
+                println!("  crossing wires cannot be avoided in {:?}", wires);
+            }
+        }
+    }
+}
@@ -0,0 +1,738 @@
+use graph::{Graph, GraphBase, Neighbors, UnderlyingGraph};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// The result of a planarity test.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Planarity<T: Hash + Copy + Eq + Ord> {
+    /// The graph is planar; here is a drawing without crossings.
+    Planar(Embedding<T>),
+    /// The graph is not planar; here is a Kuratowski subgraph, i.e. a
+    /// subdivision of K5 or K3,3, as a witness.
+    NonPlanar(Graph<T>),
+}
+
+/// A combinatorial embedding of a planar graph in the plane; i.e. the
+/// clockwise order of the neighbours around each node (a rotation system).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Embedding<T: Hash + Copy + Eq + Ord> {
+    rotations: HashMap<T, Vec<T>>,
+}
+
+impl<T: Hash + Copy + Eq + Ord> Embedding<T> {
+    /// Returns the neighbours of `v` in clockwise order; empty if `v` is not
+    /// in the graph.
+    pub fn rotation(&self, v: &T) -> &[T] {
+        match self.rotations.get(v) {
+            Some(r) => r,
+            None => &[],
+        }
+    }
+
+    /// Returns the faces, each as the cycle of nodes on its boundary.
+    ///
+    /// A face is traced by walking along an edge and turning at each node to
+    /// the next edge counterclockwise, so that the face is on the right hand
+    /// side. Every edge is walked once in each direction, so that a bridge
+    /// appears twice on the boundary of its face. Isolated nodes are on no face.
+    /// For a connected graph with n nodes and m edges there are m - n + 2
+    /// faces (Euler's formula).
+    pub fn faces(&self) -> Vec<Vec<T>> {
+        let mut nodes: Vec<T> = self.rotations.keys().copied().collect();
+        nodes.sort();
+        let mut visited: HashSet<(T, T)> = HashSet::new();
+        let mut faces = vec![];
+        for v in &nodes {
+            for w in &self.rotations[v] {
+                if visited.contains(&(*v, *w)) {
+                    continue;
+                }
+                let mut face = vec![];
+                let (mut a, mut b) = (*v, *w);
+                while visited.insert((a, b)) {
+                    face.push(a);
+                    // the neighbour before a around b in clockwise order
+                    let around = &self.rotations[&b];
+                    let i = around.iter().position(|x| *x == a).unwrap();
+                    let next = around[(i + around.len() - 1) % around.len()];
+                    a = b;
+                    b = next;
+                }
+                faces.push(face);
+            }
+        }
+        faces
+    }
+}
+
+/// Checks if a graph can be drawn in the plane without crossing edges; if so,
+/// returns such a drawing as an embedding, otherwise returns a Kuratowski
+/// subgraph (Kuratowski's theorem says that every non-planar graph has one).
+///
+/// The test is the left-right planarity test of de Fraysseix and Rosenstiehl
+/// as described by Brandes, which runs in linear time. The Kuratowski subgraph
+/// is found by deleting every edge whose deletion keeps the graph non-planar,
+/// which takes a test per edge. Arc directions of digraphs are ignored, and
+/// parallel edges and self-loops are dropped, for they never spoil planarity.
+pub fn check_planarity<T, G>(g: &G) -> Planarity<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let g = UnderlyingGraph::new(g);
+    let mut values = g.node_values().into_owned();
+    values.sort();
+    let mut adjs: Vec<Vec<usize>> = values
+        .iter()
+        .map(|v| {
+            let mut list: Vec<usize> = g
+                .neighbors(v)
+                .iter()
+                .filter(|w| *w != v)
+                .map(|w| values.binary_search(w).unwrap())
+                .collect();
+            list.sort_unstable();
+            list
+        })
+        .collect();
+    if let Some(rotations) = LrPlanarity::new(&adjs).run() {
+        let rotations = rotations
+            .into_iter()
+            .enumerate()
+            .map(|(i, r)| (values[i], r.into_iter().map(|j| values[j]).collect()))
+            .collect();
+        return Planarity::Planar(Embedding { rotations });
+    }
+
+    // keep only the edges without which the graph would be planar
+    let mut edges = vec![];
+    for v in 0..adjs.len() {
+        for w in adjs[v].clone() {
+            if w < v {
+                continue;
+            }
+            adjs[v].retain(|x| *x != w);
+            adjs[w].retain(|x| *x != v);
+            if LrPlanarity::new(&adjs).run().is_some() {
+                adjs[v].push(w);
+                adjs[w].push(v);
+                edges.push((values[v], values[w]));
+            }
+        }
+    }
+    let mut witness = Graph::new();
+    let mut nodes: Vec<T> = edges.iter().flat_map(|(v, w)| vec![*v, *w]).collect();
+    nodes.sort();
+    nodes.dedup();
+    for v in nodes {
+        witness.add_node(v);
+    }
+    for (v, w) in edges {
+        witness.add_edge(v, w);
+    }
+    Planarity::NonPlanar(witness)
+}
+
+/// Checks if a graph can be drawn in the plane without crossing edges. See
+/// `check_planarity()`.
+pub fn is_planar<T, G>(g: &G) -> bool
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    match check_planarity(g) {
+        Planarity::Planar(_) => true,
+        Planarity::NonPlanar(_) => false,
+    }
+}
+
+// an edge oriented by the depth-first search, between node indices
+type Edge = (usize, usize);
+
+// a set of return edges given by its lowest and highest edges, which are
+// linked by `refs`
+#[derive(Debug, Clone, Copy, Default)]
+struct Interval {
+    low: Option<Edge>,
+    high: Option<Edge>,
+}
+
+impl Interval {
+    fn is_empty(&self) -> bool {
+        self.low.is_none() && self.high.is_none()
+    }
+}
+
+// return edges that must be on the same side (within an interval) or on
+// different sides (between the intervals); `id` tells pairs apart on the stack
+#[derive(Debug, Clone, Copy)]
+struct ConflictPair {
+    id: usize,
+    left: Interval,
+    right: Interval,
+}
+
+impl ConflictPair {
+    fn swap(&mut self) {
+        std::mem::swap(&mut self.left, &mut self.right);
+    }
+}
+
+// the state of the left-right planarity test, on node indices
+struct LrPlanarity<'a> {
+    adjs: &'a [Vec<usize>],
+    roots: Vec<usize>,
+    height: Vec<Option<usize>>,
+    parent_edge: Vec<Option<Edge>>,
+    // the depth-first search orientation; out-going edges in the order found
+    oriented: HashSet<Edge>,
+    out_edges: Vec<Vec<usize>>,
+    ordered_adjs: Vec<Vec<usize>>,
+    lowpt: HashMap<Edge, usize>,
+    lowpt2: HashMap<Edge, usize>,
+    nesting_depth: HashMap<Edge, i64>,
+    refs: HashMap<Edge, Edge>,
+    side: HashMap<Edge, i64>,
+    stack: Vec<ConflictPair>,
+    stack_bottom: HashMap<Edge, Option<usize>>,
+    lowpt_edge: HashMap<Edge, Edge>,
+    next_id: usize,
+}
+
+impl<'a> LrPlanarity<'a> {
+    fn new(adjs: &'a [Vec<usize>]) -> Self {
+        let n = adjs.len();
+        LrPlanarity {
+            adjs,
+            roots: vec![],
+            height: vec![None; n],
+            parent_edge: vec![None; n],
+            oriented: HashSet::new(),
+            out_edges: vec![vec![]; n],
+            ordered_adjs: vec![],
+            lowpt: HashMap::new(),
+            lowpt2: HashMap::new(),
+            nesting_depth: HashMap::new(),
+            refs: HashMap::new(),
+            side: HashMap::new(),
+            stack: vec![],
+            stack_bottom: HashMap::new(),
+            lowpt_edge: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    // returns the clockwise rotation of each node if the graph is planar
+    fn run(mut self) -> Option<Vec<Vec<usize>>> {
+        let n = self.adjs.len();
+        let m = self.adjs.iter().map(|a| a.len()).sum::<usize>() / 2;
+        if n > 2 && m > 3 * n - 6 {
+            return None;
+        }
+        for v in 0..n {
+            if self.height[v].is_none() {
+                self.height[v] = Some(0);
+                self.roots.push(v);
+                self.orient(v);
+            }
+        }
+        self.order_adjacents();
+        for v in self.roots.clone() {
+            if !self.test(v) {
+                return None;
+            }
+        }
+        let edges: Vec<Edge> = self.oriented.iter().copied().collect();
+        for e in edges {
+            let sign = self.sign(e);
+            *self.nesting_depth.get_mut(&e).unwrap() *= sign;
+        }
+        self.order_adjacents();
+        Some(self.embed())
+    }
+
+    fn order_adjacents(&mut self) {
+        let nesting_depth = &self.nesting_depth;
+        self.ordered_adjs = self
+            .out_edges
+            .iter()
+            .enumerate()
+            .map(|(v, list)| {
+                let mut list = list.clone();
+                list.sort_by_key(|w| nesting_depth[&(v, *w)]);
+                list
+            })
+            .collect();
+    }
+
+    // orients the edges by a depth-first search from `root` and computes the
+    // lowpoints (without recursion, so that long paths do not overflow the
+    // stack)
+    fn orient(&mut self, root: usize) {
+        // (node, position of the next adjacent node to visit)
+        let mut calls = vec![(root, 0)];
+        while let Some((v, pos)) = calls.pop() {
+            if pos == self.adjs[v].len() {
+                // all adjacent nodes are visited; back to the parent
+                if let Some(e) = self.parent_edge[v] {
+                    self.oriented_edge(e);
+                }
+                continue;
+            }
+            calls.push((v, pos + 1));
+            let w = self.adjs[v][pos];
+            if self.oriented.contains(&(v, w)) || self.oriented.contains(&(w, v)) {
+                continue;
+            }
+            let vw = (v, w);
+            let height_v = self.height[v].unwrap();
+            self.oriented.insert(vw);
+            self.out_edges[v].push(w);
+            self.lowpt.insert(vw, height_v);
+            self.lowpt2.insert(vw, height_v);
+            match self.height[w] {
+                None => {
+                    // tree edge
+                    self.parent_edge[w] = Some(vw);
+                    self.height[w] = Some(height_v + 1);
+                    calls.push((w, 0));
+                }
+                Some(height_w) => {
+                    // back edge
+                    self.lowpt.insert(vw, height_w);
+                    self.oriented_edge(vw);
+                }
+            }
+        }
+    }
+
+    // sets the nesting depth of the oriented edge vw, whose lowpoints are
+    // final, and passes them on to the parent edge of v
+    fn oriented_edge(&mut self, vw: Edge) {
+        let v = vw.0;
+        let height_v = self.height[v].unwrap();
+        // the nesting order; chordal edges come after the others
+        let mut depth = 2 * self.lowpt[&vw] as i64;
+        if self.lowpt2[&vw] < height_v {
+            depth += 1;
+        }
+        self.nesting_depth.insert(vw, depth);
+        if let Some(e) = self.parent_edge[v] {
+            let (low_vw, low2_vw) = (self.lowpt[&vw], self.lowpt2[&vw]);
+            let (low_e, low2_e) = (self.lowpt[&e], self.lowpt2[&e]);
+            if low_vw < low_e {
+                self.lowpt2.insert(e, low_e.min(low2_vw));
+                self.lowpt.insert(e, low_vw);
+            } else if low_vw > low_e {
+                self.lowpt2.insert(e, low2_e.min(low_vw));
+            } else {
+                self.lowpt2.insert(e, low2_e.min(low2_vw));
+            }
+        }
+    }
+
+    fn top_id(&self) -> Option<usize> {
+        self.stack.last().map(|p| p.id)
+    }
+
+    fn new_pair(&mut self, left: Interval, right: Interval) -> ConflictPair {
+        self.next_id += 1;
+        ConflictPair {
+            id: self.next_id,
+            left,
+            right,
+        }
+    }
+
+    fn conflicting(&self, interval: &Interval, e: Edge) -> bool {
+        match interval.high {
+            Some(high) if !interval.is_empty() => self.lowpt[&high] > self.lowpt[&e],
+            _ => false,
+        }
+    }
+
+    // links an edge to a reference edge; nothing is linked to a missing edge
+    fn set_ref(&mut self, e: Option<Edge>, r: Option<Edge>) {
+        if let Some(e) = e {
+            match r {
+                Some(r) => self.refs.insert(e, r),
+                None => self.refs.remove(&e),
+            };
+        }
+    }
+
+    fn lowest(&self, p: &ConflictPair) -> usize {
+        match (p.left.low, p.right.low) {
+            (None, Some(r)) => self.lowpt[&r],
+            (Some(l), None) => self.lowpt[&l],
+            (Some(l), Some(r)) => self.lowpt[&l].min(self.lowpt[&r]),
+            (None, None) => unreachable!("empty conflict pair"),
+        }
+    }
+
+    // the left-right test from `root` (without recursion); false if constraints
+    // cannot be satisfied
+    fn test(&mut self, root: usize) -> bool {
+        // (node, position of the next out-going edge to visit)
+        let mut calls = vec![(root, 0)];
+        while let Some((v, pos)) = calls.pop() {
+            if pos == self.ordered_adjs[v].len() {
+                // all out-going edges are visited; back to the parent
+                if let Some(e) = self.parent_edge[v] {
+                    // remove back edges returning to the parent
+                    self.remove_back_edges(e);
+                    if !self.integrate(e) {
+                        return false;
+                    }
+                }
+                continue;
+            }
+            calls.push((v, pos + 1));
+            let w = self.ordered_adjs[v][pos];
+            let ei = (v, w);
+            let bottom = self.top_id();
+            self.stack_bottom.insert(ei, bottom);
+            if Some(ei) == self.parent_edge[w] {
+                // tree edge
+                calls.push((w, 0));
+            } else {
+                // back edge
+                self.lowpt_edge.insert(ei, ei);
+                let right = Interval {
+                    low: Some(ei),
+                    high: Some(ei),
+                };
+                let pair = self.new_pair(Interval::default(), right);
+                self.stack.push(pair);
+                if !self.integrate(ei) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    // integrates the new return edges of the visited edge vw into the
+    // constraints of the parent edge of v
+    fn integrate(&mut self, vw: Edge) -> bool {
+        let (v, w) = vw;
+        if self.lowpt[&vw] < self.height[v].unwrap() {
+            let e = self.parent_edge[v].unwrap();
+            if w == self.ordered_adjs[v][0] {
+                let low = self.lowpt_edge[&vw];
+                self.lowpt_edge.insert(e, low);
+            } else if !self.add_constraints(vw, e) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn add_constraints(&mut self, ei: Edge, e: Edge) -> bool {
+        let mut p = self.new_pair(Interval::default(), Interval::default());
+        // merge return edges of ei into p.right
+        loop {
+            let mut q = self.stack.pop().unwrap();
+            if !q.left.is_empty() {
+                q.swap();
+            }
+            if !q.left.is_empty() {
+                return false;
+            }
+            let q_low = q.right.low.unwrap();
+            if self.lowpt[&q_low] > self.lowpt[&e] {
+                // merge intervals
+                if p.right.is_empty() {
+                    p.right = q.right;
+                } else {
+                    self.set_ref(p.right.low, q.right.high);
+                }
+                p.right.low = q.right.low;
+            } else {
+                // align
+                let low = self.lowpt_edge[&e];
+                self.refs.insert(q_low, low);
+            }
+            if self.top_id() == self.stack_bottom[&ei] {
+                break;
+            }
+        }
+        // merge conflicting return edges of the previous edges into p.left
+        while let Some(top) = self.stack.last() {
+            if !self.conflicting(&top.left, ei) && !self.conflicting(&top.right, ei) {
+                break;
+            }
+            let mut q = self.stack.pop().unwrap();
+            if self.conflicting(&q.right, ei) {
+                q.swap();
+            }
+            if self.conflicting(&q.right, ei) {
+                return false;
+            }
+            // merge the interval below lowpt(ei) into p.right
+            self.set_ref(p.right.low, q.right.high);
+            if q.right.low.is_some() {
+                p.right.low = q.right.low;
+            }
+            if p.left.is_empty() {
+                p.left = q.left;
+            } else {
+                self.set_ref(p.left.low, q.left.high);
+            }
+            p.left.low = q.left.low;
+        }
+        if !(p.left.is_empty() && p.right.is_empty()) {
+            self.stack.push(p);
+        }
+        true
+    }
+
+    fn remove_back_edges(&mut self, e: Edge) {
+        let u = e.0;
+        let height_u = self.height[u].unwrap();
+        // drop entire conflict pairs
+        while let Some(top) = self.stack.last() {
+            if self.lowest(top) != height_u {
+                break;
+            }
+            let p = self.stack.pop().unwrap();
+            if let Some(low) = p.left.low {
+                self.side.insert(low, -1);
+            }
+        }
+        // one more conflict pair to consider
+        if let Some(mut p) = self.stack.pop() {
+            // trim the left interval
+            while let Some(high) = p.left.high.filter(|h| h.1 == u) {
+                p.left.high = self.refs.get(&high).copied();
+            }
+            if let (None, Some(low)) = (p.left.high, p.left.low) {
+                // just emptied
+                self.set_ref(Some(low), p.right.low);
+                self.side.insert(low, -1);
+                p.left.low = None;
+            }
+            // trim the right interval
+            while let Some(high) = p.right.high.filter(|h| h.1 == u) {
+                p.right.high = self.refs.get(&high).copied();
+            }
+            if let (None, Some(low)) = (p.right.high, p.right.low) {
+                // just emptied
+                self.set_ref(Some(low), p.left.low);
+                self.side.insert(low, -1);
+                p.right.low = None;
+            }
+            self.stack.push(p);
+        }
+        // the side of e is the side of a highest return edge
+        if self.lowpt[&e] < height_u {
+            let top = *self.stack.last().unwrap();
+            let (hl, hr) = (top.left.high, top.right.high);
+            let highest = match (hl, hr) {
+                (Some(l), Some(r)) if self.lowpt[&l] > self.lowpt[&r] => Some(l),
+                (Some(l), None) => Some(l),
+                _ => hr,
+            };
+            self.set_ref(Some(e), highest);
+        }
+    }
+
+    // resolves the side of an edge relative to its reference edges
+    fn sign(&mut self, e: Edge) -> i64 {
+        // follow the references iteratively, then fix the sides from the end
+        let mut chain = vec![e];
+        while let Some(r) = self.refs.get(chain.last().unwrap()).copied() {
+            chain.push(r);
+        }
+        let mut sign = *self.side.get(chain.last().unwrap()).unwrap_or(&1);
+        for x in chain.iter().rev().skip(1) {
+            sign *= *self.side.get(x).unwrap_or(&1);
+            self.side.insert(*x, sign);
+            self.refs.remove(x);
+        }
+        sign
+    }
+
+    // builds the rotation system from the nesting order and the sides
+    fn embed(&mut self) -> Vec<Vec<usize>> {
+        let n = self.adjs.len();
+        let mut rotations: Vec<Vec<usize>> = self.ordered_adjs.clone();
+        let mut left_ref: Vec<usize> = (0..n).collect();
+        let mut right_ref: Vec<usize> = (0..n).collect();
+        for v in self.roots.clone() {
+            self.embed_from(v, &mut rotations, &mut left_ref, &mut right_ref);
+        }
+        rotations
+    }
+
+    // walks the depth-first tree from `root` (without recursion) and inserts
+    // every back edge next to the reference edges of its side
+    fn embed_from(
+        &self,
+        root: usize,
+        rotations: &mut [Vec<usize>],
+        left_ref: &mut [usize],
+        right_ref: &mut [usize],
+    ) {
+        // (node, position of the next out-going edge to visit)
+        let mut calls = vec![(root, 0)];
+        while let Some((v, pos)) = calls.pop() {
+            if pos == self.ordered_adjs[v].len() {
+                continue;
+            }
+            calls.push((v, pos + 1));
+            let w = self.ordered_adjs[v][pos];
+            let ei = (v, w);
+            if Some(ei) == self.parent_edge[w] {
+                // tree edge; the parent comes first around the child
+                rotations[w].insert(0, v);
+                left_ref[v] = w;
+                right_ref[v] = w;
+                calls.push((w, 0));
+            } else if *self.side.get(&ei).unwrap_or(&1) == 1 {
+                // back edge on the right; directly after right_ref[w]
+                let i = rotations[w]
+                    .iter()
+                    .position(|x| *x == right_ref[w])
+                    .unwrap();
+                rotations[w].insert(i + 1, v);
+            } else {
+                // back edge on the left; directly before left_ref[w]
+                let i = rotations[w].iter().position(|x| *x == left_ref[w]).unwrap();
+                rotations[w].insert(i, v);
+                left_ref[w] = v;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use P80::graph_converters::unlabeled;
+
+    fn assert_planar(s: &str) {
+        let g = unlabeled::from_string(s);
+        match check_planarity(&g) {
+            Planarity::Planar(embedding) => {
+                for v in g.get_node_values() {
+                    let mut rotation = embedding.rotation(&v).to_vec();
+                    rotation.sort();
+                    let mut adjacents = g.get_node(&v).unwrap().adjacents();
+                    adjacents.sort();
+                    assert_eq!(rotation, adjacents);
+                }
+                // Euler's formula for connected graphs
+                let n = g.size() as i64;
+                let m = g.get_edges().len() as i64;
+                assert_eq!(embedding.faces().len() as i64, m - n + 2, "{}", s);
+            }
+            Planarity::NonPlanar(_) => panic!("{} is planar", s),
+        }
+    }
+
+    fn assert_non_planar(s: &str) {
+        let g = unlabeled::from_string(s);
+        match check_planarity(&g) {
+            Planarity::Planar(_) => panic!("{} is not planar", s),
+            Planarity::NonPlanar(witness) => {
+                assert!(!is_planar(&witness));
+                for (v, w, _) in g.get_edges() {
+                    if witness.is_linked(v, w) {
+                        assert!(g.is_linked(v, w));
+                    }
+                }
+                // a subdivision of K5 or K3,3
+                let mut degrees: Vec<usize> = witness
+                    .get_node_values()
+                    .iter()
+                    .map(|v| witness.get_node(v).unwrap().degree())
+                    .filter(|d| *d != 2)
+                    .collect();
+                degrees.sort();
+                assert!(degrees == vec![4; 5] || degrees == vec![3; 6], "{}", s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_planar() {
+        // K4
+        assert_planar("[a-b, a-c, a-d, b-c, b-d, c-d]");
+        // a tree
+        assert_planar("[a-b, a-c, c-d, c-e]");
+        // the cube
+        assert_planar("[a-b, b-c, c-d, d-a, e-f, f-g, g-h, h-e, a-e, b-f, c-g, d-h]");
+        // the octahedron
+        assert_planar("[a-b, a-c, a-d, a-e, f-b, f-c, f-d, f-e, b-c, c-d, d-e, e-b]");
+        // the wheel with six spokes
+        assert_planar("[h-a, h-b, h-c, h-d, h-e, h-f, a-b, b-c, c-d, d-e, e-f, f-a]");
+        // K5 without an edge
+        assert_planar("[a-b, a-c, a-d, a-e, b-c, b-d, b-e, c-d, c-e]");
+        // K3,3 without an edge
+        assert_planar("[a-d, a-e, a-f, b-d, b-e, b-f, c-d, c-e]");
+        // a 3 x 4 grid with diagonals
+        assert_planar(
+            "[a-b, b-c, c-d, e-f, f-g, g-h, i-j, j-k, k-l, \
+             a-e, b-f, c-g, d-h, e-i, f-j, g-k, h-l, a-f, b-g, c-h, e-j, f-k, g-l]",
+        );
+        assert!(is_planar(&unlabeled::from_string("[a, b, c-d]")));
+    }
+
+    #[test]
+    fn test_long_path() {
+        // deep depth-first searches do not overflow the stack
+        let mut g = Graph::new();
+        for v in 0..50_000u32 {
+            g.add_node(v);
+        }
+        for v in 1..50_000u32 {
+            g.add_edge(v - 1, v);
+        }
+        assert!(is_planar(&g));
+        // closed into a cycle, which has an inner and an outer face
+        g.add_edge(49_999, 0);
+        match check_planarity(&g) {
+            Planarity::Planar(embedding) => assert_eq!(embedding.faces().len(), 2),
+            Planarity::NonPlanar(_) => panic!("not planar"),
+        }
+    }
+
+    #[test]
+    fn test_non_planar() {
+        // K5
+        assert_non_planar("[a-b, a-c, a-d, a-e, b-c, b-d, b-e, c-d, c-e, d-e]");
+        // K3,3
+        assert_non_planar("[a-d, a-e, a-f, b-d, b-e, b-f, c-d, c-e, c-f]");
+        // the Petersen graph
+        assert_non_planar(
+            "[a-b, b-c, c-d, d-e, e-a, a-f, b-g, c-h, d-i, e-j, f-h, h-j, j-g, g-i, i-f]",
+        );
+        // K3,3 with subdivided edges and a planar part attached
+        assert_non_planar("[a-x, x-d, a-e, a-f, b-d, b-e, b-y, y-f, c-d, c-e, c-f, f-g, g-h, h-f]");
+        // K6
+        assert_non_planar(
+            "[a-b, a-c, a-d, a-e, a-f, b-c, b-d, b-e, b-f, c-d, c-e, c-f, d-e, d-f, e-f]",
+        );
+    }
+
+    #[test]
+    fn test_faces() {
+        let g = unlabeled::from_string("[a-b, b-c, c-a, c-d]");
+        match check_planarity(&g) {
+            Planarity::Planar(embedding) => {
+                let mut faces = embedding.faces();
+                for face in faces.iter_mut() {
+                    face.sort();
+                }
+                faces.sort();
+                // the inner triangle, and the outer face with the bridge walked twice
+                assert_eq!(
+                    faces,
+                    vec![vec!['a', 'b', 'c'], vec!['a', 'b', 'c', 'c', 'd']]
+                );
+            }
+            Planarity::NonPlanar(_) => panic!("not planar"),
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use reachability::*;
+use P80::digraph_converters::unlabeled;
+
+pub fn main() {
+    let g = unlabeled::from_string("[a>b, b>c, a>c, c>d, d>c, e]");
+    let index = ReachabilityIndex::new(&g);
+    println!("a can reach d: {}", index.can_reach('a', 'd'));
+    println!("d can reach a: {}", index.can_reach('d', 'a'));
+    println!("reachable from b: {:?}", index.reachable_from('b'));
+
+    let mut arcs = unlabeled::to_term_form(&transitive_closure(&g)).1;
+    arcs.sort();
+    println!("transitive closure: {:?}", arcs);
+    let mut arcs = unlabeled::to_term_form(&transitive_reduction(&g)).1;
+    arcs.sort();
+    println!("transitive reduction: {:?}", arcs);
+}
@@ -0,0 +1,374 @@
+use graph::{Digraph, Neighbors};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Returns the strongly connected components of a digraph. Components are listed
+/// in reverse topological order (a component comes before the components that
+/// can reach it), and each component is sorted.
+pub fn strongly_connected_components<T, G>(g: &G) -> Vec<Vec<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let adj = Adjacency::new(g);
+    tarjan(&adj.outs)
+        .into_iter()
+        .map(|comp| {
+            let mut values: Vec<T> = comp.into_iter().map(|i| adj.values[i]).collect();
+            values.sort();
+            values
+        })
+        .collect()
+}
+
+/// Transitive closure; there is an arc from `a` to `b` if and only if `b` is reachable
+/// from `a` by a non-empty path. Nodes on cycles get self-loops.
+pub fn transitive_closure<T, G>(g: &G) -> Digraph<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let index = ReachabilityIndex::new(g);
+    let mut res = Digraph::new();
+    for v in &index.values {
+        res.add_node(*v);
+    }
+    for src in &index.values {
+        for dst in index.reachable_from(*src) {
+            if *src != dst || index.is_on_cycle(*src) {
+                res.add_arc(*src, dst);
+            }
+        }
+    }
+    res
+}
+
+/// Transitive reduction; the digraph with the fewest arcs that has the same
+/// reachability as the given digraph (Aho, Garey and Ullman).
+///
+/// For a DAG this is the unique subgraph without redundant arcs. In general, the
+/// nodes of each strongly connected component are linked by a single cycle (in
+/// sorted order) and the components are linked through their smallest nodes, so
+/// the result may contain arcs that are not in the given digraph.
+pub fn transitive_reduction<T, G>(g: &G) -> Digraph<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let index = ReachabilityIndex::new(g);
+    let mut res = Digraph::new();
+    for v in &index.values {
+        res.add_node(*v);
+    }
+
+    // each component becomes a cycle
+    for members in &index.members {
+        if members.len() > 1 {
+            for (i, v) in members.iter().enumerate() {
+                res.add_arc(*v, members[(i + 1) % members.len()]);
+            }
+        }
+    }
+
+    // an arc between components is redundant if its target is reachable
+    // from another successor of the source
+    for (c, succs) in index.dag.iter().enumerate() {
+        for d in succs {
+            let redundant = succs.iter().any(|e| e != d && index.reach[*e].contains(*d));
+            if !redundant {
+                res.add_arc(index.members[c][0], index.members[*d][0]);
+            }
+        }
+    }
+    res
+}
+
+/// Reachability index; after building the index in `O(n * (n + m) / 64)`, it answers
+/// whether there is a path from one node to another in constant time.
+///
+/// Strongly connected components are collapsed into single nodes, and the set of
+/// reachable components is kept as a bit set for each component.
+pub struct ReachabilityIndex<T: Hash + Copy + Eq + Ord> {
+    values: Vec<T>,
+    component: HashMap<T, usize>,
+    members: Vec<Vec<T>>,
+    // arcs between components without duplicates
+    dag: Vec<Vec<usize>>,
+    // components reachable from each component, including itself
+    reach: Vec<BitSet>,
+    // components that contain cycles (including self-loops)
+    cyclic: Vec<bool>,
+}
+
+impl<T: Hash + Copy + Eq + Ord> ReachabilityIndex<T> {
+    /// Builds the index for a digraph.
+    pub fn new<G: Neighbors<T>>(g: &G) -> Self {
+        let adj = Adjacency::new(g);
+        let n = adj.values.len();
+        let comps = tarjan(&adj.outs);
+
+        let mut comp_of = vec![0; n];
+        for (c, comp) in comps.iter().enumerate() {
+            for i in comp {
+                comp_of[*i] = c;
+            }
+        }
+        let mut dag = vec![vec![]; comps.len()];
+        let mut cyclic: Vec<bool> = comps.iter().map(|comp| comp.len() > 1).collect();
+        for (i, outs) in adj.outs.iter().enumerate() {
+            for j in outs {
+                let (c, d) = (comp_of[i], comp_of[*j]);
+                if c == d {
+                    cyclic[c] = cyclic[c] || i == *j;
+                } else if !dag[c].contains(&d) {
+                    dag[c].push(d);
+                }
+            }
+        }
+
+        // Tarjan's algorithm emits components in reverse topological order, so all
+        // successors of a component have been resolved before the component itself.
+        let mut reach: Vec<BitSet> = vec![];
+        for (c, succs) in dag.iter().enumerate() {
+            let mut bits = BitSet::new(comps.len());
+            bits.insert(c);
+            for d in succs {
+                bits.union_with(&reach[*d]);
+            }
+            reach.push(bits);
+        }
+
+        let members = comps
+            .iter()
+            .map(|comp| {
+                let mut values: Vec<T> = comp.iter().map(|i| adj.values[*i]).collect();
+                values.sort();
+                values
+            })
+            .collect();
+        let component = adj
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (*v, comp_of[i]))
+            .collect();
+        ReachabilityIndex {
+            values: adj.values,
+            component,
+            members,
+            dag,
+            reach,
+            cyclic,
+        }
+    }
+
+    /// Returns true if `dst` is reachable from `src`. Every node can reach itself.
+    pub fn can_reach(&self, src: T, dst: T) -> bool {
+        match (self.component.get(&src), self.component.get(&dst)) {
+            (Some(c), Some(d)) => self.reach[*c].contains(*d),
+            _ => false,
+        }
+    }
+
+    /// Returns all nodes reachable from `src` (including `src` itself) in sorted order.
+    pub fn reachable_from(&self, src: T) -> Vec<T> {
+        match self.component.get(&src) {
+            Some(c) => self
+                .values
+                .iter()
+                .filter(|v| self.reach[*c].contains(self.component[v]))
+                .copied()
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Returns true if there is a non-empty path from `v` to itself.
+    pub fn is_on_cycle(&self, v: T) -> bool {
+        match self.component.get(&v) {
+            Some(c) => self.cyclic[*c],
+            None => false,
+        }
+    }
+}
+
+/// Fixed size bit set
+#[derive(Debug, Clone)]
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(size: usize) -> Self {
+        BitSet {
+            words: vec![0; size / 64 + 1],
+        }
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    fn union_with(&mut self, other: &BitSet) {
+        for (w, o) in self.words.iter_mut().zip(other.words.iter()) {
+            *w |= o;
+        }
+    }
+}
+
+/// Index based adjacency lists; nodes are numbered by their sorted values.
+struct Adjacency<T> {
+    values: Vec<T>,
+    outs: Vec<Vec<usize>>,
+}
+
+impl<T: Hash + Copy + Eq + Ord> Adjacency<T> {
+    fn new<G: Neighbors<T>>(g: &G) -> Self {
+        let mut values = g.node_values().into_owned();
+        values.sort();
+        let index: HashMap<T, usize> = values.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+        let outs = values
+            .iter()
+            .map(|v| g.neighbors(v).iter().map(|w| index[w]).collect())
+            .collect();
+        Adjacency { values, outs }
+    }
+}
+
+/// Tarjan's strongly connected components algorithm (without recursion, so that
+/// long paths do not overflow the stack).
+fn tarjan(outs: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let n = outs.len();
+    let mut index = vec![None; n];
+    let mut lowlink = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = vec![];
+    let mut comps = vec![];
+    let mut counter = 0;
+
+    for root in 0..n {
+        if index[root].is_some() {
+            continue;
+        }
+        // (node, position of the next successor to visit)
+        let mut calls = vec![(root, 0)];
+        while let Some((v, pos)) = calls.pop() {
+            if pos == 0 {
+                index[v] = Some(counter);
+                lowlink[v] = counter;
+                counter += 1;
+                stack.push(v);
+                on_stack[v] = true;
+            }
+            if pos < outs[v].len() {
+                calls.push((v, pos + 1));
+                let w = outs[v][pos];
+                match index[w] {
+                    None => calls.push((w, 0)),
+                    Some(iw) if on_stack[w] => lowlink[v] = lowlink[v].min(iw),
+                    _ => (),
+                }
+                continue;
+            }
+            // all successors are visited
+            if Some(lowlink[v]) == index[v] {
+                let mut comp = vec![];
+                loop {
+                    let w = stack.pop().unwrap();
+                    on_stack[w] = false;
+                    comp.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                comps.push(comp);
+            }
+            if let Some((parent, _)) = calls.last() {
+                lowlink[*parent] = lowlink[*parent].min(lowlink[v]);
+            }
+        }
+    }
+    comps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use P80::digraph_converters::unlabeled;
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let g = unlabeled::from_string("[a>b, b>c, c>a, c>d, d>e, e>d, f]");
+        let mut comps = strongly_connected_components(&g);
+        assert_eq!(comps.len(), 3);
+        comps.sort();
+        assert_eq!(comps, vec![vec!['a', 'b', 'c'], vec!['d', 'e'], vec!['f']]);
+    }
+
+    #[test]
+    fn test_transitive_closure() {
+        let g = unlabeled::from_string("[a>b, b>c, d]");
+        assert_eq!(
+            unlabeled::to_adjacent_form(&transitive_closure(&g))
+                .into_iter()
+                .map(|(v, mut adjs)| {
+                    adjs.sort();
+                    (v, adjs)
+                })
+                .filter(|(v, _)| *v == 'a')
+                .collect::<Vec<_>>(),
+            vec![('a', vec!['b', 'c'])]
+        );
+
+        let g = unlabeled::from_string("[a>b, b>a, c]");
+        let closure = transitive_closure(&g);
+        let mut arcs = unlabeled::to_term_form(&closure).1;
+        arcs.sort();
+        assert_eq!(arcs, vec![('a', 'a'), ('a', 'b'), ('b', 'a'), ('b', 'b')]);
+    }
+
+    #[test]
+    fn test_transitive_reduction() {
+        let g = unlabeled::from_string("[a>b, b>c, a>c, a>d, d>c]");
+        let mut arcs = unlabeled::to_term_form(&transitive_reduction(&g)).1;
+        arcs.sort();
+        assert_eq!(arcs, vec![('a', 'b'), ('a', 'd'), ('b', 'c'), ('d', 'c')]);
+
+        let g = unlabeled::from_string("[a>b, b>a, a>c, b>c, c>d, b>d]");
+        let mut arcs = unlabeled::to_term_form(&transitive_reduction(&g)).1;
+        arcs.sort();
+        assert_eq!(arcs, vec![('a', 'b'), ('a', 'c'), ('b', 'a'), ('c', 'd')]);
+    }
+
+    #[test]
+    fn test_can_reach() {
+        let g = unlabeled::from_string("[a>b, b>c, c>b, d>a, e]");
+        let index = ReachabilityIndex::new(&g);
+        assert!(index.can_reach('a', 'c'));
+        assert!(index.can_reach('d', 'b'));
+        assert!(index.can_reach('c', 'b'));
+        assert!(index.can_reach('e', 'e'));
+        assert!(!index.can_reach('c', 'a'));
+        assert!(!index.can_reach('a', 'e'));
+        assert!(!index.can_reach('a', 'x'));
+        assert_eq!(index.reachable_from('a'), vec!['a', 'b', 'c']);
+        assert!(index.is_on_cycle('b'));
+        assert!(!index.is_on_cycle('a'));
+    }
+
+    #[test]
+    fn test_csr_graph() {
+        use graph::CsrGraph;
+        let g = CsrGraph::from_digraph(&unlabeled::from_string("[a>b, b>c, c>b, d>a, e]"));
+        let index = ReachabilityIndex::new(&g);
+        assert!(index.can_reach('d', 'c'));
+        assert!(!index.can_reach('c', 'a'));
+        let mut comps = strongly_connected_components(&g);
+        comps.sort();
+        assert_eq!(comps, vec![vec!['a'], vec!['b', 'c'], vec!['d'], vec!['e']]);
+    }
+}
@@ -0,0 +1,68 @@
+use search::*;
+
+type Board = [u8; 9];
+
+const GOAL: Board = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+
+// moves of the blank (0), each costs 1
+fn moves(b: &Board) -> Vec<(Board, u32)> {
+    let p = b.iter().position(|&x| x == 0).unwrap();
+    let mut targets = vec![];
+    if p >= 3 {
+        targets.push(p - 3);
+    }
+    if p < 6 {
+        targets.push(p + 3);
+    }
+    if p % 3 > 0 {
+        targets.push(p - 1);
+    }
+    if p % 3 < 2 {
+        targets.push(p + 1);
+    }
+    targets
+        .into_iter()
+        .map(|q| {
+            let mut next = *b;
+            next.swap(p, q);
+            (next, 1)
+        })
+        .collect()
+}
+
+// sum of the distances of the tiles from their goal positions
+fn manhattan(b: &Board) -> u32 {
+    let dist = |a: usize, b: usize| (a.max(b) - a.min(b)) as u32;
+    b.iter()
+        .enumerate()
+        .filter(|(_, &x)| x != 0)
+        .map(|(p, &x)| {
+            let q = (x - 1) as usize;
+            dist(p / 3, q / 3) + dist(p % 3, q % 3)
+        })
+        .sum()
+}
+
+pub fn main() {
+    let start: Board = [8, 6, 7, 2, 5, 4, 3, 0, 1];
+    let is_goal = |b: &Board| *b == GOAL;
+
+    let steps = |b: &Board| moves(b).into_iter().map(|(n, _)| n);
+    let res = bfs(start, steps, is_goal).unwrap();
+    println!("BFS: {} moves, {} states expanded", res.cost, res.expanded);
+    let res = uniform_cost(start, moves, is_goal).unwrap();
+    println!("UCS: {} moves, {} states expanded", res.cost, res.expanded);
+    let res = astar(start, moves, manhattan, is_goal).unwrap();
+    println!("A*: {} moves, {} states expanded", res.cost, res.expanded);
+    let res = idastar(start, moves, manhattan, is_goal).unwrap();
+    println!("IDA*: {} moves, {} states expanded", res.cost, res.expanded);
+    let res = bidirectional(start, GOAL, moves, moves).unwrap();
+    println!(
+        "bidirectional: {} moves, {} states expanded",
+        res.cost, res.expanded
+    );
+    for b in res.path.iter().take(3) {
+        println!("{:?}", b);
+    }
+    println!("...");
+}
@@ -0,0 +1,517 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Path costs; non-negative integers are expected (floats are not `Ord`).
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<C: Copy + Ord + Default + Add<Output = C>> Cost for C {}
+
+/// A path found by a search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solution<S, C> {
+    /// States from the start to the goal (both included).
+    pub path: Vec<S>,
+    /// The sum of the step costs along the path (the number of steps for
+    /// unweighted searches).
+    pub cost: C,
+    /// The number of states whose successors were generated.
+    pub expanded: usize,
+}
+
+/// States reached so far with their parents and costs, so that paths can be
+/// reconstructed. Each state is stored once; parents are referred by index.
+struct SearchTree<S, C> {
+    states: Vec<S>,
+    parents: Vec<Option<usize>>,
+    costs: Vec<C>,
+    index: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> SearchTree<S, C> {
+    fn new() -> Self {
+        SearchTree {
+            states: vec![],
+            parents: vec![],
+            costs: vec![],
+            index: HashMap::new(),
+        }
+    }
+
+    fn get(&self, s: &S) -> Option<usize> {
+        self.index.get(s).copied()
+    }
+
+    fn insert(&mut self, s: S, parent: Option<usize>, cost: C) -> usize {
+        let i = self.states.len();
+        self.index.insert(s.clone(), i);
+        self.states.push(s);
+        self.parents.push(parent);
+        self.costs.push(cost);
+        i
+    }
+
+    // Records `s` reached by `cost` from `parent`; returns the index of `s` if
+    // it is new or the cost is improved.
+    fn relax(&mut self, s: S, parent: usize, cost: C) -> Option<usize> {
+        match self.get(&s) {
+            Some(i) if self.costs[i] <= cost => None,
+            Some(i) => {
+                self.parents[i] = Some(parent);
+                self.costs[i] = cost;
+                Some(i)
+            }
+            None => Some(self.insert(s, Some(parent), cost)),
+        }
+    }
+
+    // states from the root to `i`
+    fn path(&self, mut i: usize) -> Vec<S> {
+        let mut path = vec![self.states[i].clone()];
+        while let Some(p) = self.parents[i] {
+            path.push(self.states[p].clone());
+            i = p;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Breadth-first search; finds a path with the fewest steps.
+pub fn bfs<S, F, I, G>(start: S, mut successors: F, mut is_goal: G) -> Option<Solution<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut tree = SearchTree::new();
+    let mut queue = VecDeque::new();
+    queue.push_back(tree.insert(start, None, 0));
+    let mut expanded = 0;
+    while let Some(i) = queue.pop_front() {
+        if is_goal(&tree.states[i]) {
+            return Some(Solution {
+                path: tree.path(i),
+                cost: tree.costs[i],
+                expanded,
+            });
+        }
+        expanded += 1;
+        let depth = tree.costs[i] + 1;
+        for s in successors(&tree.states[i].clone()) {
+            if tree.get(&s).is_none() {
+                queue.push_back(tree.insert(s, Some(i), depth));
+            }
+        }
+    }
+    None
+}
+
+/// Depth-first search; finds a path, not necessarily the shortest one.
+/// Successors are explored in the order they are given.
+pub fn dfs<S, F, I, G>(start: S, mut successors: F, mut is_goal: G) -> Option<Solution<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut tree = SearchTree::new();
+    // states to visit with their parents; a state is added to the tree when visited
+    let mut stack = vec![(start, None)];
+    let mut expanded = 0;
+    while let Some((s, parent)) = stack.pop() {
+        if tree.get(&s).is_some() {
+            continue;
+        }
+        let depth = parent.map_or(0, |p: usize| tree.costs[p] + 1);
+        let i = tree.insert(s, parent, depth);
+        if is_goal(&tree.states[i]) {
+            return Some(Solution {
+                path: tree.path(i),
+                cost: depth,
+                expanded,
+            });
+        }
+        expanded += 1;
+        let mut next: Vec<S> = successors(&tree.states[i].clone())
+            .into_iter()
+            .filter(|t| tree.get(t).is_none())
+            .collect();
+        next.reverse();
+        stack.extend(next.into_iter().map(|t| (t, Some(i))));
+    }
+    None
+}
+
+/// Uniform-cost search (Dijkstra's algorithm); finds a cheapest path.
+/// `successors` returns the next states with the costs of the steps.
+pub fn uniform_cost<S, C, F, I, G>(start: S, successors: F, is_goal: G) -> Option<Solution<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// A* search; finds a cheapest path if `heuristic` is admissible, i.e. it never
+/// overestimates the cost from a state to the nearest goal.
+pub fn astar<S, C, F, I, H, G>(
+    start: S,
+    mut successors: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> Option<Solution<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let mut tree = SearchTree::new();
+    let mut heap = BinaryHeap::new();
+    let h = heuristic(&start);
+    let zero = C::default();
+    heap.push(Reverse((h, zero, tree.insert(start, None, zero))));
+    let mut expanded = 0;
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        if cost > tree.costs[i] {
+            continue; // a cheaper path to this state was found later
+        }
+        if is_goal(&tree.states[i]) {
+            return Some(Solution {
+                path: tree.path(i),
+                cost,
+                expanded,
+            });
+        }
+        expanded += 1;
+        for (s, step) in successors(&tree.states[i].clone()) {
+            if let Some(j) = tree.relax(s, i, cost + step) {
+                let h = heuristic(&tree.states[j]);
+                heap.push(Reverse((tree.costs[j] + h, tree.costs[j], j)));
+            }
+        }
+    }
+    None
+}
+
+/// Iterative deepening A* (IDA*); finds a cheapest path with an admissible
+/// `heuristic`, using memory only for the current path. States on the current
+/// path are not revisited.
+pub fn idastar<S, C, F, I, H, G>(
+    start: S,
+    successors: F,
+    mut heuristic: H,
+    is_goal: G,
+) -> Option<Solution<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let mut bound = heuristic(&start);
+    let mut search = IdaSearch {
+        path: vec![start],
+        successors,
+        heuristic,
+        is_goal,
+        expanded: 0,
+    };
+    loop {
+        match search.search(C::default(), bound) {
+            Bound::Found(cost) => {
+                return Some(Solution {
+                    path: search.path,
+                    cost,
+                    expanded: search.expanded,
+                })
+            }
+            Bound::Next(Some(next)) => bound = next,
+            Bound::Next(None) => return None,
+        }
+    }
+}
+
+enum Bound<C> {
+    /// reached a goal with the cost
+    Found(C),
+    /// the smallest f-value that exceeded the bound, if any
+    Next(Option<C>),
+}
+
+struct IdaSearch<S, F, H, G> {
+    path: Vec<S>,
+    successors: F,
+    heuristic: H,
+    is_goal: G,
+    expanded: usize,
+}
+
+impl<S, C, F, I, H, G> IdaSearch<S, F, H, G>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    // depth-first search below the last state of the path, which is reached by `cost`
+    fn search(&mut self, cost: C, bound: C) -> Bound<C> {
+        let s = self.path.last().unwrap().clone();
+        let f = cost + (self.heuristic)(&s);
+        if f > bound {
+            return Bound::Next(Some(f));
+        }
+        if (self.is_goal)(&s) {
+            return Bound::Found(cost);
+        }
+        self.expanded += 1;
+        let mut min = None;
+        for (t, step) in (self.successors)(&s) {
+            if self.path.contains(&t) {
+                continue;
+            }
+            self.path.push(t);
+            match self.search(cost + step, bound) {
+                Bound::Found(c) => return Bound::Found(c),
+                Bound::Next(Some(next)) => min = Some(min.map_or(next, |m: C| m.min(next))),
+                Bound::Next(_) => {}
+            }
+            self.path.pop();
+        }
+        Bound::Next(min)
+    }
+}
+
+/// Bidirectional uniform-cost search from `start` and from `goal`; finds a
+/// cheapest path. `predecessors` returns the states from which a state is
+/// reached with the costs of the steps (the same as `successors` for
+/// undirected graphs).
+pub fn bidirectional<S, C, F, I, P, J>(
+    start: S,
+    goal: S,
+    mut successors: F,
+    mut predecessors: P,
+) -> Option<Solution<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    P: FnMut(&S) -> J,
+    J: IntoIterator<Item = (S, C)>,
+{
+    let zero = C::default();
+    if start == goal {
+        return Some(Solution {
+            path: vec![start],
+            cost: zero,
+            expanded: 0,
+        });
+    }
+    // forward and backward searches
+    let mut trees = [SearchTree::new(), SearchTree::new()];
+    let mut heaps = [BinaryHeap::new(), BinaryHeap::new()];
+    heaps[0].push(Reverse((zero, trees[0].insert(start, None, zero))));
+    heaps[1].push(Reverse((zero, trees[1].insert(goal, None, zero))));
+    // the cheapest path found so far and the state where the searches met
+    let mut best: Option<(C, S)> = None;
+    let mut expanded = 0;
+    loop {
+        let tops: Vec<Option<C>> = heaps
+            .iter()
+            .map(|h| h.peek().map(|Reverse((c, _))| *c))
+            .collect();
+        let dir = match (tops[0], tops[1]) {
+            (Some(f), Some(b)) => {
+                // no shorter path can be found once the frontiers together exceed it
+                if let Some((cost, _)) = &best {
+                    if f + b >= *cost {
+                        break;
+                    }
+                }
+                if f <= b {
+                    0
+                } else {
+                    1
+                }
+            }
+            _ => break,
+        };
+        let Reverse((cost, i)) = heaps[dir].pop().unwrap();
+        if cost > trees[dir].costs[i] {
+            continue;
+        }
+        expanded += 1;
+        let s = trees[dir].states[i].clone();
+        let next: Vec<(S, C)> = if dir == 0 {
+            successors(&s).into_iter().collect()
+        } else {
+            predecessors(&s).into_iter().collect()
+        };
+        for (t, step) in next {
+            if let Some(j) = trees[dir].relax(t, i, cost + step) {
+                let c = trees[dir].costs[j];
+                heaps[dir].push(Reverse((c, j)));
+                let t = &trees[dir].states[j];
+                if let Some(k) = trees[1 - dir].get(t) {
+                    let total = c + trees[1 - dir].costs[k];
+                    let better = match &best {
+                        Some((b, _)) => total < *b,
+                        None => true,
+                    };
+                    if better {
+                        best = Some((total, t.clone()));
+                    }
+                }
+            }
+        }
+    }
+    best.map(|(cost, meet)| {
+        let mut path = trees[0].path(trees[0].get(&meet).unwrap());
+        let mut rest = trees[1].path(trees[1].get(&meet).unwrap());
+        rest.reverse();
+        path.extend(rest.into_iter().skip(1));
+        Solution {
+            path,
+            cost,
+            expanded,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Board = [u8; 9];
+
+    // moves of the blank (0) in the 8-puzzle
+    fn moves(b: &Board) -> Vec<(Board, u32)> {
+        let p = b.iter().position(|&x| x == 0).unwrap();
+        let (r, c) = (p / 3, p % 3);
+        let mut res = vec![];
+        let mut swap = |q: usize| {
+            let mut next = *b;
+            next.swap(p, q);
+            res.push((next, 1));
+        };
+        if r > 0 {
+            swap(p - 3);
+        }
+        if r < 2 {
+            swap(p + 3);
+        }
+        if c > 0 {
+            swap(p - 1);
+        }
+        if c < 2 {
+            swap(p + 1);
+        }
+        res
+    }
+
+    fn manhattan(b: &Board) -> u32 {
+        b.iter()
+            .enumerate()
+            .filter(|(_, &x)| x != 0)
+            .map(|(p, &x)| {
+                let q = (x - 1) as usize;
+                let dist = |a: usize, b: usize| (a.max(b) - a.min(b)) as u32;
+                dist(p / 3, q / 3) + dist(p % 3, q % 3)
+            })
+            .sum()
+    }
+
+    const GOAL: Board = [1, 2, 3, 4, 5, 6, 7, 8, 0];
+
+    #[test]
+    fn test_bfs_and_dfs() {
+        // two jugs of 3 and 5 liters; measure 4 liters
+        let pour = |&(a, b): &(u8, u8)| {
+            vec![
+                (3, b),
+                (a, 5),
+                (0, b),
+                (a, 0),
+                (a - a.min(5 - b), b + a.min(5 - b)),
+                (a + b.min(3 - a), b - b.min(3 - a)),
+            ]
+        };
+        let res = bfs((0, 0), pour, |&(_, b)| b == 4).unwrap();
+        assert_eq!(res.cost, 6);
+        assert_eq!(res.path.len(), 7);
+        assert_eq!(res.path[0], (0, 0));
+
+        let res = dfs((0, 0), pour, |&(_, b)| b == 4).unwrap();
+        assert_eq!(res.path.len(), res.cost + 1);
+        assert_eq!(res.path.last().unwrap().1, 4);
+        for w in res.path.windows(2) {
+            assert!(pour(&w[0]).contains(&w[1]));
+        }
+
+        assert_eq!(bfs((0, 0), pour, |&(a, _)| a == 4), None);
+        assert_eq!(dfs((0, 0), pour, |&(a, _)| a == 4), None);
+    }
+
+    #[test]
+    fn test_informed_searches() {
+        let start: Board = [8, 1, 3, 4, 0, 2, 7, 6, 5];
+        let steps = |b: &Board| moves(b).into_iter().map(|(n, _)| n);
+        let shortest = bfs(start, steps, |b| *b == GOAL).unwrap();
+
+        let ucs = uniform_cost(start, moves, |b| *b == GOAL).unwrap();
+        let a = astar(start, moves, manhattan, |b| *b == GOAL).unwrap();
+        let ida = idastar(start, moves, manhattan, |b| *b == GOAL).unwrap();
+        for res in &[&ucs, &a, &ida] {
+            assert_eq!(res.cost as usize, shortest.cost);
+            assert_eq!(res.path.len(), shortest.path.len());
+            assert_eq!(res.path[0], start);
+            assert_eq!(*res.path.last().unwrap(), GOAL);
+        }
+        // the heuristic saves a lot of work
+        assert!(a.expanded < ucs.expanded);
+    }
+
+    #[test]
+    fn test_bidirectional() {
+        use graph::EdgeWeights;
+        use P80::graph_converters::labeled;
+        let g = labeled::from_string(
+            "[a-b/5, a-d/3, b-c/2, b-e/4, c-e/6, d-e/7, d-f/4, d-g/3, e-h/5, f-g/4, g-h/1]",
+        );
+        let next = |v: &char| -> Vec<(char, i32)> {
+            g.neighbors_with_weight(v)
+                .into_iter()
+                .map(|(w, l)| (w, l.unwrap()))
+                .collect()
+        };
+        let res = bidirectional('a', 'h', next, next).unwrap();
+        assert_eq!(res.cost, 7);
+        assert_eq!(res.path, vec!['a', 'd', 'g', 'h']);
+        let res = bidirectional('c', 'f', next, next).unwrap();
+        let expected = uniform_cost('c', next, |v| *v == 'f').unwrap();
+        assert_eq!(res.cost, expected.cost);
+
+        let res = bidirectional('a', 'a', next, next).unwrap();
+        assert_eq!((res.path, res.cost), (vec!['a'], 0));
+
+        // 0 -> 1 -> 2 -> 3, no way back
+        let succ = |v: &u32| if *v < 3 { vec![(v + 1, 1)] } else { vec![] };
+        let pred = |v: &u32| if *v > 0 { vec![(v - 1, 1)] } else { vec![] };
+        assert_eq!(bidirectional(0, 3, succ, pred).unwrap().cost, 3);
+        assert_eq!(bidirectional(3, 0, succ, pred), None);
+    }
+}
@@ -0,0 +1,31 @@
+use bintree::Tree;
+use graph::Graph;
+use trees::*;
+use P70::{str_to_tree, tree_to_str};
+use P80::graph_converters::unlabeled;
+
+pub fn main() {
+    // a multiway tree becomes a graph that can be rooted at any node
+    let tree = str_to_tree("afg^^c^bd^e^^^");
+    let g = mtree_to_graph(&tree).unwrap();
+    let mut edges = unlabeled::to_term_form(&g).1;
+    edges.sort();
+    println!("edges: {:?}", edges);
+    for root in "abg".chars() {
+        let tree = graph_to_mtree(&g, root).unwrap();
+        println!("rooted at {}: {}", root, tree_to_str(&tree));
+    }
+
+    // a binary tree becomes a digraph with arcs to the children
+    let tree = Tree::node(5, Tree::leaf(3), Tree::node(8, Tree::end(), Tree::leaf(9)));
+    let g = bintree_to_digraph(&tree).unwrap();
+    println!("arcs: {:?}", g.get_arcs());
+
+    // spanning trees of a general graph
+    let g: Graph<char> = unlabeled::from_string("[a-b, a-c, a-d, b-c, c-d, d-e, b-e]");
+    let bfs = bfs_tree(&g, 'a').unwrap();
+    let dfs = dfs_tree(&g, 'a').unwrap();
+    println!("bfs: {}", tree_to_str(&graph_to_mtree(&bfs, 'a').unwrap()));
+    println!("dfs: {}", tree_to_str(&graph_to_mtree(&dfs, 'a').unwrap()));
+    println!("{}", graph_to_mtree(&g, 'a').unwrap_err());
+}
@@ -0,0 +1,338 @@
+use bintree::Tree;
+use graph::{Digraph, Directed, Graph, Neighbors};
+use mtree::MTree;
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::hash::Hash;
+use P83::is_tree;
+
+/// Errors on converting between trees and graphs
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeError<T> {
+    /// Graph nodes are identified by their values, so a tree cannot have the
+    /// same value twice.
+    DuplicateValue(T),
+    /// The root is not a node of the graph.
+    UnknownRoot(T),
+    /// The graph is not a tree (checked with P83's `is_tree()`).
+    NotATree,
+    /// Not every node of the (directed) tree can be reached from the root.
+    NotARoot(T),
+}
+
+impl<T: fmt::Debug> fmt::Display for TreeError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TreeError::DuplicateValue(v) => write!(f, "duplicate value: {:?}", v),
+            TreeError::UnknownRoot(v) => write!(f, "unknown root: {:?}", v),
+            TreeError::NotATree => write!(f, "the graph is not a tree"),
+            TreeError::NotARoot(v) => write!(f, "not the root of the tree: {:?}", v),
+        }
+    }
+}
+
+impl<T: fmt::Debug> std::error::Error for TreeError<T> {}
+
+/// Converts a multiway tree into a digraph with arcs from every node to its
+/// children, so that the root is the only node without incoming arcs.
+pub fn mtree_to_digraph(tree: &MTree) -> Result<Digraph<char>, TreeError<char>> {
+    let (nodes, edges) = check_values(mtree_edges(tree))?;
+    let mut g = Digraph::new();
+    for v in nodes {
+        g.add_node(v);
+    }
+    for (parent, child) in edges {
+        g.add_arc(parent, child);
+    }
+    Ok(g)
+}
+
+/// Converts a multiway tree into an (undirected) graph with an edge between
+/// every node and each of its children.
+pub fn mtree_to_graph(tree: &MTree) -> Result<Graph<char>, TreeError<char>> {
+    let (nodes, edges) = check_values(mtree_edges(tree))?;
+    Ok(to_graph(nodes, edges))
+}
+
+/// Converts a binary tree into a digraph with arcs from every node to its
+/// children (left first). Which child is left or right is not kept.
+pub fn bintree_to_digraph<T>(tree: &Tree<T>) -> Result<Digraph<T>, TreeError<T>>
+where
+    T: fmt::Display + Hash + Copy + Eq + Ord,
+{
+    let (nodes, edges) = check_values(bintree_edges(tree))?;
+    let mut g = Digraph::new();
+    for v in nodes {
+        g.add_node(v);
+    }
+    for (parent, child) in edges {
+        g.add_arc(parent, child);
+    }
+    Ok(g)
+}
+
+/// Converts a binary tree into an (undirected) graph with an edge between every
+/// node and each of its children.
+pub fn bintree_to_graph<T>(tree: &Tree<T>) -> Result<Graph<T>, TreeError<T>>
+where
+    T: fmt::Display + Hash + Copy + Eq + Ord,
+{
+    let (nodes, edges) = check_values(bintree_edges(tree))?;
+    Ok(to_graph(nodes, edges))
+}
+
+/// Converts a tree-shaped graph into a multiway tree rooted at `root`; the
+/// children of every node are sorted by their values. Any node of an undirected
+/// tree can be the root; a directed tree must be an out-tree from `root`.
+pub fn graph_to_mtree<G>(graph: &G, root: char) -> Result<MTree, TreeError<char>>
+where
+    G: Neighbors<char> + Directed,
+{
+    fn build<G: Neighbors<char> + Directed>(
+        graph: &G,
+        v: char,
+        parent: Option<char>,
+        count: &mut usize,
+    ) -> MTree {
+        *count += 1;
+        let mut children = graph.neighbors(&v).into_owned();
+        // the parent is adjacent to its children only in undirected trees
+        if !graph.is_directed() {
+            children.retain(|w| Some(*w) != parent);
+        }
+        children.sort();
+        let children = children
+            .into_iter()
+            .map(|w| build(graph, w, Some(v), count))
+            .collect();
+        MTree::node(v, children)
+    }
+
+    if !graph.contains(&root) {
+        return Err(TreeError::UnknownRoot(root));
+    }
+    if !is_tree(graph) {
+        return Err(TreeError::NotATree);
+    }
+    let mut count = 0;
+    let tree = build(graph, root, None, &mut count);
+    if count < graph.node_count() {
+        return Err(TreeError::NotARoot(root));
+    }
+    Ok(tree)
+}
+
+/// Returns the breadth-first spanning tree of the nodes reachable from `root`,
+/// as a digraph with arcs from every node to its children. Adjacent nodes are
+/// visited in the order of their values.
+pub fn bfs_tree<T, G>(graph: &G, root: T) -> Result<Digraph<T>, TreeError<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    if !graph.contains(&root) {
+        return Err(TreeError::UnknownRoot(root));
+    }
+    let mut visited = HashSet::new();
+    visited.insert(root);
+    let mut nodes = vec![root];
+    let mut edges = vec![];
+    let mut queue = VecDeque::from(vec![root]);
+    while let Some(v) = queue.pop_front() {
+        for w in sorted_neighbors(graph, &v) {
+            if visited.insert(w) {
+                nodes.push(w);
+                edges.push((v, w));
+                queue.push_back(w);
+            }
+        }
+    }
+    Ok(to_digraph(nodes, edges))
+}
+
+/// Returns the depth-first spanning tree of the nodes reachable from `root`,
+/// as a digraph with arcs from every node to its children. Adjacent nodes are
+/// visited in the order of their values.
+pub fn dfs_tree<T, G>(graph: &G, root: T) -> Result<Digraph<T>, TreeError<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    if !graph.contains(&root) {
+        return Err(TreeError::UnknownRoot(root));
+    }
+    let mut visited = HashSet::new();
+    visited.insert(root);
+    let mut nodes = vec![root];
+    let mut edges = vec![];
+    // nodes on the current path with the neighbors still to visit
+    let mut stack = vec![(root, sorted_neighbors(graph, &root).into_iter())];
+    while let Some((v, adjs)) = stack.last_mut() {
+        let v = *v;
+        match adjs.find(|w| !visited.contains(w)) {
+            Some(w) => {
+                visited.insert(w);
+                nodes.push(w);
+                edges.push((v, w));
+                stack.push((w, sorted_neighbors(graph, &w).into_iter()));
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+    Ok(to_digraph(nodes, edges))
+}
+
+fn sorted_neighbors<T, G>(graph: &G, v: &T) -> Vec<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: Neighbors<T>,
+{
+    let mut adjs = graph.neighbors(v).into_owned();
+    adjs.sort();
+    adjs
+}
+
+// the nodes in preorder and the (parent, child) edges of a multiway tree
+fn mtree_edges(tree: &MTree) -> (Vec<char>, Vec<(char, char)>) {
+    fn walk(tree: &MTree, nodes: &mut Vec<char>, edges: &mut Vec<(char, char)>) {
+        nodes.push(tree.get_value());
+        for child in tree.get_children() {
+            edges.push((tree.get_value(), child.get_value()));
+            walk(child, nodes, edges);
+        }
+    }
+    let (mut nodes, mut edges) = (vec![], vec![]);
+    walk(tree, &mut nodes, &mut edges);
+    (nodes, edges)
+}
+
+// the nodes in preorder and the (parent, child) edges of a binary tree
+fn bintree_edges<T: fmt::Display + Copy>(tree: &Tree<T>) -> (Vec<T>, Vec<(T, T)>) {
+    fn walk<T: fmt::Display + Copy>(tree: &Tree<T>, nodes: &mut Vec<T>, edges: &mut Vec<(T, T)>) {
+        if let Tree::Node { value, left, right } = tree {
+            nodes.push(*value);
+            for child in &[left, right] {
+                if let Some(w) = child.get_value() {
+                    edges.push((*value, *w));
+                }
+                walk(child, nodes, edges);
+            }
+        }
+    }
+    let (mut nodes, mut edges) = (vec![], vec![]);
+    walk(tree, &mut nodes, &mut edges);
+    (nodes, edges)
+}
+
+type Nodes<T> = Vec<T>;
+type Edges<T> = Vec<(T, T)>;
+
+fn check_values<T: Hash + Copy + Eq>(
+    (nodes, edges): (Nodes<T>, Edges<T>),
+) -> Result<(Nodes<T>, Edges<T>), TreeError<T>> {
+    let mut seen = HashSet::new();
+    for v in &nodes {
+        if !seen.insert(*v) {
+            return Err(TreeError::DuplicateValue(*v));
+        }
+    }
+    Ok((nodes, edges))
+}
+
+fn to_graph<T: Hash + Copy + Eq + Ord>(nodes: Nodes<T>, edges: Edges<T>) -> Graph<T> {
+    let mut g = Graph::new();
+    for v in nodes {
+        g.add_node(v);
+    }
+    for (v, w) in edges {
+        g.add_edge(v, w);
+    }
+    g
+}
+
+fn to_digraph<T: Hash + Copy + Eq>(nodes: Nodes<T>, edges: Edges<T>) -> Digraph<T> {
+    let mut g = Digraph::new();
+    for v in nodes {
+        g.add_node(v);
+    }
+    for (v, w) in edges {
+        g.add_arc(v, w);
+    }
+    g
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use P70::{str_to_tree, tree_to_str};
+    use P80::digraph_converters;
+    use P80::graph_converters::unlabeled;
+
+    #[test]
+    fn test_mtree_conversions() {
+        let tree = str_to_tree("afg^^c^bd^e^^^");
+        let g = mtree_to_digraph(&tree).unwrap();
+        let mut arcs = g.get_arcs();
+        arcs.sort();
+        assert_eq!(
+            arcs,
+            vec![
+                ('a', 'b', None),
+                ('a', 'c', None),
+                ('a', 'f', None),
+                ('b', 'd', None),
+                ('b', 'e', None),
+                ('f', 'g', None)
+            ]
+        );
+        assert!(is_tree(&g));
+        assert_eq!(graph_to_mtree(&g, 'a'), Ok(str_to_tree("abd^e^^c^fg^^^")));
+        assert_eq!(graph_to_mtree(&g, 'b'), Err(TreeError::NotARoot('b')));
+        let g = digraph_converters::unlabeled::from_string("[a>b, b>a, b>c]");
+        assert_eq!(graph_to_mtree(&g, 'b'), Err(TreeError::NotATree));
+
+        // an undirected tree can be rooted anywhere
+        let g = mtree_to_graph(&tree).unwrap();
+        let tree = graph_to_mtree(&g, 'b').unwrap();
+        assert_eq!(tree_to_str(&tree), "bac^fg^^^d^e^^");
+
+        let tree = str_to_tree("aba^^^");
+        assert_eq!(mtree_to_graph(&tree), Err(TreeError::DuplicateValue('a')));
+    }
+
+    #[test]
+    fn test_bintree_conversions() {
+        let tree = Tree::node(2, Tree::leaf(1), Tree::node(4, Tree::leaf(3), Tree::end()));
+        let g = bintree_to_digraph(&tree).unwrap();
+        assert_eq!(g.get_arcs(), vec![(2, 1, None), (2, 4, None), (4, 3, None)]);
+        let g = bintree_to_graph(&tree).unwrap();
+        assert_eq!(g.get_edges().len(), 3);
+        assert!(is_tree(&g));
+        assert_eq!(bintree_to_graph(&Tree::<u32>::end()).unwrap().size(), 0);
+    }
+
+    #[test]
+    fn test_spanning_trees() {
+        let g = unlabeled::from_string("[a-b, a-c, b-d, c-d, d-e, f]");
+        let bfs = bfs_tree(&g, 'a').unwrap();
+        assert_eq!(
+            tree_to_str(&graph_to_mtree(&bfs, 'a').unwrap()),
+            "abde^^^c^^"
+        );
+        let dfs = dfs_tree(&g, 'a').unwrap();
+        assert_eq!(
+            tree_to_str(&graph_to_mtree(&dfs, 'a').unwrap()),
+            "abdc^e^^^^"
+        );
+        assert_eq!(dfs.size(), 5);
+
+        assert_eq!(bfs_tree(&g, 'x').unwrap_err(), TreeError::UnknownRoot('x'));
+        assert_eq!(graph_to_mtree(&g, 'a').unwrap_err(), TreeError::NotATree);
+        assert_eq!(
+            graph_to_mtree(&g, 'x').unwrap_err().to_string(),
+            "unknown root: 'x'"
+        );
+    }
+}
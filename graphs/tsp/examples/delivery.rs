@@ -0,0 +1,38 @@
+use tsp::*;
+use P80::graph_converters::labeled;
+
+pub fn main() {
+    // a depot and eight stops on a city map; driving times are rounded
+    // straight-line distances
+    let stops = vec![
+        ('a', (0, 0)),
+        ('b', (2, 9)),
+        ('c', (7, 8)),
+        ('d', (12, 10)),
+        ('e', (14, 4)),
+        ('f', (9, 1)),
+        ('g', (5, 4)),
+        ('h', (11, 6)),
+        ('i', (3, 13)),
+    ];
+    let mut edges = vec![];
+    for (k, (v, (x1, y1))) in stops.iter().enumerate() {
+        for (w, (x2, y2)) in &stops[k + 1..] {
+            let d = f64::from((x1 - x2) * (x1 - x2) + (y1 - y2) * (y1 - y2)).sqrt();
+            edges.push((*v, *w, d.round() as i32));
+        }
+    }
+    let nodes: Vec<char> = stops.iter().map(|(v, _)| *v).collect();
+    let g = labeled::from_term_form(&nodes, &edges);
+
+    let nn = nearest_neighbor(&g, 'a').unwrap();
+    println!("nearest neighbor: {:?} (cost={})", nn.nodes, nn.cost);
+    let tour = christofides(&g).unwrap();
+    println!("christofides:     {:?} (cost={})", tour.nodes, tour.cost);
+    let tour = two_opt(&g, &nn);
+    println!("2-opt:            {:?} (cost={})", tour.nodes, tour.cost);
+    let tour = or_opt(&g, &nn);
+    println!("or-opt:           {:?} (cost={})", tour.nodes, tour.cost);
+    let tour = held_karp(&g).unwrap();
+    println!("held-karp:        {:?} (cost={})", tour.nodes, tour.cost);
+}
@@ -0,0 +1,487 @@
+use graph::EdgeWeights;
+use std::collections::BTreeSet;
+use std::hash::Hash;
+
+/// The largest number of nodes `held_karp` accepts; it needs memory and time
+/// exponential in the number of nodes.
+pub const HELD_KARP_MAX_NODES: usize = 16;
+
+// odd-degree nodes are matched exactly up to this number, greedily above it
+const EXACT_MATCHING_MAX_NODES: usize = 16;
+
+/// A closed tour through all nodes of a graph.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tour<T> {
+    /// Every node once, in visiting order. The tour starts at the smallest node
+    /// and returns to it after the last one; of both directions, the one with
+    /// the smaller second node is taken.
+    pub nodes: Vec<T>,
+    /// The sum of the labels of the edges along the tour, including the edge
+    /// back to the start.
+    pub cost: i32,
+}
+
+// the graph as a matrix of edge labels between node indices
+struct Distances<T> {
+    values: Vec<T>,
+    matrix: Vec<Vec<i32>>,
+}
+
+impl<T: Hash + Copy + Eq + Ord> Distances<T> {
+    fn new<G: EdgeWeights<T, Weight = i32>>(g: &G) -> Self {
+        let mut values = g.node_values().into_owned();
+        values.sort();
+        let n = values.len();
+        let mut matrix: Vec<Vec<Option<i32>>> = vec![vec![None; n]; n];
+        for (i, v) in values.iter().enumerate() {
+            matrix[i][i] = Some(0);
+            for (w, l) in g.neighbors_with_weight(v) {
+                let l = l.expect("edges must be labeled");
+                let j = values.binary_search(&w).unwrap();
+                // of parallel edges the lightest one is taken
+                if i != j {
+                    matrix[i][j] = Some(matrix[i][j].map_or(l, |m| m.min(l)));
+                }
+            }
+        }
+        let matrix = matrix
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|l| l.expect("the graph must be complete"))
+                    .collect()
+            })
+            .collect();
+        Distances { values, matrix }
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn cost(&self, order: &[usize]) -> i32 {
+        let n = order.len();
+        (0..n)
+            .map(|k| self.matrix[order[k]][order[(k + 1) % n]])
+            .sum()
+    }
+
+    fn indices(&self, tour: &Tour<T>) -> Vec<usize> {
+        assert_eq!(
+            tour.nodes.len(),
+            self.len(),
+            "the tour must visit every node once"
+        );
+        tour.nodes
+            .iter()
+            .map(|v| {
+                self.values
+                    .binary_search(v)
+                    .expect("the tour must visit nodes of the graph")
+            })
+            .collect()
+    }
+
+    fn to_tour(&self, mut order: Vec<usize>) -> Tour<T> {
+        // node indices follow the sorted order, so index 0 is the smallest node
+        let start = order.iter().position(|i| *i == 0).unwrap();
+        order.rotate_left(start);
+        if order.len() > 2 && order[1] > order[order.len() - 1] {
+            order[1..].reverse();
+        }
+        Tour {
+            nodes: order.iter().map(|i| self.values[*i]).collect(),
+            cost: self.cost(&order),
+        }
+    }
+}
+
+/// Returns the sum of the labels of the edges along a closed tour through
+/// `nodes`, including the edge from the last node back to the first one.
+///
+/// Panics if consecutive nodes are not adjacent or their edge is unlabeled.
+pub fn tour_cost<T, G>(g: &G, nodes: &[T]) -> i32
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let n = nodes.len();
+    (0..n)
+        .map(|k| {
+            let (v, w) = (&nodes[k], &nodes[(k + 1) % n]);
+            if v == w {
+                0
+            } else {
+                g.neighbors_with_weight(v)
+                    .into_iter()
+                    .filter(|(x, _)| x == w)
+                    .map(|(_, l)| l.expect("edges must be labeled"))
+                    .min()
+                    .expect("consecutive nodes must be adjacent")
+            }
+        })
+        .sum()
+}
+
+/// Builds a tour by starting at `start` and always going on to the nearest
+/// node not yet visited. `None` if `start` is not in the graph.
+///
+/// Edges must be labeled, and the graph complete (every pair of nodes
+/// adjacent); this holds for all functions in this crate.
+pub fn nearest_neighbor<T, G>(g: &G, start: T) -> Option<Tour<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let d = Distances::new(g);
+    let mut current = d.values.binary_search(&start).ok()?;
+    let mut visited = vec![false; d.len()];
+    visited[current] = true;
+    let mut order = vec![current];
+    while order.len() < d.len() {
+        current = (0..d.len())
+            .filter(|i| !visited[*i])
+            .min_by_key(|i| d.matrix[current][*i])
+            .unwrap();
+        visited[current] = true;
+        order.push(current);
+    }
+    Some(d.to_tour(order))
+}
+
+/// Builds a tour in the manner of Christofides' algorithm; `None` if the graph
+/// is empty.
+///
+/// A minimum spanning tree is extended by a perfect matching of its odd-degree
+/// nodes, and the resulting Eulerian circuit is shortcut at repeated nodes. The
+/// matching has minimum weight for up to 16 odd-degree nodes, so that the tour
+/// costs at most 1.5 times the optimum if the labels satisfy the triangle
+/// inequality; above that the matching is greedy and the bound may not hold.
+pub fn christofides<T, G>(g: &G) -> Option<Tour<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let d = Distances::new(g);
+    let n = d.len();
+    if n == 0 {
+        return None;
+    }
+    // Prim's algorithm
+    let mut adjs: Vec<Vec<usize>> = vec![vec![]; n];
+    let mut in_tree = vec![false; n];
+    let mut closest: Vec<(i32, usize)> = (0..n).map(|i| (d.matrix[0][i], 0)).collect();
+    in_tree[0] = true;
+    for _ in 1..n {
+        let i = (0..n)
+            .filter(|i| !in_tree[*i])
+            .min_by_key(|i| closest[*i])
+            .unwrap();
+        in_tree[i] = true;
+        let parent = closest[i].1;
+        adjs[i].push(parent);
+        adjs[parent].push(i);
+        for j in (0..n).filter(|j| !in_tree[*j]) {
+            if d.matrix[i][j] < closest[j].0 {
+                closest[j] = (d.matrix[i][j], i);
+            }
+        }
+    }
+
+    let odd: Vec<usize> = (0..n).filter(|i| adjs[*i].len() % 2 == 1).collect();
+    for (i, j) in perfect_matching(&d.matrix, &odd) {
+        adjs[i].push(j);
+        adjs[j].push(i);
+    }
+
+    // Hierholzer's algorithm; every edge is removed from both ends when used
+    let mut circuit = vec![];
+    let mut stack = vec![0];
+    while let Some(&i) = stack.last() {
+        match adjs[i].pop() {
+            Some(j) => {
+                let k = adjs[j].iter().position(|x| *x == i).unwrap();
+                adjs[j].swap_remove(k);
+                stack.push(j);
+            }
+            None => circuit.push(stack.pop().unwrap()),
+        }
+    }
+    let mut visited = vec![false; n];
+    let mut order = vec![];
+    for i in circuit {
+        if !visited[i] {
+            visited[i] = true;
+            order.push(i);
+        }
+    }
+    Some(d.to_tour(order))
+}
+
+// pairs the nodes (an even number of them); minimum weight for few nodes
+fn perfect_matching(matrix: &[Vec<i32>], nodes: &[usize]) -> Vec<(usize, usize)> {
+    let k = nodes.len();
+    let mut pairs = vec![];
+    if k <= EXACT_MATCHING_MAX_NODES {
+        // best[mask]: the cheapest matching of the nodes in mask, which always
+        // pairs the first of them with another one
+        let full = (1usize << k) - 1;
+        let mut best: Vec<Option<(i64, usize)>> = vec![None; full + 1];
+        best[0] = Some((0, 0));
+        for mask in 1..=full {
+            if mask.count_ones() % 2 == 1 {
+                continue;
+            }
+            let a = mask.trailing_zeros() as usize;
+            for b in a + 1..k {
+                if mask & (1 << b) == 0 {
+                    continue;
+                }
+                let rest = mask & !(1 << a) & !(1 << b);
+                if let Some((w, _)) = best[rest] {
+                    let w = w + i64::from(matrix[nodes[a]][nodes[b]]);
+                    match best[mask] {
+                        Some((m, _)) if m <= w => {}
+                        _ => best[mask] = Some((w, b)),
+                    }
+                }
+            }
+        }
+        let mut mask = full;
+        while mask != 0 {
+            let a = mask.trailing_zeros() as usize;
+            let b = best[mask].unwrap().1;
+            pairs.push((nodes[a], nodes[b]));
+            mask &= !(1 << a) & !(1 << b);
+        }
+    } else {
+        let mut candidates: Vec<(i32, usize, usize)> = nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(a, i)| nodes[a + 1..].iter().map(move |j| (*i, *j)))
+            .map(|(i, j)| (matrix[i][j], i, j))
+            .collect();
+        candidates.sort();
+        let mut matched = BTreeSet::new();
+        for (_, i, j) in candidates {
+            if !matched.contains(&i) && !matched.contains(&j) {
+                matched.insert(i);
+                matched.insert(j);
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+/// Improves a tour by 2-opt moves: while replacing two edges of the tour by
+/// two others (reversing the path between them) makes the tour cheaper, the
+/// first such move found is made.
+///
+/// Panics if `tour` does not visit every node of the graph exactly once.
+pub fn two_opt<T, G>(g: &G, tour: &Tour<T>) -> Tour<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let d = Distances::new(g);
+    let mut order = d.indices(tour);
+    let n = order.len();
+    let w = |i: usize, j: usize| d.matrix[i][j];
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for a in 0..n {
+            for b in a + 2..n {
+                // edges (order[a], order[a + 1]) and (order[b], order[b + 1])
+                let c = (b + 1) % n;
+                if c == a {
+                    continue;
+                }
+                let before = w(order[a], order[a + 1]) + w(order[b], order[c]);
+                let after = w(order[a], order[b]) + w(order[a + 1], order[c]);
+                if after < before {
+                    order[a + 1..=b].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+    d.to_tour(order)
+}
+
+/// Improves a tour by Or-opt moves: while moving a segment of one, two or
+/// three consecutive nodes (possibly reversed) to another place in the tour
+/// makes it cheaper, the first such move found is made.
+///
+/// Panics if `tour` does not visit every node of the graph exactly once.
+pub fn or_opt<T, G>(g: &G, tour: &Tour<T>) -> Tour<T>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let d = Distances::new(g);
+    let mut order = d.indices(tour);
+    let n = order.len();
+    let w = |i: usize, j: usize| d.matrix[i][j];
+    let mut improved = true;
+    while improved {
+        improved = false;
+        'search: for len in 1..=3 {
+            if len + 2 > n {
+                break;
+            }
+            for start in 0..n {
+                // take out the segment, rotated to the front
+                let mut rest = order.clone();
+                rest.rotate_left(start);
+                let segment: Vec<usize> = rest.drain(..len).collect();
+                let (first, last) = (segment[0], segment[len - 1]);
+                let (prev, next) = (rest[rest.len() - 1], rest[0]);
+                let removed = w(prev, first) + w(last, next) - w(prev, next);
+                // insert it between rest[k] and rest[k + 1]
+                for k in 0..rest.len() - 1 {
+                    let (x, y) = (rest[k], rest[k + 1]);
+                    let forward = w(x, first) + w(last, y) - w(x, y);
+                    let backward = w(x, last) + w(first, y) - w(x, y);
+                    if forward.min(backward) < removed {
+                        let mut segment = segment.clone();
+                        if backward < forward {
+                            segment.reverse();
+                        }
+                        rest.splice(k + 1..k + 1, segment);
+                        order = rest;
+                        improved = true;
+                        break 'search;
+                    }
+                }
+            }
+        }
+    }
+    d.to_tour(order)
+}
+
+/// Returns an optimal tour by the Held–Karp dynamic program over subsets of
+/// nodes; `None` if the graph is empty.
+///
+/// Panics if the graph has more than `HELD_KARP_MAX_NODES` nodes.
+pub fn held_karp<T, G>(g: &G) -> Option<Tour<T>>
+where
+    T: Hash + Copy + Eq + Ord,
+    G: EdgeWeights<T, Weight = i32>,
+{
+    let d = Distances::new(g);
+    let n = d.len();
+    assert!(
+        n <= HELD_KARP_MAX_NODES,
+        "too many nodes for the Held-Karp algorithm"
+    );
+    if n <= 2 {
+        return if n == 0 {
+            None
+        } else {
+            Some(d.to_tour((0..n).collect()))
+        };
+    }
+    // cost[mask][j]: the cheapest path from node 0 through the nodes 1..n in
+    // mask (bit j - 1 for node j), ending at node j
+    let m = n - 1;
+    let full = (1usize << m) - 1;
+    let mut cost: Vec<Vec<Option<(i64, usize)>>> = vec![vec![None; n]; full + 1];
+    for j in 1..n {
+        cost[1 << (j - 1)][j] = Some((i64::from(d.matrix[0][j]), 0));
+    }
+    for mask in 1..=full {
+        for j in 1..n {
+            let (c, _) = match cost[mask][j] {
+                Some(c) => c,
+                None => continue,
+            };
+            for (k, w) in d.matrix[j].iter().enumerate().skip(1) {
+                if mask & (1 << (k - 1)) != 0 {
+                    continue;
+                }
+                let next = mask | (1 << (k - 1));
+                let c = c + i64::from(*w);
+                match cost[next][k] {
+                    Some((best, _)) if best <= c => {}
+                    _ => cost[next][k] = Some((c, j)),
+                }
+            }
+        }
+    }
+    let mut last = (1..n)
+        .min_by_key(|j| cost[full][*j].unwrap().0 + i64::from(d.matrix[*j][0]))
+        .unwrap();
+    let mut mask = full;
+    let mut order = vec![];
+    while last != 0 {
+        order.push(last);
+        let prev = cost[mask][last].unwrap().1;
+        mask &= !(1 << (last - 1));
+        last = prev;
+    }
+    order.push(0);
+    order.reverse();
+    Some(d.to_tour(order))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::LabeledGraph;
+    use P80::graph_converters::labeled;
+
+    // five cities; the optimal tour a-b-c-d-e costs 22
+    fn example() -> LabeledGraph<char, i32> {
+        labeled::from_string(
+            "[a-b/3, a-c/4, a-d/8, a-e/5, b-c/5, b-d/9, b-e/8, c-d/4, c-e/7, d-e/5]",
+        )
+    }
+
+    #[test]
+    fn test_held_karp() {
+        let g = example();
+        let tour = held_karp(&g).unwrap();
+        assert_eq!(tour.nodes, vec!['a', 'b', 'c', 'd', 'e']);
+        assert_eq!(tour.cost, 22);
+        assert_eq!(tour_cost(&g, &tour.nodes), 22);
+
+        let g = labeled::from_string("[a-b/7]");
+        assert_eq!(held_karp(&g).unwrap().cost, 14);
+        let g = labeled::from_string("[a]");
+        assert_eq!(held_karp(&g).unwrap().cost, 0);
+        assert_eq!(held_karp(&LabeledGraph::<char, i32>::new()), None);
+    }
+
+    #[test]
+    fn test_constructions() {
+        let g = example();
+        let tour = nearest_neighbor(&g, 'e').unwrap();
+        assert_eq!(tour.nodes, vec!['a', 'b', 'c', 'd', 'e']);
+        assert_eq!(tour.cost, 22);
+        assert_eq!(nearest_neighbor(&g, 'x'), None);
+
+        // the spanning tree a-b, a-c, c-d, a-e has odd nodes b, d, e and a
+        let tour = christofides(&g).unwrap();
+        assert_eq!(tour.cost, tour_cost(&g, &tour.nodes));
+        assert!(f64::from(tour.cost) <= 1.5 * 22.0);
+    }
+
+    #[test]
+    fn test_improvements() {
+        let g = example();
+        let bad = Tour {
+            nodes: vec!['a', 'd', 'b', 'e', 'c'],
+            cost: 0,
+        };
+        assert_eq!(tour_cost(&g, &bad.nodes), 36);
+        let tour = two_opt(&g, &bad);
+        assert!(tour.cost < 36);
+        assert_eq!(tour.cost, tour_cost(&g, &tour.nodes));
+        let tour = or_opt(&g, &bad);
+        assert!(tour.cost < 36);
+        assert_eq!(tour.cost, tour_cost(&g, &tour.nodes));
+        assert_eq!(or_opt(&g, &two_opt(&g, &bad)).cost, 22);
+    }
+}